@@ -5,12 +5,12 @@ use netlink_packet_route::{
     constants::{AF_BRIDGE, RTEXT_FILTER_BRVLAN},
     link::nlas::Nla,
 };
-use rtnetlink::{new_connection, Error, Handle};
+use rtnetlink::{new_smol_connection, Error, Handle};
 
 #[async_std::main]
 async fn main() -> Result<(), ()> {
     env_logger::init();
-    let (connection, handle, _) = new_connection().unwrap();
+    let (connection, handle, _) = new_smol_connection().unwrap();
     async_std::task::spawn(connection);
 
     // Fetch a link by its index
@@ -46,7 +46,8 @@ async fn main() -> Result<(), String> {
         | nl_mgrp(RTNLGRP_NSID)
         | nl_mgrp(RTNLGRP_MPLS_NETCONF);
 
-    let addr = SocketAddr::new(0, groups);
+    // The socket is already bound to its port id, which must be kept.
+    let addr = SocketAddr::new(_handle.port_id().unwrap(), groups);
     conn.socket_mut()
         .socket_mut()
         .bind(&addr)
@@ -13,15 +13,16 @@ use rtnetlink::{
 #[tokio::main]
 async fn main() -> Result<(), String> {
     // Open the netlink socket
-    let (mut connection, _, mut messages) =
+    let (mut connection, handle, mut messages) =
         new_connection().map_err(|e| format!("{e}"))?;
 
     // These flags specify what kinds of broadcast messages we want to listen
     // for.
     let mgroup_flags = RTMGRP_IPV4_ROUTE | RTMGRP_IPV6_ROUTE;
 
-    // A netlink socket address is created with said flags.
-    let addr = SocketAddr::new(0, mgroup_flags);
+    // A netlink socket address is created with said flags. The socket is
+    // already bound to its port id, which must be kept.
+    let addr = SocketAddr::new(handle.port_id().unwrap(), mgroup_flags);
     // Said address is bound so new conenctions and thus new message broadcasts
     // can be received.
     connection
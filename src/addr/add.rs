@@ -1,6 +1,6 @@
 // SPDX-License-Identifier: MIT
 
-use futures::stream::StreamExt;
+use futures::stream::{StreamExt, TryStreamExt};
 use std::net::{IpAddr, Ipv4Addr};
 
 use netlink_packet_core::{
@@ -12,7 +12,7 @@ use netlink_packet_route::{
     nlas::address::Nla, AddressMessage, RtnlMessage, AF_INET, AF_INET6,
 };
 
-use crate::{try_nl, Error, Handle};
+use crate::{try_nl, AddressGetRequest, Error, Handle};
 
 /// A request to create a new address. This is equivalent to the `ip address
 /// add` commands.
@@ -20,6 +20,7 @@ pub struct AddressAddRequest {
     handle: Handle,
     message: AddressMessage,
     replace: bool,
+    ensure: bool,
 }
 
 impl AddressAddRequest {
@@ -70,7 +71,10 @@ impl AddressAddRequest {
                     address_vec[3],
                 ));
                 let brd = Ipv4Addr::from(
-                    (0xffff_ffff_u32) >> u32::from(prefix_len) | ip_addr,
+                    (0xffff_ffff_u32)
+                        .checked_shr(u32::from(prefix_len))
+                        .unwrap_or(0)
+                        | ip_addr,
                 );
                 message.nlas.push(Nla::Broadcast(brd.octets().to_vec()));
             };
@@ -79,10 +83,20 @@ impl AddressAddRequest {
             handle,
             message,
             replace: false,
+            ensure: false,
         }
     }
 
-    /// Replace existing matching address.
+    pub(crate) fn ensure(self) -> Self {
+        Self {
+            ensure: true,
+            ..self
+        }
+    }
+
+    /// Replace existing matching address. With
+    /// [`crate::AddressHandle::ensure`], the address is only replaced if it
+    /// differs from the requested one.
     pub fn replace(self) -> Self {
         Self {
             replace: true,
@@ -90,22 +104,53 @@ impl AddressAddRequest {
         }
     }
 
-    /// Execute the request.
+    /// Execute the request. An [`Error::InvalidRequest`] is returned if the
+    /// prefix length is larger than the address.
     pub async fn execute(self) -> Result<(), Error> {
+        let max_prefix_len = match self.message.header.family as u16 {
+            AF_INET => 32,
+            _ => 128,
+        };
+        if self.message.header.prefix_len > max_prefix_len {
+            return Err(Error::InvalidRequest(format!(
+                "prefix length {} is out of range",
+                self.message.header.prefix_len
+            )));
+        }
         let AddressAddRequest {
             mut handle,
             message,
             replace,
+            ensure,
         } = self;
-        let mut req = NetlinkMessage::from(RtnlMessage::NewAddress(message));
-        let replace = if replace { NLM_F_REPLACE } else { NLM_F_EXCL };
-        req.header.flags = NLM_F_REQUEST | NLM_F_ACK | replace | NLM_F_CREATE;
+        if !ensure {
+            let flags = if replace { NLM_F_REPLACE } else { NLM_F_EXCL };
+            return add(&mut handle, message, flags).await;
+        }
 
-        let mut response = handle.request(req)?;
-        while let Some(message) = response.next().await {
-            try_nl!(message);
+        // The kernel reports EEXIST as soon as the link has the address,
+        // whatever its other attributes (e.g. its label or broadcast
+        // address): compare the existing addresses with the requested one.
+        match add(&mut handle, message.clone(), NLM_F_EXCL).await {
+            Err(e) if e.is_already_exists() => {
+                let addresses: Vec<_> = AddressGetRequest::new(handle.clone())
+                    .set_link_index_filter(message.header.index)
+                    .execute()
+                    .try_collect()
+                    .await?;
+                if addresses
+                    .iter()
+                    .any(|address| is_same_address(&message, address))
+                {
+                    Ok(())
+                } else if replace {
+                    add(&mut handle, message, NLM_F_REPLACE).await
+                } else {
+                    Err(e)
+                }
+            }
+            result => result,
         }
-        Ok(())
     }
 
     /// Return a mutable reference to the request message.
@@ -113,3 +158,31 @@ impl AddressAddRequest {
         &mut self.message
     }
 }
+
+async fn add(
+    handle: &mut Handle,
+    message: AddressMessage,
+    flags: u16,
+) -> Result<(), Error> {
+    let mut req = NetlinkMessage::from(RtnlMessage::NewAddress(message));
+    req.header.flags = NLM_F_REQUEST | NLM_F_ACK | NLM_F_CREATE | flags;
+    let mut response = handle.try_request(req)?;
+    while let Some(message) = response.next().await {
+        try_nl!(message?);
+    }
+    Ok(())
+}
+
+// Whether `existing` is the address that `requested` would create: the
+// attributes of the request must all be set on the existing address, which
+// also has the ones the kernel adds (e.g. the flags or the cache info).
+fn is_same_address(
+    requested: &AddressMessage,
+    existing: &AddressMessage,
+) -> bool {
+    let (a, b) = (&requested.header, &existing.header);
+    a.family == b.family
+        && a.prefix_len == b.prefix_len
+        && a.index == b.index
+        && requested.nlas.iter().all(|nla| existing.nlas.contains(nla))
+}
@@ -25,9 +25,9 @@ impl AddressDelRequest {
 
         let mut req = NetlinkMessage::from(RtnlMessage::DelAddress(message));
         req.header.flags = NLM_F_REQUEST | NLM_F_ACK;
-        let mut response = handle.request(req)?;
+        let mut response = handle.try_request(req)?;
         while let Some(msg) = response.next().await {
-            try_nl!(msg);
+            try_nl!(msg?);
         }
         Ok(())
     }
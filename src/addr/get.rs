@@ -10,7 +10,7 @@ use std::net::IpAddr;
 use netlink_packet_core::{NetlinkMessage, NLM_F_DUMP, NLM_F_REQUEST};
 use netlink_packet_route::{nlas::address::Nla, AddressMessage, RtnlMessage};
 
-use crate::{try_rtnl, Error, Handle};
+use crate::{collect::collect, try_rtnl, CollectOptions, Error, Handle};
 
 pub struct AddressGetRequest {
     handle: Handle,
@@ -31,6 +31,15 @@ impl AddressGetRequest {
         &mut self.message
     }
 
+    /// Execute the request, gathering the results into a `Vec` according
+    /// to `options`, for dumps small enough not to need a stream.
+    pub async fn execute_collect(
+        self,
+        options: CollectOptions,
+    ) -> Result<Vec<AddressMessage>, Error> {
+        collect(self.execute(), options).await
+    }
+
     pub fn execute(self) -> impl TryStream<Ok = AddressMessage, Error = Error> {
         let AddressGetRequest {
             mut handle,
@@ -42,10 +51,12 @@ impl AddressGetRequest {
         req.header.flags = NLM_F_REQUEST | NLM_F_DUMP;
 
         let filter = filter_builder.build();
-        match handle.request(req) {
+        match handle.try_request(req) {
             Ok(response) => Either::Left(
                 response
-                    .map(move |msg| Ok(try_rtnl!(msg, RtnlMessage::NewAddress)))
+                    .map(move |msg| {
+                        Ok(try_rtnl!(msg?, RtnlMessage::NewAddress))
+                    })
                     .try_filter(move |msg| future::ready(filter(msg))),
             ),
             Err(e) => Either::Right(
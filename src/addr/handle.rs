@@ -2,11 +2,12 @@
 
 use std::net::IpAddr;
 
-use super::{AddressAddRequest, AddressDelRequest, AddressGetRequest};
-use crate::Handle;
-
+use futures::stream::TryStreamExt;
 use netlink_packet_route::AddressMessage;
 
+use super::{AddressAddRequest, AddressDelRequest, AddressGetRequest};
+use crate::{Address, Error, Handle};
+
 pub struct AddressHandle(Handle);
 
 impl AddressHandle {
@@ -19,6 +20,28 @@ impl AddressHandle {
         AddressGetRequest::new(self.0.clone())
     }
 
+    /// Retrieve the assignments of `address` to the links, whatever their
+    /// prefix length
+    pub async fn find(&self, address: IpAddr) -> Result<Vec<Address>, Error> {
+        let messages: Vec<AddressMessage> = self
+            .get()
+            .set_address_filter(address)
+            .execute()
+            .try_collect()
+            .await?;
+        Ok(messages.iter().map(Address::from).collect())
+    }
+
+    /// Check that `address` is not assigned to any link yet, to fail early
+    /// with a useful error before adding it. Fails with
+    /// [`Error::AddressInUse`] if it is, naming the link it is assigned to.
+    pub async fn check_unused(&self, address: IpAddr) -> Result<(), Error> {
+        match self.find(address).await?.first() {
+            Some(existing) => Err(Error::AddressInUse(address, existing.index)),
+            None => Ok(()),
+        }
+    }
+
     /// Add an ip address on an interface (equivalent to `ip addr add`)
     pub fn add(
         &self,
@@ -29,8 +52,84 @@ impl AddressHandle {
         AddressAddRequest::new(self.0.clone(), index, address, prefix_len)
     }
 
+    /// Same as [`AddressHandle::add`], but succeeds if the same address
+    /// already exists on the interface. The address with other attributes
+    /// (e.g. another label) is a conflict, reported as `EEXIST`, unless
+    /// [`AddressAddRequest::replace`] is used to replace it.
+    pub fn ensure(
+        &self,
+        index: u32,
+        address: IpAddr,
+        prefix_len: u8,
+    ) -> AddressAddRequest {
+        self.add(index, address, prefix_len).ensure()
+    }
+
     /// Delete the given address
     pub fn del(&self, address: AddressMessage) -> AddressDelRequest {
         AddressDelRequest::new(self.0.clone(), address)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::net::Ipv4Addr;
+
+    use tokio::runtime::Runtime;
+
+    use netlink_packet_route::nlas::address::Nla;
+
+    use super::*;
+    use crate::MockNetwork;
+
+    #[test]
+    fn test_check_unused() {
+        let rt = Runtime::new().unwrap();
+        rt.block_on(async {
+            let network = MockNetwork::new();
+            let handle = network.handle().address();
+            let address = IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1));
+            handle.check_unused(address).await.unwrap();
+
+            handle.add(1, address, 24).execute().await.unwrap();
+            let found = handle.find(address).await.unwrap();
+            assert_eq!(found.len(), 1);
+            assert_eq!((found[0].index, found[0].prefix_len), (1, 24));
+            let err = handle.check_unused(address).await.unwrap_err();
+            assert_eq!(err, Error::AddressInUse(address, 1));
+            assert!(err.is_already_exists());
+            handle
+                .check_unused(Ipv4Addr::new(192, 0, 2, 2).into())
+                .await
+                .unwrap();
+        });
+    }
+
+    #[test]
+    fn test_ensure_address() {
+        let rt = Runtime::new().unwrap();
+        rt.block_on(async {
+            let network = MockNetwork::new();
+            let handle = network.handle().address();
+            let address = IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1));
+            let ensure = |label: &str| {
+                let mut request = handle.ensure(1, address, 24);
+                request.message_mut().nlas.push(Nla::Label(label.into()));
+                request
+            };
+
+            ensure("lo").execute().await.unwrap();
+            // The same address already exists
+            ensure("lo").execute().await.unwrap();
+            // The address with another label
+            let err = ensure("lo:1").execute().await.unwrap_err();
+            assert!(err.is_already_exists());
+            assert_eq!(network.addresses()[0].label.as_deref(), Some("lo"));
+
+            ensure("lo:1").replace().execute().await.unwrap();
+            let addresses = network.addresses();
+            assert_eq!(addresses.len(), 1);
+            assert_eq!(addresses[0].label.as_deref(), Some("lo:1"));
+        });
+    }
+}
@@ -0,0 +1,72 @@
+// SPDX-License-Identifier: MIT
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use netlink_packet_route::{address::Nla, AddressMessage};
+
+/// The most commonly used properties of an IP address, extracted from an
+/// [`AddressMessage`] (for instance one returned by
+/// [`crate::AddressGetRequest::execute`]).
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Address {
+    /// Index of the link the address is assigned to
+    pub index: u32,
+    /// Address of the interface. For point-to-point links, this is the
+    /// address of the peer, and `local` is the local address.
+    pub address: Option<IpAddr>,
+    pub local: Option<IpAddr>,
+    pub prefix_len: u8,
+    pub scope: u8,
+    pub label: Option<String>,
+    pub broadcast: Option<IpAddr>,
+}
+
+impl From<&AddressMessage> for Address {
+    fn from(message: &AddressMessage) -> Self {
+        let mut address = Address {
+            index: message.header.index,
+            address: None,
+            local: None,
+            prefix_len: message.header.prefix_len,
+            scope: message.header.scope,
+            label: None,
+            broadcast: None,
+        };
+        for nla in &message.nlas {
+            match nla {
+                Nla::Address(bytes) => address.address = parse_ip_addr(bytes),
+                Nla::Local(bytes) => address.local = parse_ip_addr(bytes),
+                Nla::Broadcast(bytes) => {
+                    address.broadcast = parse_ip_addr(bytes)
+                }
+                Nla::Label(label) => address.label = Some(label.clone()),
+                _ => (),
+            }
+        }
+        address
+    }
+}
+
+impl From<AddressMessage> for Address {
+    fn from(message: AddressMessage) -> Self {
+        Address::from(&message)
+    }
+}
+
+/// Parse an IPv4 or IPv6 address attribute
+pub(crate) fn parse_ip_addr(bytes: &[u8]) -> Option<IpAddr> {
+    match bytes.len() {
+        4 => {
+            let mut octets = [0; 4];
+            octets.copy_from_slice(bytes);
+            Some(Ipv4Addr::from(octets).into())
+        }
+        16 => {
+            let mut octets = [0; 16];
+            octets.copy_from_slice(bytes);
+            Some(Ipv6Addr::from(octets).into())
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,328 @@
+// SPDX-License-Identifier: MIT
+
+use std::io;
+
+use futures::{stream, StreamExt};
+
+use netlink_packet_core::{
+    NetlinkMessage, NetlinkPayload, NLMSG_DONE, NLMSG_ERROR, NLM_F_ACK,
+    NLM_F_REQUEST,
+};
+use netlink_packet_route::RtnlMessage;
+use netlink_sys::{AsyncSocket, AsyncSocketExt, SocketAddr};
+
+use crate::{
+    handle::error_code,
+    raw::{dump_result, new_socket, split_messages},
+    try_nl, Error, Handle,
+};
+
+// Limits of the requests sent in one sendmsg() call, see chunk_end()
+const CHUNK_SIZE: usize = 32 * 1024;
+const CHUNK_LEN: usize = 64;
+
+/// A batch of requests sent to the kernel with as few `sendmsg()` calls as
+/// possible, which is much faster than sending the requests one by one when
+/// programming large tables (e.g. thousands of routes). Large batches are
+/// split into chunks of up to 64 requests.
+///
+/// The kernel processes the requests in order, and keeps processing them
+/// when one of them fails: [`Batch::execute`] returns the result of each
+/// request. The batch uses its own netlink socket, so it doesn't need a
+/// [`crate::Handle`].
+///
+/// ```no_run
+/// use netlink_packet_core::{NLM_F_CREATE, NLM_F_EXCL};
+/// use netlink_packet_route::{RouteMessage, RtnlMessage};
+/// use rtnetlink::Batch;
+///
+/// async fn add_routes(routes: Vec<RouteMessage>) -> std::io::Result<()> {
+///     let mut batch = Batch::new();
+///     for route in routes {
+///         batch.add(RtnlMessage::NewRoute(route), NLM_F_CREATE | NLM_F_EXCL);
+///     }
+///     for result in batch.execute().await? {
+///         if let Err(e) = result {
+///             eprintln!("{}", e);
+///         }
+///     }
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct Batch {
+    messages: Vec<NetlinkMessage<RtnlMessage>>,
+}
+
+impl Batch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a request to the batch. `NLM_F_REQUEST` and `NLM_F_ACK` are
+    /// always added to `flags`. A dump request (`NLM_F_DUMP`) succeeds once
+    /// the dump is complete, and its results are discarded.
+    pub fn add(&mut self, message: RtnlMessage, flags: u16) -> &mut Self {
+        let mut message = NetlinkMessage::from(message);
+        message.header.flags = flags | NLM_F_REQUEST | NLM_F_ACK;
+        self.messages.push(message);
+        self
+    }
+
+    /// Number of requests in the batch
+    pub fn len(&self) -> usize {
+        self.messages.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+
+    /// Send the batch and wait for the kernel to acknowledge every request.
+    /// The results are in the same order as the requests.
+    #[cfg(feature = "tokio_socket")]
+    pub async fn execute(self) -> io::Result<Vec<Result<(), Error>>> {
+        self.execute_with_socket::<netlink_sys::TokioSocket>().await
+    }
+
+    /// Same as [`Batch::execute`], using the socket type `S` (for instance
+    /// `SmolSocket`).
+    pub async fn execute_with_socket<S>(
+        self,
+    ) -> io::Result<Vec<Result<(), Error>>>
+    where
+        S: AsyncSocket,
+    {
+        let mut results: Vec<Option<Result<(), Error>>> =
+            vec![None; self.messages.len()];
+        if results.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let socket = new_socket::<S>()?;
+        let messages: Vec<Vec<u8>> = self
+            .messages
+            .into_iter()
+            .enumerate()
+            .map(|(i, mut message)| {
+                message.header.sequence_number = i as u32 + 1;
+                message.finalize();
+                let mut buf = vec![0; message.buffer_len()];
+                message.serialize(&mut buf);
+                buf
+            })
+            .collect();
+
+        let mut start = 0;
+        while start < messages.len() {
+            let end = chunk_end(&messages, start);
+            socket
+                .send_to(&messages[start..end].concat(), &SocketAddr::new(0, 0))
+                .await?;
+            receive_acks(&socket, start, &mut results[start..end]).await?;
+            start = end;
+        }
+        Ok(results.into_iter().map(Option::unwrap).collect())
+    }
+}
+
+/// The end of the chunk of `messages` sent in one `sendmsg()` call from
+/// `start`. The chunks are kept below [`CHUNK_SIZE`] bytes, since a
+/// datagram larger than the send buffer fails with `EMSGSIZE`, and below
+/// [`CHUNK_LEN`] messages, since the kernel drops the acknowledgements
+/// that overrun the receive buffer.
+fn chunk_end(messages: &[Vec<u8>], start: usize) -> usize {
+    let mut size = messages[start].len();
+    let mut end = start + 1;
+    while end < messages.len()
+        && end - start < CHUNK_LEN
+        && size + messages[end].len() <= CHUNK_SIZE
+    {
+        size += messages[end].len();
+        end += 1;
+    }
+    end
+}
+
+/// Wait for the acknowledgements of the requests `first..first +
+/// results.len()` of the batch
+async fn receive_acks<S>(
+    socket: &S,
+    first: usize,
+    results: &mut [Option<Result<(), Error>>],
+) -> io::Result<()>
+where
+    S: AsyncSocket,
+{
+    let mut pending = results.len();
+    while pending > 0 {
+        let (buf, _) = socket.recv_from_full().await?;
+        let messages =
+            split_messages(&buf).map_err(|e| invalid_data(e.to_string()))?;
+        for message in messages {
+            let done = match message.message_type() {
+                NLMSG_ERROR => false,
+                // A dump request is not acknowledged, it ends with
+                // NLMSG_DONE instead
+                NLMSG_DONE => true,
+                _ => continue,
+            };
+            let index = (message.sequence_number() as usize)
+                .checked_sub(first + 1)
+                .filter(|index| *index < results.len());
+            let result = match index {
+                Some(index) if results[index].is_none() => &mut results[index],
+                _ => continue,
+            };
+            if done {
+                *result = Some(dump_result(&message));
+                pending -= 1;
+                continue;
+            }
+            let message = NetlinkMessage::<RtnlMessage>::deserialize(
+                message.into_inner(),
+            )
+            .map_err(|e| invalid_data(e.to_string()))?;
+            *result = Some(match message.payload {
+                NetlinkPayload::Error(err) if error_code(&err) != 0 => {
+                    Err(Error::netlink(message.header.flags, err))
+                }
+                _ => Ok(()),
+            });
+            pending -= 1;
+        }
+    }
+    Ok(())
+}
+
+impl Handle {
+    /// Send the requests of `batch` through the connection of this handle,
+    /// keeping up to `parallelism` of them waiting for their
+    /// acknowledgement at a time, and return the result of each request, in
+    /// the same order as the requests.
+    ///
+    /// Unlike [`Batch::execute`], each request is a separate message of the
+    /// connection, so that the requests are subject to the timeout, the
+    /// retry policy and the in-flight limit of the handle, and to the
+    /// dry-run or mock mode. The requests are independent: they may be
+    /// processed in any order, and a failed request doesn't stop the others.
+    pub async fn execute_all(
+        &self,
+        batch: Batch,
+        parallelism: usize,
+    ) -> Vec<Result<(), Error>> {
+        stream::iter(batch.messages)
+            .map(|message| acknowledge(self.clone(), message))
+            .buffered(parallelism.max(1))
+            .collect()
+            .await
+    }
+}
+
+async fn acknowledge(
+    mut handle: Handle,
+    message: NetlinkMessage<RtnlMessage>,
+) -> Result<(), Error> {
+    let mut response = handle.try_request(message)?;
+    while let Some(message) = response.next().await {
+        try_nl!(message?);
+    }
+    Ok(())
+}
+
+fn invalid_data(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+#[cfg(test)]
+mod test {
+    use netlink_packet_core::{NLM_F_CREATE, NLM_F_DUMP, NLM_F_EXCL};
+    use netlink_packet_route::{link::nlas::Nla, LinkMessage};
+    use nix::libc;
+    use tokio::runtime::Runtime;
+
+    use super::*;
+    use crate::MockNetwork;
+
+    fn new_link(name: &str) -> RtnlMessage {
+        let mut link = LinkMessage::default();
+        link.nlas.push(Nla::IfName(name.into()));
+        RtnlMessage::NewLink(link)
+    }
+
+    #[test]
+    fn test_chunk_end() {
+        let small = vec![vec![0; 32]; 100];
+        assert_eq!(chunk_end(&small, 0), CHUNK_LEN);
+        assert_eq!(chunk_end(&small, 96), 100);
+
+        let large = vec![vec![0; CHUNK_SIZE / 2]; 3];
+        assert_eq!(chunk_end(&large, 0), 2);
+        let huge = vec![vec![0; CHUNK_SIZE + 1]; 2];
+        assert_eq!(chunk_end(&huge, 0), 1);
+    }
+
+    #[test]
+    #[cfg_attr(not(feature = "test_as_root"), ignore)]
+    fn test_execute_in_chunks() {
+        let rt = Runtime::new().unwrap();
+        rt.block_on(async {
+            // More requests (and acknowledgements) than fit in one datagram
+            let mut batch = Batch::new();
+            for _ in 0..1000 {
+                let mut link = LinkMessage::default();
+                link.header.index = 0x7fff_fff0;
+                batch.add(RtnlMessage::DelLink(link), 0);
+            }
+            let results = batch.execute().await.unwrap();
+            assert_eq!(results.len(), 1000);
+            for result in results {
+                assert_eq!(result.unwrap_err().errno(), Some(libc::ENODEV));
+            }
+        });
+    }
+
+    #[test]
+    #[cfg_attr(not(feature = "test_as_root"), ignore)]
+    fn test_execute_dump() {
+        let rt = Runtime::new().unwrap();
+        rt.block_on(async {
+            let mut link = LinkMessage::default();
+            link.header.index = 0x7fff_fff0;
+            let mut batch = Batch::new();
+            batch
+                .add(RtnlMessage::GetLink(LinkMessage::default()), NLM_F_DUMP)
+                .add(RtnlMessage::DelLink(link), 0);
+            let results = batch.execute().await.unwrap();
+            assert_eq!(results.len(), 2);
+            assert!(results[0].is_ok());
+            assert_eq!(
+                results[1].as_ref().unwrap_err().errno(),
+                Some(libc::ENODEV)
+            );
+        });
+    }
+
+    #[test]
+    fn test_execute_all() {
+        let rt = Runtime::new().unwrap();
+        rt.block_on(async {
+            let network = MockNetwork::new();
+            let handle = network.handle();
+
+            let mut batch = Batch::new();
+            for name in ["dummy0", "dummy1", "dummy0", "dummy2"] {
+                batch.add(new_link(name), NLM_F_CREATE | NLM_F_EXCL);
+            }
+            let results = handle.execute_all(batch, 2).await;
+            assert_eq!(results.len(), 4);
+            assert!(results[0].is_ok());
+            assert!(results[1].is_ok());
+            assert_eq!(
+                results[2].as_ref().unwrap_err().errno(),
+                Some(libc::EEXIST)
+            );
+            assert!(results[3].is_ok());
+        });
+    }
+}
@@ -0,0 +1,123 @@
+// SPDX-License-Identifier: MIT
+
+//! A blocking API, for programs that do not want to deal with an async
+//! runtime. The requests are the same as with the async API, but are run on a
+//! private single threaded tokio runtime.
+//!
+//! ```no_run
+//! use rtnetlink::blocking::Handle;
+//!
+//! let handle = Handle::new().unwrap();
+//! let links = handle.collect(handle.link().get().execute()).unwrap();
+//! for link in links {
+//!     handle.block_on(handle.link().set(link.header.index).up().execute())
+//!         .unwrap();
+//! }
+//! ```
+
+use std::{future::Future, io};
+
+use futures::{TryStream, TryStreamExt};
+use tokio::runtime::{Builder, Runtime};
+
+use crate::{
+    new_connection_with_options, AddressHandle, ConnectionOptions, LinkHandle,
+    NeighbourHandle, QDiscHandle, RouteHandle, RuleHandle,
+};
+
+/// A blocking equivalent of [`crate::Handle`]. It owns the netlink
+/// connection and the runtime it runs on.
+pub struct Handle {
+    runtime: Runtime,
+    handle: crate::Handle,
+}
+
+impl Handle {
+    /// Open a new netlink connection.
+    pub fn new() -> io::Result<Self> {
+        Self::with_options(ConnectionOptions::default())
+    }
+
+    /// Open a new netlink connection, with the given socket options.
+    pub fn with_options(options: ConnectionOptions) -> io::Result<Self> {
+        let runtime = Builder::new_current_thread().enable_all().build()?;
+        // the socket must be registered in the runtime it is polled in.
+        let (connection, handle, _) =
+            runtime.block_on(async { new_connection_with_options(options) })?;
+        runtime.spawn(connection);
+        Ok(Handle { runtime, handle })
+    }
+
+    /// Run a request, for instance `handle.block_on(request.execute())`,
+    /// until it completes.
+    pub fn block_on<F: Future>(&self, future: F) -> F::Output {
+        self.runtime.block_on(future)
+    }
+
+    /// Run a dump request, for instance
+    /// `handle.collect(handle.link().get().execute())`, and collect all the
+    /// messages it returns.
+    pub fn collect<S>(&self, stream: S) -> Result<Vec<S::Ok>, S::Error>
+    where
+        S: TryStream,
+    {
+        self.runtime.block_on(stream.try_collect())
+    }
+
+    /// Return the underlying async handle.
+    pub fn handle(&self) -> crate::Handle {
+        self.handle.clone()
+    }
+
+    /// Create a new handle for link requests (equivalent to `ip link`
+    /// commands)
+    pub fn link(&self) -> LinkHandle {
+        self.handle.link()
+    }
+
+    /// Create a new handle for address requests (equivalent to `ip addr`
+    /// commands)
+    pub fn address(&self) -> AddressHandle {
+        self.handle.address()
+    }
+
+    /// Create a new handle for routing table requests (equivalent to `ip
+    /// route` commands)
+    pub fn route(&self) -> RouteHandle {
+        self.handle.route()
+    }
+
+    /// Create a new handle for routing rule requests (equivalent to `ip rule`
+    /// commands)
+    pub fn rule(&self) -> RuleHandle {
+        self.handle.rule()
+    }
+
+    /// Create a new handle for neighbours requests (equivalent to `ip
+    /// neighbour` commands)
+    pub fn neighbours(&self) -> NeighbourHandle {
+        self.handle.neighbours()
+    }
+
+    /// Create a new handle for traffic control qdisc requests (equivalent to
+    /// `tc qdisc` commands)
+    pub fn qdisc(&self) -> QDiscHandle {
+        self.handle.qdisc()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use netlink_packet_route::link::nlas::Nla;
+
+    use super::*;
+
+    #[test]
+    fn test_dump_links() {
+        let handle = Handle::new().unwrap();
+        let links = handle.collect(handle.link().get().execute()).unwrap();
+        assert!(links
+            .iter()
+            .any(|link| link.nlas.contains(&Nla::IfName("lo".into()))));
+    }
+}
@@ -0,0 +1,322 @@
+// SPDX-License-Identifier: MIT
+
+use std::{
+    collections::HashMap,
+    fmt::{self, Display},
+    net::IpAddr,
+};
+
+use netlink_packet_core::{NetlinkMessage, NetlinkPayload};
+use netlink_packet_route::{RtnlMessage, AF_BRIDGE};
+
+use crate::{kind_name, Address, Link};
+
+/// A change of a field of a [`Link`] or an [`Address`], displayed as
+/// `mtu 1500→9000`. Absent values are shown as `none`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct FieldChange {
+    pub field: &'static str,
+    pub old: String,
+    pub new: String,
+}
+
+impl Display for FieldChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}→{}", self.field, self.old, self.new)
+    }
+}
+
+/// What happened to an object, see [`ChangeTracker`]
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum ObjectChange<T> {
+    Added(T),
+    /// The object was updated. `changes` lists the fields which differ
+    /// between `old` and `new`: it is empty when the kernel notified a change
+    /// of an attribute the view does not represent (or of the counters of a
+    /// link, which are not compared).
+    Changed {
+        old: T,
+        new: T,
+        changes: Vec<FieldChange>,
+    },
+    /// The object was removed. This is the last known state of the object.
+    Removed(T),
+}
+
+/// An event of a [`ChangeTracker`]
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum ChangeEvent {
+    Link(ObjectChange<Link>),
+    Address(ObjectChange<Address>),
+}
+
+/// Pairs the link and address events with the previous state of the
+/// objects, so that consumers can react to specific changes (e.g. `operstate
+/// down→up`) without maintaining their own copy of the objects.
+///
+/// The tracker only knows the objects it got an event for: it can be filled
+/// with the result of a dump, fed as `RTM_NEWLINK` / `RTM_NEWADDR` messages,
+/// before processing the events.
+///
+/// ```no_run
+/// use futures::StreamExt;
+/// use rtnetlink::{
+///     constants::{RTMGRP_IPV4_IFADDR, RTMGRP_LINK},
+///     new_supervised_monitor, ChangeEvent, ChangeTracker, MonitorEvent,
+///     ObjectChange,
+/// };
+///
+/// async fn print_changes() {
+///     let mut tracker = ChangeTracker::new();
+///     let mut events =
+///         new_supervised_monitor(RTMGRP_LINK | RTMGRP_IPV4_IFADDR);
+///     while let Some(event) = events.next().await {
+///         let message = match event {
+///             MonitorEvent::Message(message) => message,
+///             MonitorEvent::Reconnected => {
+///                 tracker.clear();
+///                 continue;
+///             }
+///         };
+///         if let Some(ChangeEvent::Link(ObjectChange::Changed {
+///             new,
+///             changes,
+///             ..
+///         })) = tracker.update(&message)
+///         {
+///             for change in changes {
+///                 println!("{}: {}", new.name, change);
+///             }
+///         }
+///     }
+/// }
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct ChangeTracker {
+    links: HashMap<u32, Link>,
+    addresses: HashMap<AddressKey, Address>,
+}
+
+// An address is identified by its link, its local address and its prefix
+type AddressKey = (u32, Option<IpAddr>, u8);
+
+fn address_key(address: &Address) -> AddressKey {
+    (
+        address.index,
+        address.local.or(address.address),
+        address.prefix_len,
+    )
+}
+
+impl ChangeTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Update the tracker with a message of the kernel, and return the
+    /// change it describes. Messages other than the link and address ones
+    /// are ignored, as well as the bridge port notifications (link messages
+    /// of the `AF_BRIDGE` family).
+    pub fn update(
+        &mut self,
+        message: &NetlinkMessage<RtnlMessage>,
+    ) -> Option<ChangeEvent> {
+        let message = match &message.payload {
+            NetlinkPayload::InnerMessage(message) => message,
+            _ => return None,
+        };
+        match message {
+            RtnlMessage::NewLink(link) | RtnlMessage::DelLink(link)
+                if link.header.interface_family == AF_BRIDGE as u8 =>
+            {
+                None
+            }
+            RtnlMessage::NewLink(link) => {
+                let link = Link::from(link);
+                let change = match self.links.insert(link.index, link.clone()) {
+                    Some(old) => ObjectChange::Changed {
+                        changes: link_changes(&old, &link),
+                        old,
+                        new: link,
+                    },
+                    None => ObjectChange::Added(link),
+                };
+                Some(ChangeEvent::Link(change))
+            }
+            RtnlMessage::DelLink(link) => {
+                let link = Link::from(link);
+                self.addresses
+                    .retain(|_, address| address.index != link.index);
+                let link = self.links.remove(&link.index).unwrap_or(link);
+                Some(ChangeEvent::Link(ObjectChange::Removed(link)))
+            }
+            RtnlMessage::NewAddress(address) => {
+                let address = Address::from(address);
+                let key = address_key(&address);
+                let change = match self.addresses.insert(key, address.clone()) {
+                    Some(old) => ObjectChange::Changed {
+                        changes: address_changes(&old, &address),
+                        old,
+                        new: address,
+                    },
+                    None => ObjectChange::Added(address),
+                };
+                Some(ChangeEvent::Address(change))
+            }
+            RtnlMessage::DelAddress(address) => {
+                let address = Address::from(address);
+                let address = self
+                    .addresses
+                    .remove(&address_key(&address))
+                    .unwrap_or(address);
+                Some(ChangeEvent::Address(ObjectChange::Removed(address)))
+            }
+            _ => None,
+        }
+    }
+
+    /// Forget all the objects, for instance after events were lost
+    pub fn clear(&mut self) {
+        self.links.clear();
+        self.addresses.clear();
+    }
+
+    /// The last known state of the link `index`
+    pub fn link(&self, index: u32) -> Option<&Link> {
+        self.links.get(&index)
+    }
+}
+
+struct Changes(Vec<FieldChange>);
+
+impl Changes {
+    fn compare<T: PartialEq>(
+        &mut self,
+        field: &'static str,
+        old: &T,
+        new: &T,
+        show: impl Fn(&T) -> String,
+    ) {
+        if old != new {
+            self.0.push(FieldChange {
+                field,
+                old: show(old),
+                new: show(new),
+            });
+        }
+    }
+}
+
+fn show<T: Display>(value: &Option<T>) -> String {
+    match value {
+        Some(value) => value.to_string(),
+        None => "none".to_string(),
+    }
+}
+
+fn show_hex(bytes: &Option<Vec<u8>>) -> String {
+    match bytes {
+        Some(bytes) => bytes
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect::<Vec<_>>()
+            .join(":"),
+        None => "none".to_string(),
+    }
+}
+
+fn link_changes(old: &Link, new: &Link) -> Vec<FieldChange> {
+    let mut changes = Changes(Vec::new());
+    changes.compare("name", &old.name, &new.name, String::clone);
+    changes.compare("flags", &old.flags, &new.flags, |flags| {
+        format!("{:#x}", flags)
+    });
+    changes.compare(
+        "link_type",
+        &old.link_layer_type,
+        &new.link_layer_type,
+        u16::to_string,
+    );
+    changes.compare("mtu", &old.mtu, &new.mtu, show);
+    changes.compare("operstate", &old.oper_state, &new.oper_state, |state| {
+        format!("{:?}", state).to_lowercase()
+    });
+    changes.compare("kind", &old.kind, &new.kind, |kind| {
+        show(&kind.as_ref().map(kind_name))
+    });
+    changes.compare("address", &old.address, &new.address, show_hex);
+    changes.compare("master", &old.master, &new.master, show);
+    changes.0
+}
+
+fn address_changes(old: &Address, new: &Address) -> Vec<FieldChange> {
+    let mut changes = Changes(Vec::new());
+    changes.compare("address", &old.address, &new.address, show);
+    changes.compare("scope", &old.scope, &new.scope, u8::to_string);
+    changes.compare("label", &old.label, &new.label, show);
+    changes.compare("broadcast", &old.broadcast, &new.broadcast, show);
+    changes.0
+}
+
+#[cfg(test)]
+mod test {
+    use netlink_packet_route::{
+        link::nlas::{Nla, State},
+        LinkMessage,
+    };
+
+    use super::*;
+
+    fn link_message(mtu: u32, state: State) -> NetlinkMessage<RtnlMessage> {
+        let mut link = LinkMessage::default();
+        link.header.index = 3;
+        link.nlas.push(Nla::IfName("eth0".into()));
+        link.nlas.push(Nla::Mtu(mtu));
+        link.nlas.push(Nla::OperState(state));
+        NetlinkMessage::from(RtnlMessage::NewLink(link))
+    }
+
+    #[test]
+    fn test_link_changes() {
+        let mut tracker = ChangeTracker::new();
+        assert!(matches!(
+            tracker.update(&link_message(1500, State::Down)),
+            Some(ChangeEvent::Link(ObjectChange::Added(_)))
+        ));
+        let changes = match tracker.update(&link_message(9000, State::Up)) {
+            Some(ChangeEvent::Link(ObjectChange::Changed {
+                old,
+                new,
+                changes,
+            })) => {
+                assert_eq!(old.mtu, Some(1500));
+                assert_eq!(new.mtu, Some(9000));
+                changes
+            }
+            event => panic!("unexpected event {:?}", event),
+        };
+        let changes: Vec<_> =
+            changes.iter().map(FieldChange::to_string).collect();
+        assert_eq!(changes, vec!["mtu 1500→9000", "operstate down→up"]);
+
+        // Bridge port notifications don't describe the link itself
+        let mut message = link_message(1500, State::Up);
+        if let NetlinkPayload::InnerMessage(RtnlMessage::NewLink(link)) =
+            &mut message.payload
+        {
+            link.header.interface_family = AF_BRIDGE as u8;
+        }
+        assert_eq!(tracker.update(&message), None);
+
+        let mut link = LinkMessage::default();
+        link.header.index = 3;
+        let message = NetlinkMessage::from(RtnlMessage::DelLink(link));
+        match tracker.update(&message) {
+            Some(ChangeEvent::Link(ObjectChange::Removed(link))) => {
+                assert_eq!(link.name, "eth0")
+            }
+            event => panic!("unexpected event {:?}", event),
+        }
+        assert_eq!(tracker.link(3), None);
+    }
+}
@@ -0,0 +1,81 @@
+// SPDX-License-Identifier: MIT
+
+use futures::{pin_mut, TryStream, TryStreamExt};
+
+use crate::Error;
+
+/// How the `execute_collect` methods of the get requests gather the results
+/// of a dump. By default, all the results are collected.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CollectOptions {
+    limit: Option<usize>,
+    capacity: usize,
+}
+
+impl CollectOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fail with [`Error::TooManyResults`] if the dump returns more than
+    /// `limit` results. The rest of the dump is then discarded.
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Reserve room for `capacity` results up front (at most the limit),
+    /// when the expected size of the dump is known.
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+}
+
+pub(crate) async fn collect<S>(
+    stream: S,
+    options: CollectOptions,
+) -> Result<Vec<S::Ok>, Error>
+where
+    S: TryStream<Error = Error>,
+{
+    let limit = options.limit.unwrap_or(usize::MAX);
+    let mut results = Vec::with_capacity(options.capacity.min(limit));
+    let stream = stream.into_stream();
+    pin_mut!(stream);
+    while let Some(result) = stream.try_next().await? {
+        if results.len() == limit {
+            return Err(Error::TooManyResults(limit));
+        }
+        results.push(result);
+    }
+    Ok(results)
+}
+
+#[cfg(test)]
+mod test {
+    use futures::stream;
+    use tokio::runtime::Runtime;
+
+    use super::*;
+
+    #[test]
+    fn test_collect_limit() {
+        let rt = Runtime::new().unwrap();
+        let results = || stream::iter(vec![Ok(1), Ok(2), Ok(3)]);
+        let options = CollectOptions::new().limit(3).capacity(10);
+        let collected = rt.block_on(collect(results(), options)).unwrap();
+        assert_eq!(collected, vec![1, 2, 3]);
+
+        let options = CollectOptions::new().limit(2);
+        assert_eq!(
+            rt.block_on(collect(results(), options)),
+            Err(Error::TooManyResults(2))
+        );
+        assert_eq!(
+            rt.block_on(collect(results(), CollectOptions::new()))
+                .unwrap(),
+            vec![1, 2, 3]
+        );
+    }
+}
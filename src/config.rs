@@ -0,0 +1,1072 @@
+// SPDX-License-Identifier: MIT
+
+//! Declarative network configuration: describe the links, addresses, routes
+//! and rules the host should have in a [`NetworkConfig`], and [`apply`]
+//! creates, updates and removes objects until the kernel state matches it.
+//!
+//! To avoid destroying the configuration made by other tools, only these
+//! objects are removed:
+//! - the links listed with [`NetworkConfig::absent_link`],
+//! - the addresses of the links that have addresses in the configuration,
+//!   except IPv6 link-local addresses,
+//! - the routes and rules installed with the protocol of the configuration
+//!   ([`RTPROT_CONFIG`] by default, see [`NetworkConfig::protocol`]).
+//!
+//! In the example below, the addresses of `eth0` are kept, since the
+//! configuration only sets its controller.
+//!
+//! [`diff`] computes the changes without making them, for instance to show
+//! them to the user first, or to audit the state of a host.
+//!
+//! ```no_run
+//! use rtnetlink::{
+//!     config::{
+//!         apply, AddressConfig, LinkConfig, NetworkConfig, RouteConfig,
+//!     },
+//!     Handle,
+//! };
+//!
+//! async fn configure(handle: Handle) -> Result<(), rtnetlink::Error> {
+//!     let config = NetworkConfig::new()
+//!         .link(LinkConfig::bridge("br0".into()).up())
+//!         .link(LinkConfig::existing("eth0".into()).master("br0".into()))
+//!         .address(AddressConfig::new(
+//!             "br0".into(),
+//!             "192.168.1.2".parse().unwrap(),
+//!             24,
+//!         ))
+//!         .route(
+//!             RouteConfig::new("0.0.0.0".parse().unwrap(), 0)
+//!                 .gateway("192.168.1.1".parse().unwrap()),
+//!         );
+//!     apply(&handle, &config).await
+//! }
+//! ```
+
+use std::{
+    collections::{HashMap, HashSet},
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+};
+
+use futures::stream::TryStreamExt;
+use netlink_packet_route::{
+    address, link::nlas::InfoKind, route, rule, AddressMessage, RouteMessage,
+    RuleMessage, AF_INET, AF_INET6, FR_ACT_TO_TBL, IFF_UP, RT_TABLE_MAIN,
+    RT_TABLE_UNSPEC,
+};
+
+use crate::{Address, Error, Handle, IpVersion, Link, Route, Rule};
+
+const IPV6_DEFAULT_METRIC: u32 = 1024;
+
+/// The default `RTPROT_*` protocol of the routes and rules of a
+/// [`NetworkConfig`]. Other tools (`ip route`, NetworkManager,
+/// systemd-networkd, ...) install their routes with `RTPROT_STATIC` or their
+/// own protocol, so [`apply`] never removes them. It is shown as `245` by
+/// `ip route`.
+pub const RTPROT_CONFIG: u8 = 245;
+
+/// The desired network configuration, see the [module documentation](self).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NetworkConfig {
+    pub links: Vec<LinkConfig>,
+    pub absent_links: Vec<String>,
+    pub addresses: Vec<AddressConfig>,
+    pub routes: Vec<RouteConfig>,
+    pub rules: Vec<RuleConfig>,
+    /// `RTPROT_*` protocol of the routes and rules of the configuration
+    pub protocol: u8,
+}
+
+impl Default for NetworkConfig {
+    fn default() -> Self {
+        NetworkConfig {
+            links: vec![],
+            absent_links: vec![],
+            addresses: vec![],
+            routes: vec![],
+            rules: vec![],
+            protocol: RTPROT_CONFIG,
+        }
+    }
+}
+
+impl NetworkConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn link(mut self, link: LinkConfig) -> Self {
+        self.links.push(link);
+        self
+    }
+
+    /// Remove the link `name` if it exists.
+    pub fn absent_link(mut self, name: String) -> Self {
+        self.absent_links.push(name);
+        self
+    }
+
+    pub fn address(mut self, address: AddressConfig) -> Self {
+        self.addresses.push(address);
+        self
+    }
+
+    pub fn route(mut self, route: RouteConfig) -> Self {
+        self.routes.push(route);
+        self
+    }
+
+    pub fn rule(mut self, rule: RuleConfig) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// Set the protocol of the routes and rules of the configuration. The
+    /// routes and rules with this protocol that are not part of the
+    /// configuration are removed, so it should not be used by other tools.
+    pub fn protocol(mut self, protocol: u8) -> Self {
+        self.protocol = protocol;
+        self
+    }
+}
+
+/// Kind of a configured link.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LinkKind {
+    /// A link that is not created by the configuration, for instance a
+    /// physical interface.
+    Existing,
+    Dummy,
+    Bridge,
+    Vlan {
+        parent: String,
+        id: u16,
+    },
+}
+
+/// A link of the configuration. The attributes left to `None` are not
+/// changed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinkConfig {
+    pub name: String,
+    pub kind: LinkKind,
+    pub mtu: Option<u32>,
+    pub up: Option<bool>,
+    /// Name of the controller (bridge, bond, ...) of the link
+    pub master: Option<String>,
+}
+
+impl LinkConfig {
+    pub fn new(name: String, kind: LinkKind) -> Self {
+        LinkConfig {
+            name,
+            kind,
+            mtu: None,
+            up: None,
+            master: None,
+        }
+    }
+
+    pub fn existing(name: String) -> Self {
+        Self::new(name, LinkKind::Existing)
+    }
+
+    pub fn dummy(name: String) -> Self {
+        Self::new(name, LinkKind::Dummy)
+    }
+
+    pub fn bridge(name: String) -> Self {
+        Self::new(name, LinkKind::Bridge)
+    }
+
+    pub fn vlan(name: String, parent: String, id: u16) -> Self {
+        Self::new(name, LinkKind::Vlan { parent, id })
+    }
+
+    pub fn mtu(mut self, mtu: u32) -> Self {
+        self.mtu = Some(mtu);
+        self
+    }
+
+    pub fn up(mut self) -> Self {
+        self.up = Some(true);
+        self
+    }
+
+    pub fn down(mut self) -> Self {
+        self.up = Some(false);
+        self
+    }
+
+    pub fn master(mut self, master: String) -> Self {
+        self.master = Some(master);
+        self
+    }
+}
+
+/// An IP address of the configuration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AddressConfig {
+    /// Name of the link of the address
+    pub link: String,
+    pub address: IpAddr,
+    pub prefix_len: u8,
+}
+
+impl AddressConfig {
+    pub fn new(link: String, address: IpAddr, prefix_len: u8) -> Self {
+        AddressConfig {
+            link,
+            address,
+            prefix_len,
+        }
+    }
+}
+
+/// A route of the configuration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RouteConfig {
+    pub destination: IpAddr,
+    pub prefix_len: u8,
+    pub gateway: Option<IpAddr>,
+    /// Name of the output link
+    pub output_link: Option<String>,
+    pub table: u32,
+    pub metric: Option<u32>,
+}
+
+impl RouteConfig {
+    pub fn new(destination: IpAddr, prefix_len: u8) -> Self {
+        RouteConfig {
+            destination,
+            prefix_len,
+            gateway: None,
+            output_link: None,
+            table: RT_TABLE_MAIN.into(),
+            metric: None,
+        }
+    }
+
+    pub fn gateway(mut self, gateway: IpAddr) -> Self {
+        self.gateway = Some(gateway);
+        self
+    }
+
+    pub fn output_link(mut self, link: String) -> Self {
+        self.output_link = Some(link);
+        self
+    }
+
+    pub fn table(mut self, table: u32) -> Self {
+        self.table = table;
+        self
+    }
+
+    pub fn metric(mut self, metric: u32) -> Self {
+        self.metric = Some(metric);
+        self
+    }
+
+    fn family(&self) -> u8 {
+        family(&self.destination)
+    }
+
+    // The kernel uses a default metric of 1024 for IPv6 routes
+    fn normalized_metric(&self) -> u32 {
+        match (self.metric, self.destination) {
+            (Some(metric), _) => metric,
+            (None, IpAddr::V4(_)) => 0,
+            (None, IpAddr::V6(_)) => IPV6_DEFAULT_METRIC,
+        }
+    }
+}
+
+/// A routing rule of the configuration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleConfig {
+    pub ip_version: IpVersion,
+    pub priority: u32,
+    pub table: u32,
+    pub source: Option<(IpAddr, u8)>,
+    pub destination: Option<(IpAddr, u8)>,
+}
+
+impl RuleConfig {
+    pub fn new(ip_version: IpVersion, priority: u32, table: u32) -> Self {
+        RuleConfig {
+            ip_version,
+            priority,
+            table,
+            source: None,
+            destination: None,
+        }
+    }
+
+    pub fn source(mut self, address: IpAddr, prefix_len: u8) -> Self {
+        self.source = Some((address, prefix_len));
+        self
+    }
+
+    pub fn destination(mut self, address: IpAddr, prefix_len: u8) -> Self {
+        self.destination = Some((address, prefix_len));
+        self
+    }
+
+    fn matches(&self, rule: &Rule) -> bool {
+        let prefix = |address: Option<IpAddr>, prefix_len| {
+            address.map(|address| (address, prefix_len))
+        };
+        self.ip_version.clone().family() == rule.family
+            && self.priority == rule.priority
+            && self.table == rule.table
+            && self.source == prefix(rule.source, rule.source_prefix_length)
+            && self.destination
+                == prefix(rule.destination, rule.destination_prefix_length)
+    }
+}
+
+/// Apply `config`, see the [module documentation](self). This is a
+/// shortcut for dumping the [`NetworkState`], computing the [`diff`] and
+/// applying the resulting [`Plan`].
+pub async fn apply(
+    handle: &Handle,
+    config: &NetworkConfig,
+) -> Result<(), Error> {
+    let state = NetworkState::dump(handle).await?;
+    diff(&state, config)?.apply(handle).await
+}
+
+/// A snapshot of the links, addresses, routes and rules of the kernel.
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct NetworkState {
+    pub links: Vec<Link>,
+    pub addresses: Vec<Address>,
+    pub routes: Vec<Route>,
+    pub rules: Vec<Rule>,
+}
+
+impl NetworkState {
+    /// Dump the current state of the kernel
+    pub async fn dump(handle: &Handle) -> Result<Self, Error> {
+        let links = handle
+            .link()
+            .get()
+            .execute()
+            .map_ok(Link::from)
+            .try_collect()
+            .await?;
+        let addresses = handle
+            .address()
+            .get()
+            .execute()
+            .map_ok(Address::from)
+            .try_collect()
+            .await?;
+        let mut routes = vec![];
+        let mut rules = vec![];
+        for ip_version in [IpVersion::V4, IpVersion::V6] {
+            let mut dump = handle.route().get(ip_version.clone()).execute();
+            while let Some(route) = dump.try_next().await? {
+                routes.push(Route::from(route));
+            }
+            let mut dump = handle.rule().get(ip_version).execute();
+            while let Some(rule) = dump.try_next().await? {
+                rules.push(Rule::from(rule));
+            }
+        }
+        Ok(NetworkState {
+            links,
+            addresses,
+            routes,
+            rules,
+        })
+    }
+
+    fn link(&self, name: &str) -> Option<&Link> {
+        self.links.iter().find(|link| link.name == name)
+    }
+
+    fn link_name(&self, index: u32) -> Option<&str> {
+        self.links
+            .iter()
+            .find(|link| link.index == index)
+            .map(|link| link.name.as_str())
+    }
+}
+
+/// A change to make to the kernel state. The links, addresses, routes and
+/// rules to remove are the ones of the current [`NetworkState`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    DelRule(Rule),
+    DelRoute(Route),
+    DelAddress(Address),
+    DelLink(Link),
+    AddLink(LinkConfig),
+    /// Set the attributes of the link which are not `None`
+    SetLink(LinkConfig),
+    AddAddress(AddressConfig),
+    /// Add the route, or replace the route with the same destination, table
+    /// and metric
+    AddRoute(RouteConfig),
+    AddRule(RuleConfig),
+}
+
+/// The changes to make to go from a [`NetworkState`] to a
+/// [`NetworkConfig`], as returned by [`diff`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Plan {
+    /// The changes, in dependency order: removals first, then links,
+    /// addresses, routes and rules.
+    pub changes: Vec<Change>,
+    protocol: u8,
+    links: HashMap<String, u32>,
+}
+
+impl Plan {
+    /// Whether the state already matches the configuration
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Make the changes, in order. On error, the changes already made are
+    /// not rolled back.
+    pub async fn apply(self, handle: &Handle) -> Result<(), Error> {
+        let Plan {
+            changes,
+            protocol,
+            mut links,
+        } = self;
+        for change in changes {
+            change.execute(handle, &mut links, protocol).await?;
+        }
+        Ok(())
+    }
+}
+
+/// Compute the changes to make to go from the `current` state to the
+/// `desired` configuration, following the rules of the
+/// [module documentation](self). This does not talk to the kernel, so it can
+/// also be used to audit a state against a configuration.
+pub fn diff(
+    current: &NetworkState,
+    desired: &NetworkConfig,
+) -> Result<Plan, Error> {
+    let (state, config) = (current, desired);
+    let mut dels = vec![];
+    let mut adds = vec![];
+
+    // Rules
+    for rule in &state.rules {
+        if rule.protocol == Some(config.protocol)
+            && !config.rules.iter().any(|desired| desired.matches(rule))
+        {
+            dels.push(Change::DelRule(rule.clone()));
+        }
+    }
+    for desired in &config.rules {
+        if !state.rules.iter().any(|rule| desired.matches(rule)) {
+            adds.push(Change::AddRule(desired.clone()));
+        }
+    }
+
+    // Routes
+    let link_index = |name: &Option<String>| match name {
+        Some(name) => state.link(name).map(|link| link.index),
+        None => None,
+    };
+    for route in &state.routes {
+        if route.protocol == config.protocol
+            && !config
+                .routes
+                .iter()
+                .any(|desired| same_route(desired, route))
+        {
+            dels.push(Change::DelRoute(route.clone()));
+        }
+    }
+    for desired in &config.routes {
+        let unchanged = state.routes.iter().any(|route| {
+            same_route(desired, route)
+                && route.protocol == config.protocol
+                && route.gateway == desired.gateway
+                && (desired.output_link.is_none()
+                    || route.output_interface
+                        == link_index(&desired.output_link))
+        });
+        if !unchanged {
+            adds.push(Change::AddRoute(desired.clone()));
+        }
+    }
+
+    // Addresses, only on the links that have addresses in the
+    // configuration
+    for link in &config.links {
+        if !config
+            .addresses
+            .iter()
+            .any(|desired| desired.link == link.name)
+        {
+            continue;
+        }
+        let index = match state.link(&link.name) {
+            Some(link) => link.index,
+            None => continue,
+        };
+        for address in state.addresses.iter().filter(|a| a.index == index) {
+            let ip = match address.local.or(address.address) {
+                Some(ip) => ip,
+                None => continue,
+            };
+            if is_link_local(&ip) {
+                continue;
+            }
+            if !config.addresses.iter().any(|desired| {
+                desired.link == link.name
+                    && desired.address == ip
+                    && desired.prefix_len == address.prefix_len
+            }) {
+                dels.push(Change::DelAddress(address.clone()));
+            }
+        }
+    }
+    for desired in &config.addresses {
+        if !config.links.iter().any(|link| link.name == desired.link) {
+            return Err(Error::InvalidRequest(format!(
+                "address {} is on link {}, which is not in the configuration",
+                desired.address, desired.link
+            )));
+        }
+        let index = state.link(&desired.link).map(|link| link.index);
+        if !state.addresses.iter().any(|address| {
+            Some(address.index) == index
+                && address.local.or(address.address) == Some(desired.address)
+                && address.prefix_len == desired.prefix_len
+        }) {
+            adds.push(Change::AddAddress(desired.clone()));
+        }
+    }
+
+    // Links
+    let mut link_adds = vec![];
+    let mut link_sets = vec![];
+    for name in &config.absent_links {
+        if let Some(link) = state.link(name) {
+            dels.push(Change::DelLink(link.clone()));
+        }
+    }
+    for desired in &config.links {
+        let current = match state.link(&desired.name) {
+            Some(current) => current,
+            None if desired.kind == LinkKind::Existing => {
+                return Err(Error::InvalidRequest(format!(
+                    "link {} does not exist",
+                    desired.name
+                )))
+            }
+            None => {
+                link_adds.push(desired.clone());
+                let set = LinkConfig {
+                    kind: LinkKind::Existing,
+                    ..desired.clone()
+                };
+                if set != LinkConfig::existing(desired.name.clone()) {
+                    link_sets.push(set);
+                }
+                continue;
+            }
+        };
+        let kind = match desired.kind {
+            LinkKind::Existing => None,
+            LinkKind::Dummy => Some(InfoKind::Dummy),
+            LinkKind::Bridge => Some(InfoKind::Bridge),
+            LinkKind::Vlan { .. } => Some(InfoKind::Vlan),
+        };
+        if kind.is_some() && kind != current.kind {
+            return Err(Error::InvalidRequest(format!(
+                "link {} exists with a different kind",
+                desired.name
+            )));
+        }
+        let mut set = LinkConfig::existing(desired.name.clone());
+        if desired.mtu.is_some() && desired.mtu != current.mtu {
+            set.mtu = desired.mtu;
+        }
+        if desired.up.is_some()
+            && desired.up != Some(current.flags & IFF_UP != 0)
+        {
+            set.up = desired.up;
+        }
+        if desired.master.is_some()
+            && desired.master.as_deref()
+                != current.master.and_then(|index| state.link_name(index))
+        {
+            set.master = desired.master.clone();
+        }
+        if set != LinkConfig::existing(desired.name.clone()) {
+            link_sets.push(set);
+        }
+    }
+
+    // The parent of a VLAN must be created before the VLAN
+    let mut changes = dels;
+    let mut created = HashSet::new();
+    while !link_adds.is_empty() {
+        let (ready, pending): (Vec<_>, Vec<_>) =
+            link_adds.into_iter().partition(|link| match &link.kind {
+                LinkKind::Vlan { parent, .. } => {
+                    state.link(parent).is_some() || created.contains(parent)
+                }
+                _ => true,
+            });
+        if ready.is_empty() {
+            return Err(Error::InvalidRequest(format!(
+                "missing parent link for {}",
+                pending[0].name
+            )));
+        }
+        for link in ready {
+            created.insert(link.name.clone());
+            changes.push(Change::AddLink(link));
+        }
+        link_adds = pending;
+    }
+    changes.extend(link_sets.into_iter().map(Change::SetLink));
+    // Add the addresses before the routes that may use them
+    adds.sort_by_key(|change| match change {
+        Change::AddAddress(_) => 0,
+        Change::AddRoute(_) => 1,
+        _ => 2,
+    });
+    changes.extend(adds);
+    Ok(Plan {
+        changes,
+        protocol: config.protocol,
+        links: state
+            .links
+            .iter()
+            .map(|link| (link.name.clone(), link.index))
+            .collect(),
+    })
+}
+
+// Whether `route` has the same key as `desired`, i.e. whether adding
+// `desired` would replace it.
+fn same_route(desired: &RouteConfig, route: &Route) -> bool {
+    let destination = route
+        .destination
+        .unwrap_or_else(|| unspecified(route.address_family));
+    desired.family() == route.address_family
+        && desired.destination == destination
+        && desired.prefix_len == route.destination_prefix_length
+        && desired.table == route.table
+        && desired.normalized_metric() == route.priority.unwrap_or(0)
+}
+
+impl Change {
+    async fn execute(
+        self,
+        handle: &Handle,
+        links: &mut HashMap<String, u32>,
+        protocol: u8,
+    ) -> Result<(), Error> {
+        match self {
+            Change::DelRule(rule) => {
+                handle.rule().del(rule_message(&rule)).execute().await
+            }
+            Change::DelRoute(route) => {
+                handle.route().del(route_message(&route)).execute().await
+            }
+            Change::DelAddress(address) => {
+                handle
+                    .address()
+                    .del(address_message(&address))
+                    .execute()
+                    .await
+            }
+            Change::DelLink(link) => {
+                handle.link().del(link.index).execute().await?;
+                links.remove(&link.name);
+                Ok(())
+            }
+            Change::AddLink(link) => {
+                let request = handle.link().add();
+                match link.kind {
+                    LinkKind::Existing => return Ok(()),
+                    LinkKind::Dummy => {
+                        request.dummy(link.name.clone()).execute().await?
+                    }
+                    LinkKind::Bridge => {
+                        request.bridge(link.name.clone()).execute().await?
+                    }
+                    LinkKind::Vlan { parent, id } => {
+                        let parent = link_index(handle, links, &parent).await?;
+                        request
+                            .vlan(link.name.clone(), parent, id)
+                            .execute()
+                            .await?
+                    }
+                }
+                link_index(handle, links, &link.name).await?;
+                Ok(())
+            }
+            Change::SetLink(link) => {
+                let index = link_index(handle, links, &link.name).await?;
+                let mut request = handle.link().set(index);
+                if let Some(master) = &link.master {
+                    request = request
+                        .master(link_index(handle, links, master).await?);
+                }
+                if let Some(mtu) = link.mtu {
+                    request = request.mtu(mtu);
+                }
+                match link.up {
+                    Some(true) => request = request.up(),
+                    Some(false) => request = request.down(),
+                    None => (),
+                }
+                request.execute().await
+            }
+            Change::AddAddress(address) => {
+                let index = link_index(handle, links, &address.link).await?;
+                handle
+                    .address()
+                    .ensure(index, address.address, address.prefix_len)
+                    .execute()
+                    .await
+            }
+            Change::AddRoute(route) => {
+                add_route(handle, links, &route, protocol).await
+            }
+            Change::AddRule(rule) => {
+                let mut request = handle
+                    .rule()
+                    .add()
+                    .priority(rule.priority)
+                    .table_id(rule.table)
+                    .action(FR_ACT_TO_TBL);
+                let message = request.message_mut();
+                message.header.family = rule.ip_version.family();
+                message.nlas.push(rule::Nla::Protocol(protocol));
+                if let Some((address, prefix_len)) = rule.source {
+                    message.header.src_len = prefix_len;
+                    message.nlas.push(rule::Nla::Source(octets(&address)));
+                }
+                if let Some((address, prefix_len)) = rule.destination {
+                    message.header.dst_len = prefix_len;
+                    message.nlas.push(rule::Nla::Destination(octets(&address)));
+                }
+                match request.execute().await {
+                    Err(e) if e.is_already_exists() => Ok(()),
+                    result => result,
+                }
+            }
+        }
+    }
+}
+
+async fn add_route(
+    handle: &Handle,
+    links: &mut HashMap<String, u32>,
+    route: &RouteConfig,
+    protocol: u8,
+) -> Result<(), Error> {
+    let mut request = handle
+        .route()
+        .add()
+        .table_id(route.table)
+        .protocol(protocol)
+        .replace();
+    if let Some(link) = &route.output_link {
+        request =
+            request.output_interface(link_index(handle, links, link).await?);
+    }
+    if let Some(metric) = route.metric {
+        request
+            .message_mut()
+            .nlas
+            .push(route::Nla::Priority(metric));
+    }
+    let family_mismatch = || {
+        Error::InvalidRequest(format!(
+            "gateway of the route to {} is not in the same IP family",
+            route.destination
+        ))
+    };
+    match route.destination {
+        IpAddr::V4(destination) => {
+            let mut request = request
+                .v4()
+                .destination_prefix(destination, route.prefix_len);
+            match route.gateway {
+                Some(IpAddr::V4(gateway)) => request = request.gateway(gateway),
+                Some(IpAddr::V6(_)) => return Err(family_mismatch()),
+                None => (),
+            }
+            request.execute().await
+        }
+        IpAddr::V6(destination) => {
+            let mut request = request
+                .v6()
+                .destination_prefix(destination, route.prefix_len);
+            match route.gateway {
+                Some(IpAddr::V6(gateway)) => request = request.gateway(gateway),
+                Some(IpAddr::V4(_)) => return Err(family_mismatch()),
+                None => (),
+            }
+            request.execute().await
+        }
+    }
+}
+
+// Return the index of the link `name`, querying the kernel for the links
+// created since the state was dumped.
+async fn link_index(
+    handle: &Handle,
+    links: &mut HashMap<String, u32>,
+    name: &str,
+) -> Result<u32, Error> {
+    if let Some(index) = links.get(name) {
+        return Ok(*index);
+    }
+    let link = handle
+        .link()
+        .get()
+        .match_name(name.to_string())
+        .execute()
+        .try_next()
+        .await?
+        .ok_or_else(|| {
+            Error::InvalidRequest(format!("link {} does not exist", name))
+        })?;
+    links.insert(name.to_string(), link.header.index);
+    Ok(link.header.index)
+}
+
+fn address_message(address: &Address) -> AddressMessage {
+    let mut message = AddressMessage::default();
+    message.header.index = address.index;
+    message.header.prefix_len = address.prefix_len;
+    if let Some(ip) = address.local.or(address.address) {
+        message.header.family = family(&ip);
+    }
+    if let Some(ip) = &address.address {
+        message.nlas.push(address::Nla::Address(octets(ip)));
+    }
+    if let Some(ip) = &address.local {
+        message.nlas.push(address::Nla::Local(octets(ip)));
+    }
+    message
+}
+
+fn route_message(route: &Route) -> RouteMessage {
+    let mut message = RouteMessage::default();
+    message.header.address_family = route.address_family;
+    message.header.destination_prefix_length = route.destination_prefix_length;
+    message.header.protocol = route.protocol;
+    message.header.scope = route.scope;
+    message.header.kind = route.kind;
+    if route.table > 255 {
+        message.header.table = RT_TABLE_UNSPEC;
+        message.nlas.push(route::Nla::Table(route.table));
+    } else {
+        message.header.table = route.table as u8;
+    }
+    if let Some(ip) = &route.destination {
+        message.nlas.push(route::Nla::Destination(octets(ip)));
+    }
+    if let Some(ip) = &route.gateway {
+        message.nlas.push(route::Nla::Gateway(octets(ip)));
+    }
+    if let Some(index) = route.output_interface {
+        message.nlas.push(route::Nla::Oif(index));
+    }
+    if let Some(priority) = route.priority {
+        message.nlas.push(route::Nla::Priority(priority));
+    }
+    message
+}
+
+fn rule_message(rule: &Rule) -> RuleMessage {
+    let mut message = RuleMessage::default();
+    message.header.family = rule.family;
+    message.header.action = rule.action;
+    message.header.src_len = rule.source_prefix_length;
+    message.header.dst_len = rule.destination_prefix_length;
+    message.header.table = RT_TABLE_UNSPEC;
+    message.nlas.push(rule::Nla::Table(rule.table));
+    message.nlas.push(rule::Nla::Priority(rule.priority));
+    if let Some(ip) = &rule.source {
+        message.nlas.push(rule::Nla::Source(octets(ip)));
+    }
+    if let Some(ip) = &rule.destination {
+        message.nlas.push(rule::Nla::Destination(octets(ip)));
+    }
+    message
+}
+
+fn octets(ip: &IpAddr) -> Vec<u8> {
+    match ip {
+        IpAddr::V4(ip) => ip.octets().to_vec(),
+        IpAddr::V6(ip) => ip.octets().to_vec(),
+    }
+}
+
+fn family(ip: &IpAddr) -> u8 {
+    match ip {
+        IpAddr::V4(_) => AF_INET as u8,
+        IpAddr::V6(_) => AF_INET6 as u8,
+    }
+}
+
+fn unspecified(family: u8) -> IpAddr {
+    if family == AF_INET6 as u8 {
+        Ipv6Addr::UNSPECIFIED.into()
+    } else {
+        Ipv4Addr::UNSPECIFIED.into()
+    }
+}
+
+// IPv6 link-local addresses (fe80::/10) are managed by the kernel
+fn is_link_local(ip: &IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(_) => false,
+        IpAddr::V6(ip) => ip.segments()[0] & 0xffc0 == 0xfe80,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use netlink_packet_route::{
+        link::nlas::State, ARPHRD_ETHER, RTN_UNICAST, RTPROT_KERNEL,
+        RTPROT_STATIC,
+    };
+
+    use super::*;
+
+    fn link(index: u32, name: &str, kind: Option<InfoKind>) -> Link {
+        Link {
+            index,
+            name: name.into(),
+            flags: IFF_UP,
+            link_layer_type: ARPHRD_ETHER,
+            mtu: Some(1500),
+            oper_state: State::Up,
+            kind,
+            address: None,
+            master: None,
+            stats: None,
+        }
+    }
+
+    fn route(destination: &str, prefix_len: u8, protocol: u8) -> Route {
+        Route {
+            address_family: AF_INET as u8,
+            destination: Some(destination.parse().unwrap()),
+            destination_prefix_length: prefix_len,
+            gateway: None,
+            source: None,
+            output_interface: Some(2),
+            priority: None,
+            table: RT_TABLE_MAIN.into(),
+            protocol,
+            scope: 0,
+            kind: RTN_UNICAST,
+        }
+    }
+
+    fn address(index: u32, ip: &str, label: &str) -> Address {
+        Address {
+            index,
+            address: Some(ip.parse().unwrap()),
+            local: Some(ip.parse().unwrap()),
+            prefix_len: 24,
+            scope: 0,
+            label: Some(label.into()),
+            broadcast: None,
+        }
+    }
+
+    fn state() -> NetworkState {
+        NetworkState {
+            links: vec![
+                link(1, "eth0", None),
+                link(2, "br0", Some(InfoKind::Bridge)),
+            ],
+            addresses: vec![
+                address(2, "10.0.0.1", "br0"),
+                address(1, "192.168.1.2", "eth0"),
+            ],
+            routes: vec![
+                route("10.0.0.0", 24, RTPROT_KERNEL),
+                route("10.1.0.0", 16, RTPROT_CONFIG),
+                // e.g. added with `ip route add`
+                route("10.3.0.0", 16, RTPROT_STATIC),
+            ],
+            rules: vec![],
+        }
+    }
+
+    #[test]
+    fn test_diff() {
+        let config = NetworkConfig::new()
+            .link(LinkConfig::bridge("br0".into()).mtu(9000))
+            .link(LinkConfig::existing("eth0".into()).master("br0".into()))
+            .link(LinkConfig::vlan("br0.10".into(), "br0".into(), 10).up())
+            .address(AddressConfig::new(
+                "br0".into(),
+                "10.0.0.2".parse().unwrap(),
+                24,
+            ))
+            .route(
+                RouteConfig::new("10.2.0.0".parse().unwrap(), 16)
+                    .output_link("br0".into()),
+            );
+        let plan = diff(&state(), &config).unwrap();
+        assert_eq!(
+            plan.changes,
+            vec![
+                Change::DelRoute(route("10.1.0.0", 16, RTPROT_CONFIG)),
+                Change::DelAddress(state().addresses[0].clone()),
+                Change::AddLink(config.links[2].clone()),
+                Change::SetLink(LinkConfig::existing("br0".into()).mtu(9000)),
+                Change::SetLink(
+                    LinkConfig::existing("eth0".into()).master("br0".into())
+                ),
+                Change::SetLink(LinkConfig::existing("br0.10".into()).up()),
+                Change::AddAddress(config.addresses[0].clone()),
+                Change::AddRoute(config.routes[0].clone()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff_unchanged() {
+        let config = NetworkConfig::new()
+            .link(LinkConfig::existing("br0".into()).mtu(1500).up())
+            .address(AddressConfig::new(
+                "br0".into(),
+                "10.0.0.1".parse().unwrap(),
+                24,
+            ))
+            .route(
+                RouteConfig::new("10.1.0.0".parse().unwrap(), 16)
+                    .output_link("br0".into()),
+            );
+        assert!(diff(&state(), &config).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_diff_errors() {
+        let config = NetworkConfig::new().link(LinkConfig::dummy("br0".into()));
+        assert!(diff(&state(), &config).is_err());
+        let config = NetworkConfig::new().link(LinkConfig::vlan(
+            "eth1.10".into(),
+            "eth1".into(),
+            10,
+        ));
+        assert!(diff(&state(), &config).is_err());
+    }
+}
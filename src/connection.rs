@@ -1,15 +1,164 @@
 // SPDX-License-Identifier: MIT
 
-use std::io;
+use std::{convert::TryFrom, io, mem::size_of, os::unix::io::AsRawFd};
 
 use futures::channel::mpsc::UnboundedReceiver;
 use netlink_packet_core::NetlinkMessage;
 use netlink_packet_route::RtnlMessage;
 use netlink_proto::Connection;
-use netlink_sys::{protocols::NETLINK_ROUTE, AsyncSocket, SocketAddr};
+use netlink_sys::{protocols::NETLINK_ROUTE, AsyncSocket, Socket, SocketAddr};
+use nix::libc;
+
+#[cfg(feature = "smol_socket")]
+pub use netlink_sys::SmolSocket;
+#[cfg(feature = "tokio_socket")]
+pub use netlink_sys::TokioSocket;
 
 use crate::Handle;
 
+/// Options applied to the netlink socket of a new connection, see
+/// [`new_connection_with_options`].
+#[derive(Debug, Clone, Default)]
+pub struct ConnectionOptions {
+    strict_check: bool,
+    no_enobufs: bool,
+    cap_ack: bool,
+    ext_ack: bool,
+    rx_buffer_size: Option<usize>,
+    tx_buffer_size: Option<usize>,
+    port_id: Option<u32>,
+}
+
+impl ConnectionOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enable strict checking of requests (`NETLINK_GET_STRICT_CHK`). With
+    /// strict checking, the kernel rejects dump requests with malformed
+    /// headers or attributes instead of ignoring them, and applies the
+    /// filters it supports (e.g. the output interface or the table of a
+    /// route dump) on its side. This requires Linux 4.20 or later.
+    pub fn strict_check(mut self, enable: bool) -> Self {
+        self.strict_check = enable;
+        self
+    }
+
+    /// Do not report `ENOBUFS` errors when the socket receive buffer
+    /// overflows (`NETLINK_NO_ENOBUFS`). Messages are silently dropped
+    /// instead.
+    pub fn no_enobufs(mut self, enable: bool) -> Self {
+        self.no_enobufs = enable;
+        self
+    }
+
+    /// Only echo the header of the original request in error messages
+    /// (`NETLINK_CAP_ACK`), which makes errors for large requests much
+    /// smaller.
+    pub fn cap_ack(mut self, enable: bool) -> Self {
+        self.cap_ack = enable;
+        self
+    }
+
+    /// Ask the kernel to explain why it rejects a request
+    /// (`NETLINK_EXT_ACK`), for instance with "Invalid VNI" instead of a
+    /// bare `EINVAL`. The errors carrying such an explanation are reported
+    /// as [`crate::Error::NetlinkErrorWithAck`] instead of
+    /// [`crate::Error::NetlinkError`]. This requires Linux 4.12 or later;
+    /// older kernels report errors without explanation.
+    pub fn ext_ack(mut self, enable: bool) -> Self {
+        self.ext_ack = enable;
+        self
+    }
+
+    /// Set the size of the socket receive buffer (`SO_RCVBUF`). Increasing
+    /// it avoids `ENOBUFS` errors when dumping large tables or listening to
+    /// busy multicast groups. The kernel caps it to `net.core.rmem_max`.
+    pub fn rx_buffer_size(mut self, size: usize) -> Self {
+        self.rx_buffer_size = Some(size);
+        self
+    }
+
+    /// Set the size of the socket send buffer (`SO_SNDBUF`). The kernel caps
+    /// it to `net.core.wmem_max`.
+    pub fn tx_buffer_size(mut self, size: usize) -> Self {
+        self.tx_buffer_size = Some(size);
+        self
+    }
+
+    /// Bind the socket to the netlink port id `port_id`, instead of letting
+    /// the kernel pick one. Binding fails if another netlink socket of the
+    /// same protocol already uses this port id. See also
+    /// [`Handle::port_id`].
+    ///
+    /// The socket of a new connection is always bound, so binding it again
+    /// to multicast groups must use the same port id, e.g.
+    /// `SocketAddr::new(handle.port_id().unwrap(), groups)`. Binding it to
+    /// the port id `0` fails with `EINVAL`, which is a breaking change.
+    pub fn port_id(mut self, port_id: u32) -> Self {
+        self.port_id = Some(port_id);
+        self
+    }
+
+    // Apply the options to `socket` and bind it, returning its port id
+    fn apply(&self, socket: &mut Socket) -> io::Result<u32> {
+        // Bind the socket now rather than when the first request is sent,
+        // so that its port id is known
+        let port_id = match self.port_id {
+            Some(port_id) => {
+                socket.bind(&SocketAddr::new(port_id, 0))?;
+                port_id
+            }
+            None => socket.bind_auto()?.port_number(),
+        };
+        if self.strict_check {
+            socket.set_netlink_get_strict_chk(true)?;
+        }
+        if self.no_enobufs {
+            socket.set_no_enobufs(true)?;
+        }
+        if self.cap_ack {
+            socket.set_cap_ack(true)?;
+        }
+        if self.ext_ack {
+            if let Err(e) = socket.set_ext_ack(true) {
+                log::debug!("failed to enable NETLINK_EXT_ACK: {}", e);
+            }
+        }
+        if let Some(size) = self.rx_buffer_size {
+            socket.set_rx_buf_sz(buffer_size(size)?)?;
+        }
+        if let Some(size) = self.tx_buffer_size {
+            set_tx_buffer_size(socket, buffer_size(size)?)?;
+        }
+        Ok(port_id)
+    }
+}
+
+fn buffer_size(size: usize) -> io::Result<libc::c_int> {
+    libc::c_int::try_from(size).map_err(|_| {
+        io::Error::new(io::ErrorKind::InvalidInput, "buffer size too large")
+    })
+}
+
+/// Return the netlink port id the socket of `connection` is bound to. The
+/// socket is bound when the connection is created, either to the port id
+/// given with [`ConnectionOptions::port_id`] or to one picked by the kernel.
+/// It is also returned by [`Handle::port_id`].
+pub fn connection_port_id<S>(
+    connection: &mut Connection<RtnlMessage, S>,
+) -> io::Result<u32>
+where
+    S: AsyncSocket,
+{
+    let mut addr = SocketAddr::new(0, 0);
+    connection
+        .socket_mut()
+        .socket_mut()
+        .get_address(&mut addr)?;
+    Ok(addr.port_number())
+}
+
 #[cfg(feature = "tokio_socket")]
 #[allow(clippy::type_complexity)]
 pub fn new_connection() -> io::Result<(
@@ -20,6 +169,43 @@ pub fn new_connection() -> io::Result<(
     new_connection_with_socket()
 }
 
+#[cfg(feature = "tokio_socket")]
+#[allow(clippy::type_complexity)]
+pub fn new_connection_with_options(
+    options: ConnectionOptions,
+) -> io::Result<(
+    Connection<RtnlMessage>,
+    Handle,
+    UnboundedReceiver<(NetlinkMessage<RtnlMessage>, SocketAddr)>,
+)> {
+    new_connection_with_socket_and_options(options)
+}
+
+/// Create a connection driven by the `smol`/`async-io` reactor, which can be
+/// spawned on any executor (`smol`, `async-std`, ...), unlike the tokio
+/// based connection returned by [`new_connection`].
+#[cfg(feature = "smol_socket")]
+#[allow(clippy::type_complexity)]
+pub fn new_smol_connection() -> io::Result<(
+    Connection<RtnlMessage, SmolSocket>,
+    Handle,
+    UnboundedReceiver<(NetlinkMessage<RtnlMessage>, SocketAddr)>,
+)> {
+    new_connection_with_socket()
+}
+
+#[cfg(feature = "smol_socket")]
+#[allow(clippy::type_complexity)]
+pub fn new_smol_connection_with_options(
+    options: ConnectionOptions,
+) -> io::Result<(
+    Connection<RtnlMessage, SmolSocket>,
+    Handle,
+    UnboundedReceiver<(NetlinkMessage<RtnlMessage>, SocketAddr)>,
+)> {
+    new_connection_with_socket_and_options(options)
+}
+
 #[allow(clippy::type_complexity)]
 pub fn new_connection_with_socket<S>() -> io::Result<(
     Connection<RtnlMessage, S>,
@@ -29,7 +215,41 @@ pub fn new_connection_with_socket<S>() -> io::Result<(
 where
     S: AsyncSocket,
 {
-    let (conn, handle, messages) =
-        netlink_proto::new_connection_with_socket(NETLINK_ROUTE)?;
-    Ok((conn, Handle::new(handle), messages))
+    new_connection_with_socket_and_options(ConnectionOptions::default())
+}
+
+#[allow(clippy::type_complexity)]
+pub fn new_connection_with_socket_and_options<S>(
+    options: ConnectionOptions,
+) -> io::Result<(
+    Connection<RtnlMessage, S>,
+    Handle,
+    UnboundedReceiver<(NetlinkMessage<RtnlMessage>, SocketAddr)>,
+)>
+where
+    S: AsyncSocket,
+{
+    let (mut conn, handle, messages) =
+        netlink_proto::new_connection_with_socket::<RtnlMessage, S>(
+            NETLINK_ROUTE,
+        )?;
+    let port_id = options.apply(conn.socket_mut().socket_mut())?;
+    Ok((conn, Handle::new(handle, port_id), messages))
+}
+
+// netlink-sys has no setter for SO_SNDBUF
+fn set_tx_buffer_size(socket: &Socket, size: libc::c_int) -> io::Result<()> {
+    let res = unsafe {
+        libc::setsockopt(
+            socket.as_raw_fd(),
+            libc::SOL_SOCKET,
+            libc::SO_SNDBUF,
+            &size as *const libc::c_int as *const libc::c_void,
+            size_of::<libc::c_int>() as libc::socklen_t,
+        )
+    };
+    if res < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
 }
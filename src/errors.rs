@@ -1,9 +1,26 @@
 // SPDX-License-Identifier: MIT
 
+use std::{io, net::IpAddr};
+
 use netlink_packet_core::{ErrorMessage, NetlinkMessage};
 use netlink_packet_route::RtnlMessage;
+use netlink_packet_utils::{
+    byteorder::{ByteOrder, NativeEndian},
+    nla::{NlasIterator, NLA_TYPE_MASK},
+    parsers::{parse_string, parse_u32},
+    DecodeError,
+};
+use nix::libc;
 use thiserror::Error;
 
+use crate::handle::error_code;
+
+const NLMSGHDR_LEN: usize = 16;
+const NLM_F_CAPPED: u16 = 0x100;
+const NLM_F_ACK_TLVS: u16 = 0x200;
+const NLMSGERR_ATTR_MSG: u16 = 1;
+const NLMSGERR_ATTR_OFFS: u16 = 2;
+
 #[derive(Clone, Eq, PartialEq, Debug, Error)]
 pub enum Error {
     #[error("Received an unexpected message {0:?}")]
@@ -12,6 +29,13 @@ pub enum Error {
     #[error("Received a netlink error message {0}")]
     NetlinkError(ErrorMessage),
 
+    /// A netlink error message with the extended ACK attributes the kernel
+    /// attached to it, see [`Error::extended_ack`]. The kernel only sends
+    /// them to connections created with
+    /// [`crate::ConnectionOptions::ext_ack`].
+    #[error("Received a netlink error message {0}{}", ext_ack_suffix(.1))]
+    NetlinkErrorWithAck(ErrorMessage, ExtendedAck),
+
     #[error("A netlink request failed")]
     RequestFailed,
 
@@ -28,4 +52,251 @@ pub enum Error {
 
     #[error("Failed to parse a network address (IP and mask): {0:?}/{1:?}")]
     InvalidAddress(Vec<u8>, Vec<u8>),
+
+    #[error("Invalid MAC address: {0:?}")]
+    InvalidMacAddress(String),
+
+    #[error("Invalid request: {0}")]
+    InvalidRequest(String),
+
+    #[error("Timed out waiting for a netlink response")]
+    Timeout,
+
+    #[error("The request does not match the transcript: {0:?}")]
+    TranscriptMismatch(NetlinkMessage<RtnlMessage>),
+
+    #[error("The address {0} is already assigned to the link {1}")]
+    AddressInUse(IpAddr, u32),
+
+    #[error("The dump returned more than {0} results")]
+    TooManyResults(usize),
+
+    /// An I/O error on a netlink socket other than the one of the
+    /// connection, e.g. the socket of a [`crate::Batch`]
+    #[error("Netlink socket error: {1}")]
+    SocketError(io::ErrorKind, String),
+
+    #[error("Failed to decode a netlink message: {0}")]
+    DecodeError(String),
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::SocketError(err.kind(), err.to_string())
+    }
+}
+
+impl From<DecodeError> for Error {
+    fn from(err: DecodeError) -> Self {
+        Error::DecodeError(err.to_string())
+    }
+}
+
+impl Error {
+    /// Build a `NetlinkError` from an error message and the flags of the
+    /// netlink header it was received with. If the kernel sent extended ACK
+    /// attributes, a `NetlinkErrorWithAck` is built instead.
+    pub fn netlink(flags: u16, err: ErrorMessage) -> Self {
+        match ExtendedAck::parse(flags, &err) {
+            Some(ext_ack) => Error::NetlinkErrorWithAck(err, ext_ack),
+            None => Error::NetlinkError(err),
+        }
+    }
+
+    /// The error number reported by the kernel, as a positive value (e.g.
+    /// `libc::EEXIST`). `None` if the error was not reported by the kernel.
+    pub fn errno(&self) -> Option<i32> {
+        match self {
+            Error::NetlinkError(err) | Error::NetlinkErrorWithAck(err, _) => {
+                Some(-error_code(err))
+            }
+            _ => None,
+        }
+    }
+
+    /// The extended ACK attributes of a netlink error, explaining why the
+    /// kernel rejected the request
+    pub fn extended_ack(&self) -> Option<&ExtendedAck> {
+        match self {
+            Error::NetlinkErrorWithAck(_, ext_ack) => Some(ext_ack),
+            _ => None,
+        }
+    }
+
+    /// The object to create already exists (`EEXIST`, or
+    /// [`Error::AddressInUse`])
+    pub fn is_already_exists(&self) -> bool {
+        matches!(self, Error::AddressInUse(..))
+            || self.errno() == Some(libc::EEXIST)
+    }
+
+    /// The object (or one it refers to) does not exist. The kernel reports
+    /// this as `ENOENT`, `ENODEV` (links) or `ESRCH` (routes).
+    pub fn is_not_found(&self) -> bool {
+        matches!(
+            self.errno(),
+            Some(libc::ENOENT) | Some(libc::ENODEV) | Some(libc::ESRCH)
+        )
+    }
+
+    /// The network is down (`ENETDOWN`)
+    pub fn is_network_down(&self) -> bool {
+        self.errno() == Some(libc::ENETDOWN)
+    }
+
+    /// The process lacks the privileges (usually `CAP_NET_ADMIN`) to run
+    /// the request (`EPERM` or `EACCES`)
+    pub fn is_permission_denied(&self) -> bool {
+        matches!(self.errno(), Some(libc::EPERM) | Some(libc::EACCES))
+    }
+
+    /// The object is in use (`EBUSY`)
+    pub fn is_busy(&self) -> bool {
+        self.errno() == Some(libc::EBUSY)
+    }
+}
+
+/// Extended ACK attributes (`NETLINK_EXT_ACK`) that the kernel may attach to
+/// an error message to explain why a request was rejected.
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct ExtendedAck {
+    /// Error message, for instance "Invalid VNI"
+    pub message: Option<String>,
+    /// Offset in the request of the attribute that caused the error
+    pub offset: Option<u32>,
+    /// Type of the attribute that caused the error. It is only known when
+    /// the kernel echoes the whole request, i.e. without `NETLINK_CAP_ACK`.
+    pub attribute: Option<u16>,
+}
+
+impl ExtendedAck {
+    /// Parse the extended ACK attributes of an error message. `flags` are
+    /// the flags of the netlink header of the error message. Returns `None`
+    /// if the message does not carry any.
+    pub fn parse(flags: u16, err: &ErrorMessage) -> Option<Self> {
+        if flags & NLM_F_ACK_TLVS == 0 {
+            return None;
+        }
+        // The attributes follow the original request, which the kernel
+        // truncates to its header when NETLINK_CAP_ACK is set.
+        let capped = flags & NLM_F_CAPPED != 0;
+        let request_len = if capped {
+            NLMSGHDR_LEN
+        } else {
+            NativeEndian::read_u32(err.header.get(..4)?) as usize
+        };
+        let attributes = err.header.get((request_len + 3) & !3..)?;
+
+        let mut ext_ack = ExtendedAck::default();
+        for nla in NlasIterator::new(attributes) {
+            let nla = nla.ok()?;
+            match nla.kind() {
+                NLMSGERR_ATTR_MSG => {
+                    ext_ack.message = parse_string(nla.value()).ok()
+                }
+                NLMSGERR_ATTR_OFFS => {
+                    ext_ack.offset = parse_u32(nla.value()).ok()
+                }
+                _ => {}
+            }
+        }
+        // The offset is relative to the start of the request, which is only
+        // echoed back entirely when the ACK is not capped.
+        if let Some(offset) = ext_ack.offset.filter(|_| !capped) {
+            let offset = offset as usize;
+            if offset >= NLMSGHDR_LEN && offset + 4 <= request_len {
+                ext_ack.attribute = err
+                    .header
+                    .get(offset + 2..offset + 4)
+                    .map(|kind| NativeEndian::read_u16(kind) & NLA_TYPE_MASK);
+            }
+        }
+        Some(ext_ack)
+    }
+}
+
+fn ext_ack_suffix(ext_ack: &ExtendedAck) -> String {
+    let mut suffix = String::new();
+    if let Some(message) = &ext_ack.message {
+        suffix.push_str(": ");
+        suffix.push_str(message);
+    }
+    if let Some(attribute) = ext_ack.attribute {
+        suffix.push_str(&format!(" (attribute type {})", attribute));
+    }
+    suffix
+}
+
+#[cfg(test)]
+mod test {
+    use netlink_packet_core::NetlinkPayload;
+
+    use super::*;
+    use crate::handle;
+
+    fn error_message(header: Vec<u8>) -> ErrorMessage {
+        match handle::error_message(-libc::EINVAL, header).payload {
+            NetlinkPayload::Error(err) => err,
+            payload => panic!("unexpected payload {:?}", payload),
+        }
+    }
+
+    #[test]
+    fn test_errno() {
+        let err = Error::netlink(0, error_message(vec![0; NLMSGHDR_LEN]));
+        assert!(matches!(err, Error::NetlinkError(_)));
+        assert_eq!(err.errno(), Some(libc::EINVAL));
+        assert_eq!(err.extended_ack(), None);
+        assert!(!err.is_already_exists());
+        assert!(!err.is_not_found());
+        assert_eq!(Error::RequestFailed.errno(), None);
+    }
+
+    #[test]
+    fn test_socket_error() {
+        let err = Error::from(io::Error::from(io::ErrorKind::WouldBlock));
+        assert!(matches!(
+            err,
+            Error::SocketError(io::ErrorKind::WouldBlock, _)
+        ));
+        assert_eq!(err.errno(), None);
+    }
+
+    #[test]
+    fn test_parse_extended_ack() {
+        let mut header = vec![
+            // original request header, capped
+            0x20, 0, 0, 0, 0x10, 0, 0x05, 0x06, 1, 0, 0, 0, 0, 0, 0, 0,
+            // NLMSGERR_ATTR_MSG "Invalid VNI"
+            16, 0, 1, 0, b'I', b'n', b'v', b'a', b'l', b'i', b'd', b' ', b'V',
+            b'N', b'I', 0, // NLMSGERR_ATTR_OFFS
+            8, 0, 2, 0, 0x18, 0, 0, 0,
+        ];
+        let err = error_message(header.clone());
+        assert_eq!(
+            ExtendedAck::parse(NLM_F_CAPPED | NLM_F_ACK_TLVS, &err),
+            Some(ExtendedAck {
+                message: Some("Invalid VNI".into()),
+                offset: Some(0x18),
+                attribute: None,
+            })
+        );
+        assert_eq!(ExtendedAck::parse(NLM_F_CAPPED, &err), None);
+
+        // Without NETLINK_CAP_ACK, the whole request is echoed back
+        header.splice(
+            NLMSGHDR_LEN..NLMSGHDR_LEN,
+            vec![8, 0, 3, 0, 0, 0, 0, 0, 8, 0, 0x12, 0x80, 0, 0, 0, 0],
+        );
+        let err = error_message(header);
+        let ext_ack = ExtendedAck::parse(NLM_F_ACK_TLVS, &err).unwrap();
+        assert_eq!(ext_ack.message, Some("Invalid VNI".into()));
+        assert_eq!(ext_ack.attribute, Some(0x12));
+        let err = Error::netlink(NLM_F_ACK_TLVS, err);
+        assert_eq!(err.extended_ack(), Some(&ext_ack));
+        assert_eq!(err.errno(), Some(libc::EINVAL));
+        assert!(err
+            .to_string()
+            .ends_with(": Invalid VNI (attribute type 18)"));
+    }
 }
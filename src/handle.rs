@@ -1,40 +1,451 @@
 // SPDX-License-Identifier: MIT
 
-use futures::Stream;
-use netlink_packet_core::NetlinkMessage;
+use std::{
+    fs::File,
+    mem::size_of,
+    num::NonZeroUsize,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use async_lock::{Semaphore, SemaphoreGuardArc};
+use futures::{
+    future::{self, Either},
+    Future, Stream, StreamExt,
+};
+use netlink_packet_core::{
+    ErrorBuffer, ErrorMessage, NetlinkHeader, NetlinkMessage, NetlinkPayload,
+};
 use netlink_packet_route::RtnlMessage;
+use netlink_packet_utils::Parseable;
 use netlink_proto::{sys::SocketAddr, ConnectionHandle};
+use nix::libc;
 
 use crate::{
-    AddressHandle, Error, LinkHandle, NeighbourHandle, QDiscHandle,
+    raw::THREAD_NS_PATH, AddressHandle, Error, LinkHandle, LinkIndexCache,
+    MockNetwork, NeighbourHandle, NetconfHandle, PromiscuityRefs, QDiscHandle,
     RouteHandle, RuleHandle, TrafficChainHandle, TrafficClassHandle,
-    TrafficFilterHandle,
+    TrafficFilterHandle, Transcript,
 };
 
+#[cfg(feature = "smol_socket")]
+pub(crate) async fn sleep(duration: Duration) {
+    async_io::Timer::after(duration).await;
+}
+
+#[cfg(all(not(feature = "smol_socket"), feature = "tokio_socket"))]
+pub(crate) async fn sleep(duration: Duration) {
+    tokio::time::sleep(duration).await;
+}
+
+// neither smol nor tokio, use a thread to wake us up.
+#[cfg(all(not(feature = "smol_socket"), not(feature = "tokio_socket")))]
+pub(crate) async fn sleep(duration: Duration) {
+    let (tx, rx) = futures::channel::oneshot::channel();
+    std::thread::spawn(move || {
+        std::thread::sleep(duration);
+        let _ = tx.send(());
+    });
+    let _ = rx.await;
+}
+
+/// Wait for `future`, failing with [`Error::Timeout`] after `timeout`
+pub(crate) async fn timeout_after<T>(
+    timeout: Option<Duration>,
+    future: impl Future<Output = Result<T, Error>>,
+) -> Result<T, Error> {
+    match timeout {
+        Some(timeout) => {
+            let future = Box::pin(future);
+            match future::select(future, Box::pin(sleep(timeout))).await {
+                Either::Left((result, _)) => result,
+                Either::Right(_) => Err(Error::Timeout),
+            }
+        }
+        None => future.await,
+    }
+}
+
+/// How requests are retried when the kernel rejects them with a transient
+/// error (`EBUSY`, `EAGAIN` or `ENOBUFS`), see [`Handle::set_retry_policy`].
+///
+/// The delay between two attempts starts at `backoff` and doubles after
+/// each attempt, up to `max_backoff`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    max_retries: u32,
+    backoff: Duration,
+    max_backoff: Duration,
+}
+
+impl RetryPolicy {
+    /// Retry up to `max_retries` times, waiting `backoff` before the first
+    /// retry.
+    pub fn new(max_retries: u32, backoff: Duration) -> Self {
+        RetryPolicy {
+            max_retries,
+            backoff,
+            max_backoff: Duration::from_secs(1),
+        }
+    }
+
+    /// Set the maximum delay between two attempts. The default is one
+    /// second.
+    pub fn max_backoff(mut self, max_backoff: Duration) -> Self {
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    fn delay(&self, retry: u32) -> Duration {
+        self.backoff
+            .checked_mul(1 << retry.min(31))
+            .map_or(self.max_backoff, |delay| delay.min(self.max_backoff))
+    }
+}
+
+/// The requests recorded by a handle in dry-run mode, see
+/// [`Handle::dry_run`].
+#[derive(Clone, Debug, Default)]
+pub struct DryRun(Arc<Mutex<Vec<NetlinkMessage<RtnlMessage>>>>);
+
+impl DryRun {
+    fn record(&self, mut message: NetlinkMessage<RtnlMessage>) {
+        message.finalize();
+        self.0.lock().unwrap().push(message);
+    }
+
+    /// The requests recorded so far
+    pub fn messages(&self) -> Vec<NetlinkMessage<RtnlMessage>> {
+        self.0.lock().unwrap().clone()
+    }
+
+    /// The requests recorded so far, serialized as they would be sent to
+    /// the kernel (with a sequence number of 0).
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        for message in self.0.lock().unwrap().iter() {
+            let offset = buf.len();
+            buf.resize(offset + message.buffer_len(), 0);
+            message.serialize(&mut buf[offset..]);
+        }
+        buf
+    }
+
+    /// Forget the requests recorded so far
+    pub fn clear(&self) {
+        self.0.lock().unwrap().clear();
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Handle {
+    timeout: Option<Duration>,
+    retry_policy: Option<RetryPolicy>,
+    in_flight_limit: Option<Arc<Semaphore>>,
+    backend: Backend,
+    netns: Option<Arc<File>>,
+    port_id: Option<u32>,
+    link_cache: Option<LinkIndexCache>,
+    promiscuity_refs: PromiscuityRefs,
+}
+
+/// Where a handle sends its requests
 #[derive(Clone, Debug)]
-pub struct Handle(ConnectionHandle<RtnlMessage>);
+enum Backend {
+    Kernel(ConnectionHandle<RtnlMessage>),
+    DryRun(DryRun),
+    Mock(MockNetwork),
+    Replay(Transcript),
+}
 
 impl Handle {
-    pub(crate) fn new(conn: ConnectionHandle<RtnlMessage>) -> Self {
-        Handle(conn)
+    pub(crate) fn new(
+        conn: ConnectionHandle<RtnlMessage>,
+        port_id: u32,
+    ) -> Self {
+        // The socket of the connection was just created, in the network
+        // namespace of the current thread
+        let netns = match File::open(THREAD_NS_PATH) {
+            Ok(netns) => Some(Arc::new(netns)),
+            Err(e) => {
+                log::debug!("failed to open {}: {}", THREAD_NS_PATH, e);
+                None
+            }
+        };
+        Handle {
+            netns,
+            port_id: Some(port_id),
+            ..Self::with_backend(Backend::Kernel(conn))
+        }
+    }
+
+    /// A handle sending its requests to `network`, without any socket
+    pub(crate) fn new_mock(network: MockNetwork) -> Self {
+        Self::with_backend(Backend::Mock(network))
+    }
+
+    fn with_backend(backend: Backend) -> Self {
+        Handle {
+            timeout: None,
+            retry_policy: None,
+            in_flight_limit: None,
+            backend,
+            netns: None,
+            port_id: None,
+            link_cache: None,
+            promiscuity_refs: PromiscuityRefs::default(),
+        }
+    }
+
+    /// Set how long requests wait for each response message of the kernel
+    /// before failing with [`Error::Timeout`]. By default, requests wait
+    /// forever. The timeout applies to the requests made through this
+    /// handle, and through the handles created from it afterwards.
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.timeout = timeout;
+    }
+
+    pub(crate) fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    /// Retry the requests made through this handle (and the handles created
+    /// from it afterwards) that fail with a transient error, according to
+    /// `policy`. A request is only retried if the error is the first message
+    /// received, so that a dump is never returned twice. By default, requests
+    /// are not retried.
+    pub fn set_retry_policy(&mut self, policy: Option<RetryPolicy>) {
+        self.retry_policy = policy;
+    }
+
+    /// Limit the number of requests waiting for a response from the kernel
+    /// to `limit`. The requests made through this handle (and the handles
+    /// created from it afterwards) beyond this limit are only sent once
+    /// previous ones complete, so that a burst of requests can't overflow
+    /// the socket buffers. By default, there is no limit.
+    pub fn set_in_flight_limit(&mut self, limit: Option<NonZeroUsize>) {
+        self.in_flight_limit =
+            limit.map(|limit| Arc::new(Semaphore::new(limit.get())));
+    }
+
+    // Wait until a request can be sent without exceeding the in-flight
+    // limit. The request is in flight until the returned permit is dropped.
+    pub(crate) async fn in_flight_permit(&self) -> Option<SemaphoreGuardArc> {
+        match &self.in_flight_limit {
+            Some(limit) => Some(limit.acquire_arc().await),
+            None => None,
+        }
+    }
+
+    /// The network namespace of the connection of this handle, in which the
+    /// requests sent on a dedicated socket must be sent (see
+    /// `raw::handle_socket`). It is `None` if it is unknown, the
+    /// current namespace is used then. These requests can only be sent to
+    /// the kernel, so this fails for the dry-run, mock and replay handles.
+    pub(crate) fn netns(&self) -> Result<Option<&File>, Error> {
+        match self.backend {
+            Backend::Kernel(_) => Ok(self.netns.as_deref()),
+            _ => Err(Error::InvalidRequest(
+                "the request can't be sent by a dry-run, mock or replay \
+                 handle"
+                    .into(),
+            )),
+        }
+    }
+
+    /// The netlink port id the socket of the connection of this handle is
+    /// bound to, e.g. to recognize the notifications of the changes made
+    /// through this handle. It is `None` for the dry-run, mock and replay
+    /// handles, which have no socket.
+    pub fn port_id(&self) -> Option<u32> {
+        match self.backend {
+            Backend::Kernel(_) => self.port_id,
+            _ => None,
+        }
+    }
+
+    /// Answer [`LinkHandle::resolve`] from `cache` when possible, for the
+    /// lookups made through this handle and the handles created from it
+    /// afterwards. By default, each lookup is a request to the kernel.
+    pub fn set_link_cache(&mut self, cache: Option<LinkIndexCache>) {
+        self.link_cache = cache;
     }
 
+    pub(crate) fn link_cache(&self) -> Option<&LinkIndexCache> {
+        self.link_cache.as_ref()
+    }
+
+    pub(crate) fn promiscuity_refs(&self) -> &PromiscuityRefs {
+        &self.promiscuity_refs
+    }
+
+    /// Return a copy of this handle that records the requests instead of
+    /// sending them to the kernel, along with the record. This can be used to
+    /// preview what a configuration tool would do. In this mode, requests
+    /// succeed without any response: dumps return no item. The copy still
+    /// resolves link names from the link cache of this handle, if any.
+    pub fn dry_run(&self) -> (Handle, DryRun) {
+        let dry_run = DryRun::default();
+        let handle = self.derive(Backend::DryRun(dry_run.clone()));
+        (handle, dry_run)
+    }
+
+    /// Return a copy of this handle that sends the requests to the
+    /// in-memory `network` instead of the kernel. See [`MockNetwork`]. The
+    /// copy has no link cache, since the one of this handle describes the
+    /// links of the kernel.
+    pub fn mock(&self, network: &MockNetwork) -> Handle {
+        Handle {
+            link_cache: None,
+            ..self.derive(Backend::Mock(network.clone()))
+        }
+    }
+
+    /// Return a copy of this handle that answers the requests with the
+    /// responses of `transcript` (see [`crate::new_recorded_connection`])
+    /// instead of sending them to the kernel. The requests must be the same
+    /// bytes as the recorded ones, apart from the sequence number, and come
+    /// in the same order, otherwise they fail with
+    /// [`Error::TranscriptMismatch`]. Like
+    /// [`Handle::mock`], the copy has no link cache.
+    pub fn replay(&self, transcript: &Transcript) -> Handle {
+        Handle {
+            link_cache: None,
+            ..self.derive(Backend::Replay(transcript.clone()))
+        }
+    }
+
+    // A copy of this handle sending its requests to `backend`. The
+    // promiscuity references describe the links of the kernel, they are not
+    // shared with the copy.
+    fn derive(&self, backend: Backend) -> Handle {
+        Handle {
+            backend,
+            promiscuity_refs: PromiscuityRefs::default(),
+            ..self.clone()
+        }
+    }
+
+    /// Send a request and return the stream of responses from the kernel.
+    ///
+    /// It is safe to drop the stream (or a future using it) before it ends:
+    /// the connection matches the responses to their request using the
+    /// sequence number, and discards the remaining responses of a dropped
+    /// request instead of passing them to the next ones.
+    ///
+    /// The stream ends early if the request fails without a response from
+    /// the kernel, for instance when it times out (see
+    /// [`Handle::set_timeout`]). Use [`Handle::try_request`] to get the
+    /// error in this case.
+    ///
+    /// With the `tracing` feature, each request is run in a
+    /// `rtnetlink_request` span, with events for the request and its
+    /// responses.
     pub fn request(
         &mut self,
         message: NetlinkMessage<RtnlMessage>,
     ) -> Result<impl Stream<Item = NetlinkMessage<RtnlMessage>>, Error> {
-        self.0
-            .request(message, SocketAddr::new(0, 0))
-            .map_err(|_| Error::RequestFailed)
+        Ok(self
+            .try_request(message)?
+            .take_while(|response| future::ready(response.is_ok()))
+            .filter_map(|response| future::ready(response.ok())))
+    }
+
+    /// Like [`Handle::request`], but the stream ends with an error if the
+    /// request fails without a response from the kernel, e.g.
+    /// [`Error::Timeout`]. The error messages of the kernel are still
+    /// returned as responses.
+    pub fn try_request(
+        &mut self,
+        message: NetlinkMessage<RtnlMessage>,
+    ) -> Result<
+        impl Stream<Item = Result<NetlinkMessage<RtnlMessage>, Error>>,
+        Error,
+    > {
+        #[cfg(feature = "tracing")]
+        let span = request_span(&message);
+        let response = match &self.backend {
+            Backend::Kernel(_) => Either::Left(self.limited_request(message)?),
+            Backend::DryRun(dry_run) => {
+                dry_run.record(message);
+                Either::Right(local_responses(vec![]))
+            }
+            Backend::Mock(mock) => {
+                Either::Right(local_responses(mock.respond(&message)))
+            }
+            Backend::Replay(transcript) => {
+                Either::Right(local_responses(transcript.replay(message)?))
+            }
+        };
+        #[cfg(feature = "tracing")]
+        let response = trace_responses(span, response);
+        Ok(response)
+    }
+
+    fn limited_request(
+        &mut self,
+        message: NetlinkMessage<RtnlMessage>,
+    ) -> Result<
+        impl Stream<Item = Result<NetlinkMessage<RtnlMessage>, Error>>,
+        Error,
+    > {
+        let limit = match self.in_flight_limit.clone() {
+            Some(limit) => limit,
+            None => return Ok(Either::Left(self.send_request(message)?)),
+        };
+        let mut handle = self.clone();
+        let response = futures::stream::once(async move {
+            let permit = limit.acquire_arc().await;
+            match handle.send_request(message) {
+                // The permit is released when the response stream is
+                // dropped
+                Ok(response) => Either::Left(response.map(move |message| {
+                    let _permit = &permit;
+                    message
+                })),
+                Err(err) => {
+                    Either::Right(futures::stream::iter(Some(Err(err))))
+                }
+            }
+        })
+        .flatten();
+        Ok(Either::Right(Box::pin(response)))
+    }
+
+    fn send_request(
+        &mut self,
+        message: NetlinkMessage<RtnlMessage>,
+    ) -> Result<
+        impl Stream<Item = Result<NetlinkMessage<RtnlMessage>, Error>> + Unpin,
+        Error,
+    > {
+        let mut conn = match &self.backend {
+            Backend::Kernel(conn) => conn.clone(),
+            _ => return Err(Error::RequestFailed),
+        };
+        Ok(match self.retry_policy.clone() {
+            Some(policy) => {
+                Either::Left(with_retry(conn, message, self.timeout, policy)?)
+            }
+            None => Either::Right(send(&mut conn, message, self.timeout)?),
+        })
     }
 
+    /// Send a message without waiting for a response. A handle in dry-run
+    /// mode records it, and the mock and replay handles reject it.
     pub fn notify(
         &mut self,
         msg: NetlinkMessage<RtnlMessage>,
     ) -> Result<(), Error> {
-        self.0
-            .notify(msg, SocketAddr::new(0, 0))
-            .map_err(|_| Error::RequestFailed)?;
+        match &mut self.backend {
+            Backend::Kernel(conn) => conn
+                .notify(msg, SocketAddr::new(0, 0))
+                .map_err(|_| Error::RequestFailed)?,
+            Backend::DryRun(dry_run) => dry_run.record(msg),
+            Backend::Mock(_) | Backend::Replay(_) => {
+                return Err(Error::RequestFailed)
+            }
+        }
         Ok(())
     }
 
@@ -68,6 +479,12 @@ impl Handle {
         NeighbourHandle::new(self.clone())
     }
 
+    /// Create a new handle, specifically for the IP settings of the links
+    /// (equivalent to `ip netconf` commands)
+    pub fn netconf(&self) -> NetconfHandle {
+        NetconfHandle::new(self.clone())
+    }
+
     /// Create a new handle, specifically for traffic control qdisc requests
     /// (equivalent to `tc qdisc show` commands)
     pub fn qdisc(&self) -> QDiscHandle {
@@ -92,3 +509,202 @@ impl Handle {
         TrafficChainHandle::new(self.clone(), ifindex)
     }
 }
+
+#[cfg(feature = "tracing")]
+fn request_span(message: &NetlinkMessage<RtnlMessage>) -> tracing::Span {
+    let span = tracing::debug_span!(
+        "rtnetlink_request",
+        message_type = message.header.message_type,
+        flags = message.header.flags,
+    );
+    span.in_scope(
+        || tracing::trace!(request = ?message.payload, "sending request"),
+    );
+    span
+}
+
+#[cfg(feature = "tracing")]
+fn trace_responses<S>(
+    span: tracing::Span,
+    response: S,
+) -> impl Stream<Item = Result<NetlinkMessage<RtnlMessage>, Error>>
+where
+    S: Stream<Item = Result<NetlinkMessage<RtnlMessage>, Error>>,
+{
+    response.inspect(move |response| {
+        let _enter = span.enter();
+        match response.as_ref().map(|message| &message.payload) {
+            Ok(NetlinkPayload::Error(err)) if error_code(err) != 0 => {
+                tracing::debug!(code = error_code(err), "request failed")
+            }
+            Ok(payload) => {
+                tracing::trace!(response = ?payload, "received response")
+            }
+            Err(err) => tracing::debug!(error = %err, "request failed"),
+        }
+    })
+}
+
+// The responses of the handles that do not send their requests to the kernel
+fn local_responses(
+    messages: Vec<NetlinkMessage<RtnlMessage>>,
+) -> impl Stream<Item = Result<NetlinkMessage<RtnlMessage>, Error>> {
+    futures::stream::iter(messages.into_iter().map(Ok))
+}
+
+/// An error message of the kernel, for the mock network
+pub(crate) fn error_message(
+    code: i32,
+    header: Vec<u8>,
+) -> NetlinkMessage<RtnlMessage> {
+    let mut buf = vec![0; size_of::<i32>() + header.len()];
+    let mut error = ErrorBuffer::new(&mut buf[..]);
+    error.set_code(code);
+    error.payload_mut().copy_from_slice(&header);
+    let error = ErrorMessage::parse(&ErrorBuffer::new(&buf))
+        .expect("invalid error message");
+    NetlinkMessage::new(NetlinkHeader::default(), NetlinkPayload::Error(error))
+}
+
+pub(crate) fn error_code(err: &ErrorMessage) -> i32 {
+    err.code
+}
+
+fn send(
+    conn: &mut ConnectionHandle<RtnlMessage>,
+    message: NetlinkMessage<RtnlMessage>,
+    timeout: Option<Duration>,
+) -> Result<
+    impl Stream<Item = Result<NetlinkMessage<RtnlMessage>, Error>> + Unpin,
+    Error,
+> {
+    let response = conn
+        .request(message, SocketAddr::new(0, 0))
+        .map_err(|_| Error::RequestFailed)?
+        .map(Ok);
+    Ok(match timeout {
+        Some(timeout) => Either::Left(with_timeout(response, timeout)),
+        None => Either::Right(response),
+    })
+}
+
+fn is_transient_error(
+    response: &Result<NetlinkMessage<RtnlMessage>, Error>,
+) -> bool {
+    match response.as_ref().map(|message| &message.payload) {
+        Ok(NetlinkPayload::Error(err)) => matches!(
+            -error_code(err),
+            libc::EBUSY | libc::EAGAIN | libc::ENOBUFS
+        ),
+        _ => false,
+    }
+}
+
+fn with_retry(
+    mut conn: ConnectionHandle<RtnlMessage>,
+    message: NetlinkMessage<RtnlMessage>,
+    timeout: Option<Duration>,
+    policy: RetryPolicy,
+) -> Result<impl Stream<Item = Result<NetlinkMessage<RtnlMessage>, Error>>, Error>
+{
+    let mut response = send(&mut conn, message.clone(), timeout)?;
+    Ok(Box::pin(
+        futures::stream::once(async move {
+            let mut retry = 0;
+            loop {
+                let first = response.next().await;
+                match first {
+                    Some(ref msg)
+                        if retry < policy.max_retries
+                            && is_transient_error(msg) =>
+                    {
+                        sleep(policy.delay(retry)).await;
+                        retry += 1;
+                        match send(&mut conn, message.clone(), timeout) {
+                            Ok(new_response) => response = new_response,
+                            Err(_) => {
+                                return futures::stream::iter(first)
+                                    .chain(response)
+                            }
+                        }
+                    }
+                    _ => return futures::stream::iter(first).chain(response),
+                }
+            }
+        })
+        .flatten(),
+    ))
+}
+
+fn with_timeout<S>(
+    response: S,
+    timeout: Duration,
+) -> impl Stream<Item = Result<NetlinkMessage<RtnlMessage>, Error>>
+where
+    S: Stream<Item = Result<NetlinkMessage<RtnlMessage>, Error>>,
+{
+    Box::pin(futures::stream::unfold(
+        Some(Box::pin(response)),
+        move |response| async move {
+            let mut response = response?;
+            let delay = Box::pin(sleep(timeout));
+            match future::select(response.next(), delay).await {
+                Either::Left((Some(message), _)) => {
+                    Some((message, Some(response)))
+                }
+                Either::Left((None, _)) => None,
+                Either::Right(_) => Some((Err(Error::Timeout), None)),
+            }
+        },
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use tokio::runtime::Runtime;
+
+    use super::*;
+
+    #[test]
+    fn test_timeout() {
+        let rt = Runtime::new().unwrap();
+        rt.block_on(async {
+            let mut response = with_timeout(
+                futures::stream::pending(),
+                Duration::from_millis(10),
+            );
+            assert_eq!(response.next().await, Some(Err(Error::Timeout)));
+            assert_eq!(response.next().await, None);
+        });
+    }
+
+    #[test]
+    fn test_dry_run_records_requests() {
+        let (handle, dry_run) = MockNetwork::new().handle().dry_run();
+        futures::executor::block_on(
+            handle.link().set(4242).mtu(1400).execute(),
+        )
+        .unwrap();
+
+        let messages = dry_run.messages();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(
+            messages[0].header.length as usize,
+            dry_run.to_bytes().len()
+        );
+        dry_run.clear();
+        assert!(dry_run.messages().is_empty());
+    }
+
+    #[test]
+    fn test_port_id() {
+        let rt = Runtime::new().unwrap();
+        rt.block_on(async {
+            let (mut conn, handle, _) = crate::new_connection().unwrap();
+            let port_id = crate::connection_port_id(&mut conn).unwrap();
+            assert_ne!(port_id, 0);
+            assert_eq!(handle.port_id(), Some(port_id));
+            assert_eq!(handle.dry_run().0.port_id(), None);
+        });
+    }
+}
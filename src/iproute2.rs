@@ -0,0 +1,447 @@
+// SPDX-License-Identifier: MIT
+
+//! Representations of links, addresses and routes that serialize with the
+//! schema of the JSON output of iproute2 (`ip -json`), so that the tools
+//! parsing it can consume the output of programs using this crate.
+//!
+//! Only the fields available in [`Link`], [`Address`] and [`Route`] are
+//! rendered: for instance `qdisc` and `txqlen` are missing from the links.
+//!
+//! ```no_run
+//! use futures::stream::TryStreamExt;
+//! use rtnetlink::{iproute2, Handle, IpVersion, Link, Route};
+//!
+//! async fn ip_route(handle: Handle) -> Result<(), rtnetlink::Error> {
+//!     let links: Vec<Link> = handle
+//!         .link()
+//!         .get()
+//!         .execute()
+//!         .map_ok(Link::from)
+//!         .try_collect()
+//!         .await?;
+//!     let routes: Vec<Route> = handle
+//!         .route()
+//!         .get(IpVersion::V4)
+//!         .execute()
+//!         .map_ok(Route::from)
+//!         .try_collect()
+//!         .await?;
+//!     // Serialize this with serde_json to get the output of `ip -j route`
+//!     let _routes = iproute2::routes(&links, &routes);
+//!     Ok(())
+//! }
+//! ```
+
+use std::{convert::TryFrom, net::IpAddr};
+
+use netlink_packet_route::{
+    constants::*, AF_INET, AF_INET6, ARPHRD_ETHER, ARPHRD_LOOPBACK,
+};
+use nix::libc;
+use serde::Serialize;
+
+use crate::{kind_name, Address, Link, MacAddress, Route};
+
+// Names of the link flags, in the order `ip` shows them
+const LINK_FLAGS: &[(u32, &str)] = &[
+    (IFF_LOOPBACK, "LOOPBACK"),
+    (IFF_BROADCAST, "BROADCAST"),
+    (IFF_POINTOPOINT, "POINTOPOINT"),
+    (IFF_MULTICAST, "MULTICAST"),
+    (IFF_NOARP, "NOARP"),
+    (IFF_ALLMULTI, "ALLMULTI"),
+    (IFF_PROMISC, "PROMISC"),
+    (IFF_MASTER, "MASTER"),
+    (libc::IFF_SLAVE as u32, "SLAVE"),
+    (IFF_DEBUG, "DEBUG"),
+    (libc::IFF_DYNAMIC as u32, "DYNAMIC"),
+    (IFF_AUTOMEDIA, "AUTOMEDIA"),
+    (IFF_PORTSEL, "PORTSEL"),
+    (libc::IFF_NOTRAILERS as u32, "NOTRAILERS"),
+    (IFF_UP, "UP"),
+    (IFF_LOWER_UP, "LOWER_UP"),
+    (IFF_DORMANT, "DORMANT"),
+    (libc::IFF_ECHO as u32, "ECHO"),
+];
+
+/// A link, as shown by `ip -json link` (or `ip -json address`, with the
+/// addresses of the link)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct IpLink {
+    pub ifindex: u32,
+    pub ifname: String,
+    pub flags: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mtu: Option<u32>,
+    /// Name of the controller of the link
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub master: Option<String>,
+    pub operstate: String,
+    pub link_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub linkinfo: Option<IpLinkInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub addr_info: Option<Vec<IpAddrInfo>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct IpLinkInfo {
+    pub info_kind: String,
+}
+
+/// An address, as shown in the `addr_info` list of `ip -json address`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct IpAddrInfo {
+    pub family: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub local: Option<IpAddr>,
+    /// Address of the peer, for point-to-point links
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub address: Option<IpAddr>,
+    pub prefixlen: u8,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub broadcast: Option<IpAddr>,
+    pub scope: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+}
+
+/// A route, as shown by `ip -json route`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct IpRoute {
+    /// Type of the route, only set if it is not `unicast`
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+    /// `default`, a prefix (`192.0.2.0/24`) or an address for host routes
+    pub dst: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gateway: Option<IpAddr>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dev: Option<String>,
+    /// Only set if the route is not in the main table
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub table: Option<String>,
+    /// Only set if the protocol is not `boot`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub protocol: Option<String>,
+    /// Only set if the scope is not `global`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prefsrc: Option<IpAddr>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metric: Option<u32>,
+    pub flags: Vec<String>,
+}
+
+/// The output of `ip -json link` for `links`
+pub fn links(links: &[Link]) -> Vec<IpLink> {
+    links.iter().map(|link| ip_link(link, links)).collect()
+}
+
+/// The output of `ip -json address` for `links` and their `addresses`
+pub fn addresses(links: &[Link], addresses: &[Address]) -> Vec<IpLink> {
+    links
+        .iter()
+        .map(|link| IpLink {
+            addr_info: Some(
+                addresses
+                    .iter()
+                    .filter(|address| address.index == link.index)
+                    .map(ip_addr_info)
+                    .collect(),
+            ),
+            ..ip_link(link, links)
+        })
+        .collect()
+}
+
+/// The output of `ip -json route` for `routes`. `links` are used to find
+/// the name of the output interfaces.
+pub fn routes(links: &[Link], routes: &[Route]) -> Vec<IpRoute> {
+    routes
+        .iter()
+        .map(|route| IpRoute {
+            kind: Some(route.kind)
+                .filter(|kind| *kind != RTN_UNICAST)
+                .map(route_type_name),
+            dst: match route.destination {
+                None if route.destination_prefix_length == 0 => {
+                    "default".into()
+                }
+                None => format!("0/{}", route.destination_prefix_length),
+                Some(dst) if is_host_prefix(dst, route) => dst.to_string(),
+                Some(dst) => {
+                    format!("{}/{}", dst, route.destination_prefix_length)
+                }
+            },
+            gateway: route.gateway,
+            dev: route.output_interface.map(|index| link_name(links, index)),
+            table: Some(route.table)
+                .filter(|table| *table != RT_TABLE_MAIN as u32)
+                .map(table_name),
+            protocol: Some(route.protocol)
+                .filter(|protocol| *protocol != RTPROT_BOOT)
+                .map(protocol_name),
+            scope: Some(route.scope)
+                .filter(|scope| *scope != RT_SCOPE_UNIVERSE)
+                .map(scope_name),
+            prefsrc: route.source,
+            metric: route.priority,
+            flags: vec![],
+        })
+        .collect()
+}
+
+fn ip_link(link: &Link, links: &[Link]) -> IpLink {
+    let mut flags = Vec::new();
+    if link.flags & IFF_UP != 0 && link.flags & IFF_RUNNING == 0 {
+        flags.push("NO-CARRIER".to_string());
+    }
+    flags.extend(
+        LINK_FLAGS
+            .iter()
+            .filter(|(flag, _)| link.flags & flag != 0)
+            .map(|(_, name)| name.to_string()),
+    );
+    IpLink {
+        ifindex: link.index,
+        ifname: link.name.clone(),
+        flags,
+        mtu: link.mtu,
+        master: link.master.map(|index| link_name(links, index)),
+        operstate: format!("{:?}", link.oper_state).to_uppercase(),
+        link_type: link_type_name(link.link_layer_type),
+        address: link.address.as_deref().map(hardware_address),
+        linkinfo: link.kind.as_ref().map(|kind| IpLinkInfo {
+            info_kind: kind_name(kind),
+        }),
+        addr_info: None,
+    }
+}
+
+fn ip_addr_info(address: &Address) -> IpAddrInfo {
+    let local = address.local.or(address.address);
+    IpAddrInfo {
+        family: match local {
+            Some(IpAddr::V6(_)) => "inet6".into(),
+            _ => "inet".into(),
+        },
+        local,
+        address: address.address.filter(|peer| Some(*peer) != local),
+        prefixlen: address.prefix_len,
+        broadcast: address.broadcast,
+        scope: scope_name(address.scope),
+        label: address.label.clone(),
+    }
+}
+
+fn is_host_prefix(dst: IpAddr, route: &Route) -> bool {
+    let len = match dst {
+        IpAddr::V4(_) => 32,
+        IpAddr::V6(_) => 128,
+    };
+    route.destination_prefix_length == len
+        && (route.address_family == AF_INET as u8
+            || route.address_family == AF_INET6 as u8)
+}
+
+// `ip` shows the index of the links it does not know of as `if<index>`
+fn link_name(links: &[Link], index: u32) -> String {
+    links
+        .iter()
+        .find(|link| link.index == index)
+        .map_or_else(|| format!("if{}", index), |link| link.name.clone())
+}
+
+fn hardware_address(bytes: &[u8]) -> String {
+    match <[u8; 6]>::try_from(bytes) {
+        Ok(octets) => MacAddress(octets).to_string(),
+        Err(_) => bytes
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect::<Vec<_>>()
+            .join(":"),
+    }
+}
+
+fn link_type_name(link_layer_type: u16) -> String {
+    match link_layer_type {
+        ARPHRD_ETHER => "ether".into(),
+        ARPHRD_LOOPBACK => "loopback".into(),
+        ARPHRD_NONE => "none".into(),
+        ARPHRD_TUNNEL => "ipip".into(),
+        ARPHRD_SIT => "sit".into(),
+        ARPHRD_IPGRE => "gre".into(),
+        ARPHRD_TUNNEL6 => "tunnel6".into(),
+        ARPHRD_IP6GRE => "gre6".into(),
+        ARPHRD_INFINIBAND => "infiniband".into(),
+        ARPHRD_VOID => "void".into(),
+        ARPHRD_IEEE80211 => "ieee802.11".into(),
+        other => format!("[{}]", other),
+    }
+}
+
+fn scope_name(scope: u8) -> String {
+    match scope {
+        RT_SCOPE_UNIVERSE => "global".into(),
+        RT_SCOPE_SITE => "site".into(),
+        RT_SCOPE_LINK => "link".into(),
+        RT_SCOPE_HOST => "host".into(),
+        RT_SCOPE_NOWHERE => "nowhere".into(),
+        other => other.to_string(),
+    }
+}
+
+fn table_name(table: u32) -> String {
+    match table {
+        0 => "unspec".into(),
+        253 => "default".into(),
+        254 => "main".into(),
+        255 => "local".into(),
+        other => other.to_string(),
+    }
+}
+
+fn protocol_name(protocol: u8) -> String {
+    match protocol {
+        RTPROT_UNSPEC => "unspec".into(),
+        RTPROT_REDIRECT => "redirect".into(),
+        RTPROT_KERNEL => "kernel".into(),
+        RTPROT_BOOT => "boot".into(),
+        RTPROT_STATIC => "static".into(),
+        RTPROT_GATED => "gated".into(),
+        RTPROT_RA => "ra".into(),
+        RTPROT_MRT => "mrt".into(),
+        RTPROT_ZEBRA => "zebra".into(),
+        RTPROT_BIRD => "bird".into(),
+        RTPROT_DNROUTED => "dnrouted".into(),
+        RTPROT_XORP => "xorp".into(),
+        RTPROT_NTK => "ntk".into(),
+        RTPROT_DHCP => "dhcp".into(),
+        RTPROT_MROUTED => "mrouted".into(),
+        RTPROT_BABEL => "babel".into(),
+        186 => "bgp".into(),
+        187 => "isis".into(),
+        188 => "ospf".into(),
+        189 => "rip".into(),
+        192 => "eigrp".into(),
+        other => other.to_string(),
+    }
+}
+
+fn route_type_name(kind: u8) -> String {
+    match kind {
+        RTN_UNSPEC => "none".into(),
+        RTN_UNICAST => "unicast".into(),
+        RTN_LOCAL => "local".into(),
+        RTN_BROADCAST => "broadcast".into(),
+        RTN_ANYCAST => "anycast".into(),
+        RTN_MULTICAST => "multicast".into(),
+        RTN_BLACKHOLE => "blackhole".into(),
+        RTN_UNREACHABLE => "unreachable".into(),
+        RTN_PROHIBIT => "prohibit".into(),
+        RTN_THROW => "throw".into(),
+        RTN_NAT => "nat".into(),
+        RTN_XRESOLVE => "xresolve".into(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::net::Ipv4Addr;
+
+    use netlink_packet_route::link::nlas::{InfoKind, State};
+
+    use super::*;
+
+    fn link(index: u32, name: &str, flags: u32) -> Link {
+        Link {
+            index,
+            name: name.into(),
+            flags,
+            link_layer_type: ARPHRD_ETHER,
+            mtu: Some(1500),
+            oper_state: State::Up,
+            kind: None,
+            address: Some(vec![0x52, 0x54, 0, 0x12, 0x34, 0x56]),
+            master: None,
+            stats: None,
+        }
+    }
+
+    #[test]
+    fn test_links() {
+        let br0 = Link {
+            kind: Some(InfoKind::Bridge),
+            ..link(3, "br0", IFF_UP | IFF_BROADCAST)
+        };
+        let eth0 = Link {
+            master: Some(3),
+            ..link(2, "eth0", IFF_UP | IFF_RUNNING | IFF_LOWER_UP)
+        };
+        let links = links(&[eth0, br0]);
+        assert_eq!(links[0].flags, vec!["UP", "LOWER_UP"]);
+        assert_eq!(links[0].master.as_deref(), Some("br0"));
+        assert_eq!(links[0].operstate, "UP");
+        assert_eq!(links[0].link_type, "ether");
+        assert_eq!(links[0].address.as_deref(), Some("52:54:00:12:34:56"));
+        assert_eq!(links[0].linkinfo, None);
+        assert_eq!(links[1].flags, vec!["NO-CARRIER", "BROADCAST", "UP"]);
+        assert_eq!(links[1].linkinfo.as_ref().unwrap().info_kind, "bridge");
+    }
+
+    #[test]
+    fn test_routes() {
+        let eth0 = link(2, "eth0", IFF_UP);
+        let route = Route {
+            address_family: AF_INET as u8,
+            destination: None,
+            destination_prefix_length: 0,
+            gateway: Some(Ipv4Addr::new(192, 0, 2, 1).into()),
+            source: None,
+            output_interface: Some(2),
+            priority: Some(100),
+            table: RT_TABLE_MAIN.into(),
+            protocol: RTPROT_DHCP,
+            scope: RT_SCOPE_UNIVERSE,
+            kind: RTN_UNICAST,
+        };
+        let local = Route {
+            destination: Some(Ipv4Addr::new(192, 0, 2, 10).into()),
+            destination_prefix_length: 32,
+            gateway: None,
+            output_interface: Some(4),
+            priority: None,
+            table: RT_TABLE_LOCAL.into(),
+            protocol: RTPROT_KERNEL,
+            scope: RT_SCOPE_HOST,
+            kind: RTN_LOCAL,
+            ..route.clone()
+        };
+        let routes = routes(&[eth0], &[route, local]);
+        assert_eq!(
+            routes[0],
+            IpRoute {
+                kind: None,
+                dst: "default".into(),
+                gateway: Some(Ipv4Addr::new(192, 0, 2, 1).into()),
+                dev: Some("eth0".into()),
+                table: None,
+                protocol: Some("dhcp".into()),
+                scope: None,
+                prefsrc: None,
+                metric: Some(100),
+                flags: vec![],
+            }
+        );
+        assert_eq!(routes[1].kind.as_deref(), Some("local"));
+        assert_eq!(routes[1].dst, "192.0.2.10");
+        assert_eq!(routes[1].dev.as_deref(), Some("if4"));
+        assert_eq!(routes[1].table.as_deref(), Some("local"));
+        assert_eq!(routes[1].scope.as_deref(), Some("host"));
+    }
+}
@@ -14,6 +14,9 @@ pub use crate::ns::*;
 mod errors;
 pub use crate::errors::*;
 
+mod mac;
+pub use crate::mac::*;
+
 mod link;
 pub use crate::link::*;
 
@@ -29,12 +32,45 @@ pub use crate::rule::*;
 mod connection;
 pub use crate::connection::*;
 
+mod batch;
+pub use crate::batch::*;
+
+mod collect;
+pub use crate::collect::CollectOptions;
+
+mod changes;
+pub use crate::changes::*;
+
+mod mock;
+pub use crate::mock::*;
+
+mod transcript;
+pub use crate::transcript::*;
+
+mod monitor;
+pub use crate::monitor::*;
+
+mod raw;
+
 mod traffic_control;
 pub use crate::traffic_control::*;
 
 mod neighbour;
 pub use crate::neighbour::*;
 
+mod netconf;
+pub use crate::netconf::*;
+
 pub mod constants;
 
+pub mod config;
+
+#[cfg(feature = "serde")]
+pub mod iproute2;
+
+pub mod prelude;
+
+#[cfg(feature = "tokio_socket")]
+pub mod blocking;
+
 mod macros;
@@ -1,6 +1,9 @@
 // SPDX-License-Identifier: MIT
 
-use std::net::{Ipv4Addr, Ipv6Addr};
+use std::{
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    os::unix::io::RawFd,
+};
 
 use futures::stream::StreamExt;
 use netlink_packet_core::{
@@ -10,14 +13,57 @@ use netlink_packet_core::{
 
 use netlink_packet_route::{
     link::nlas::{
-        Info, InfoBond, InfoData, InfoKind, InfoMacVlan, InfoMacVtap, InfoVlan,
-        InfoVxlan, InfoXfrmTun, Nla, VethInfo,
+        Info, InfoBond, InfoBridge, InfoData, InfoIpVlan, InfoKind,
+        InfoMacVlan, InfoMacVtap, InfoVlan, InfoVrf, InfoVxlan, InfoXfrmTun,
+        Nla, VethInfo,
     },
     LinkMessage, RtnlMessage, IFF_UP,
 };
+use netlink_packet_utils::{
+    byteorder::{ByteOrder, NativeEndian},
+    nla::{self, DefaultNla},
+};
 
-use crate::{try_nl, Error, Handle};
-
+use super::macsec::{
+    InfoMacSec, MacSecCipherId, MacSecOffload, MacSecValidation,
+};
+use super::nla::encode;
+use super::set::{push_vlan_qos, VlanQosMapping};
+use super::tunnel::{
+    GeneveDf, GtpRole, InfoBareUdp, InfoGeneve, InfoGre, InfoGtp, InfoIpTun,
+    InfoVti, Ip6TnlMode, TunnelEncap, GRE_KEY, IP6_TNL_F_IGN_ENCAP_LIMIT,
+    TUNNEL_ENCAP_FLAG_CSUM, TUNNEL_ENCAP_FLAG_REMCSUM,
+};
+use crate::{try_nl, Error, Handle, MacAddress};
+
+const IFNAMSIZ: usize = 16;
+const VXLAN_VNI_MAX: u32 = (1 << 24) - 1;
+const VLAN_ID_MAX: u16 = 4094;
+const ETH_P_IP: u16 = 0x0800;
+const ETH_P_MPLS_UC: u16 = 0x8847;
+// BOND_MAX_ARP_TARGETS and BOND_MAX_NS_TARGETS
+const BOND_MAX_TARGETS: usize = 16;
+
+const MACVLAN_FLAG_NOPROMISC: u16 = 1;
+const IFLA_MACVLAN_BC_QUEUE_LEN: u16 = 7;
+
+const IPVLAN_F_PRIVATE: u16 = 1;
+const IPVLAN_F_VEPA: u16 = 2;
+
+const IFLA_VXLAN_GBP: u16 = 23;
+const IFLA_VXLAN_GPE: u16 = 27;
+
+const IFLA_VLAN_FLAGS: u16 = 2;
+const VLAN_FLAG_REORDER_HDR: u32 = 0x1;
+const VLAN_FLAG_GVRP: u32 = 0x2;
+const VLAN_FLAG_LOOSE_BINDING: u32 = 0x4;
+const VLAN_FLAG_MVRP: u32 = 0x8;
+
+/// A request to create a bond.
+/// This is equivalent to `ip link add NAME type bond ...` commands. The
+/// options of an existing bond can be changed with
+/// [`crate::BondSetRequest`], and its ports are added with
+/// [`crate::LinkSetRequest::master`].
 pub struct BondAddRequest {
     request: LinkAddRequest,
     info_data: Vec<InfoBond>,
@@ -32,13 +78,6 @@ impl BondAddRequest {
         s.execute().await
     }
 
-    /// Sets the interface up
-    /// This is equivalent to `ip link set up dev NAME`.
-    pub fn up(mut self) -> Self {
-        self.request = self.request.up();
-        self
-    }
-
     /// Adds the `mode` attribute to the bond
     /// This is equivalent to `ip link add name NAME type bond mode MODE`.
     pub fn mode(mut self, mode: u8) -> Self {
@@ -271,7 +310,7 @@ impl BondAddRequest {
         self
     }
 
-    /// Adds the `arp_ip_target` attribute to the bond
+    /// Adds the `arp_ip_target` attribute to the bond (at most 16 addresses)
     /// This is equivalent to `ip link add name NAME type bond arp_ip_target
     /// LIST`.
     pub fn arp_ip_target(mut self, arp_ip_target: Vec<Ipv4Addr>) -> Self {
@@ -279,7 +318,7 @@ impl BondAddRequest {
         self
     }
 
-    /// Adds the `ns_ip6_target` attribute to the bond
+    /// Adds the `ns_ip6_target` attribute to the bond (at most 16 addresses)
     /// This is equivalent to `ip link add name NAME type bond ns_ip6_target
     /// LIST`.
     pub fn ns_ip6_target(mut self, ns_ip6_target: Vec<Ipv6Addr>) -> Self {
@@ -288,6 +327,324 @@ impl BondAddRequest {
     }
 }
 
+/// A request to create a bridge.
+/// This is equivalent to `ip link add NAME type bridge ...` commands. The
+/// options use the same units as [`crate::BridgeSetRequest`], which changes
+/// them on an existing bridge.
+pub struct BridgeAddRequest {
+    request: LinkAddRequest,
+    info_data: Vec<InfoBridge>,
+}
+
+impl BridgeAddRequest {
+    /// Execute the request.
+    pub async fn execute(self) -> Result<(), Error> {
+        let data = if self.info_data.is_empty() {
+            None
+        } else {
+            Some(InfoData::Bridge(self.info_data))
+        };
+        self.request
+            .link_info(InfoKind::Bridge, data)
+            .execute()
+            .await
+    }
+
+    /// Enable or disable the spanning tree protocol. This is equivalent to
+    /// `ip link add NAME type bridge stp_state STP_STATE`.
+    pub fn stp_state(mut self, stp_state: u32) -> Self {
+        self.info_data.push(InfoBridge::StpState(stp_state));
+        self
+    }
+
+    /// Set the bridge priority used by the spanning tree protocol. This is
+    /// equivalent to `ip link add NAME type bridge priority PRIORITY`.
+    pub fn priority(mut self, priority: u16) -> Self {
+        self.info_data.push(InfoBridge::Priority(priority));
+        self
+    }
+
+    /// Set the STP forward delay, in hundredths of a second. This is
+    /// equivalent to `ip link add NAME type bridge forward_delay
+    /// FORWARD_DELAY`.
+    pub fn forward_delay(mut self, forward_delay: u32) -> Self {
+        self.info_data.push(InfoBridge::ForwardDelay(forward_delay));
+        self
+    }
+
+    /// Set the STP hello time, in hundredths of a second. This is equivalent
+    /// to `ip link add NAME type bridge hello_time HELLO_TIME`.
+    pub fn hello_time(mut self, hello_time: u32) -> Self {
+        self.info_data.push(InfoBridge::HelloTime(hello_time));
+        self
+    }
+
+    /// Set the STP maximum message age, in hundredths of a second. This is
+    /// equivalent to `ip link add NAME type bridge max_age MAX_AGE`.
+    pub fn max_age(mut self, max_age: u32) -> Self {
+        self.info_data.push(InfoBridge::MaxAge(max_age));
+        self
+    }
+
+    /// Set the lifetime of learnt FDB entries, in hundredths of a second.
+    /// This is equivalent to `ip link add NAME type bridge ageing_time
+    /// AGEING_TIME`.
+    pub fn ageing_time(mut self, ageing_time: u32) -> Self {
+        self.info_data.push(InfoBridge::AgeingTime(ageing_time));
+        self
+    }
+
+    /// Enable or disable VLAN filtering. This is equivalent to `ip link add
+    /// NAME type bridge vlan_filtering 0/1`.
+    pub fn vlan_filtering(mut self, enable: bool) -> Self {
+        self.info_data.push(InfoBridge::VlanFiltering(enable as u8));
+        self
+    }
+
+    /// Set the default PVID of the ports, `0` disabling it. This is
+    /// equivalent to `ip link add NAME type bridge vlan_default_pvid PVID`.
+    pub fn vlan_default_pvid(mut self, pvid: u16) -> Self {
+        self.info_data.push(InfoBridge::VlanDefaultPvid(pvid));
+        self
+    }
+
+    /// Enable or disable multicast snooping. This is equivalent to `ip link
+    /// add NAME type bridge mcast_snooping 0/1`.
+    pub fn mcast_snooping(mut self, enable: bool) -> Self {
+        self.info_data
+            .push(InfoBridge::MulticastSnooping(enable as u8));
+        self
+    }
+
+    /// Pass (or not) the bridged IPv4 traffic through the iptables chains.
+    /// This is equivalent to `ip link add NAME type bridge nf_call_iptables
+    /// 0/1`.
+    pub fn nf_call_iptables(mut self, enable: bool) -> Self {
+        self.info_data
+            .push(InfoBridge::NfCallIpTables(enable as u8));
+        self
+    }
+
+    /// Pass (or not) the bridged IPv6 traffic through the ip6tables chains.
+    /// This is equivalent to `ip link add NAME type bridge nf_call_ip6tables
+    /// 0/1`.
+    pub fn nf_call_ip6tables(mut self, enable: bool) -> Self {
+        self.info_data
+            .push(InfoBridge::NfCallIp6Tables(enable as u8));
+        self
+    }
+
+    /// Pass (or not) the bridged ARP traffic through the arptables chains.
+    /// This is equivalent to `ip link add NAME type bridge nf_call_arptables
+    /// 0/1`.
+    pub fn nf_call_arptables(mut self, enable: bool) -> Self {
+        self.info_data
+            .push(InfoBridge::NfCallArpTables(enable as u8));
+        self
+    }
+}
+
+/// A request to create a veth pair.
+/// This is equivalent to `ip link add NAME type veth peer name PEER_NAME
+/// ...` commands. The settings of the peer are sent in its
+/// `VETH_INFO_PEER` attribute, so that the pair is created in one request.
+pub struct VethAddRequest {
+    request: LinkAddRequest,
+    peer: LinkMessage,
+}
+
+impl VethAddRequest {
+    /// Execute the request.
+    pub async fn execute(self) -> Result<(), Error> {
+        let data = InfoData::Veth(VethInfo::Peer(self.peer));
+        self.request
+            .link_info(InfoKind::Veth, Some(data))
+            .execute()
+            .await
+    }
+
+    /// Set the MTU of the peer (equivalent to `ip link add NAME type veth
+    /// peer name PEER_NAME mtu MTU`)
+    pub fn peer_mtu(mut self, mtu: u32) -> Self {
+        self.peer.nlas.push(Nla::Mtu(mtu));
+        self
+    }
+
+    /// Set the hardware address of the peer (equivalent to `ip link add NAME
+    /// type veth peer name PEER_NAME address ADDRESS`)
+    pub fn peer_address(mut self, address: Vec<u8>) -> Self {
+        self.peer.nlas.push(Nla::Address(address));
+        self
+    }
+
+    /// Create the peer in the network namespace of the process with the
+    /// given `pid` (equivalent to `ip link add NAME type veth peer name
+    /// PEER_NAME netns PID`)
+    pub fn peer_setns_by_pid(mut self, pid: u32) -> Self {
+        self.peer.nlas.push(Nla::NetNsPid(pid));
+        self
+    }
+
+    /// Create the peer in the network namespace corresponding to the given
+    /// file descriptor (equivalent to `ip link add NAME type veth peer name
+    /// PEER_NAME netns NETNS`)
+    pub fn peer_setns_by_fd(mut self, fd: RawFd) -> Self {
+        self.peer.nlas.push(Nla::NetNsFd(fd));
+        self
+    }
+
+    /// Set the number of transmit queues of the peer (equivalent to `ip link
+    /// add NAME type veth peer name PEER_NAME numtxqueues COUNT`)
+    pub fn peer_num_tx_queues(mut self, count: u32) -> Self {
+        self.peer.nlas.push(Nla::NumTxQueues(count));
+        self
+    }
+
+    /// Set the number of receive queues of the peer (equivalent to `ip link
+    /// add NAME type veth peer name PEER_NAME numrxqueues COUNT`)
+    pub fn peer_num_rx_queues(mut self, count: u32) -> Self {
+        self.peer.nlas.push(Nla::NumRxQueues(count));
+        self
+    }
+}
+
+/// A request to create a VLAN link.
+/// This is equivalent to `ip link add link LINK name NAME type vlan id
+/// VLAN_ID ...` commands.
+pub struct VlanAddRequest {
+    request: LinkAddRequest,
+    info_data: Vec<InfoVlan>,
+    ingress_qos: Vec<VlanQosMapping>,
+    egress_qos: Vec<VlanQosMapping>,
+    flags: VlanFlags,
+}
+
+impl VlanAddRequest {
+    /// Execute the request. An [`Error::InvalidRequest`] is returned if a
+    /// VLAN priority of a QoS map is above 7.
+    pub async fn execute(self) -> Result<(), Error> {
+        let VlanAddRequest {
+            request,
+            mut info_data,
+            ingress_qos,
+            egress_qos,
+            flags,
+        } = self;
+        push_vlan_qos(&mut info_data, ingress_qos, egress_qos)?;
+        validate_vlan(&info_data)?;
+        // netlink-packet-route emits the mask of IFLA_VLAN_FLAGS over the
+        // flags, so the data is encoded here when there are flags
+        let data = if flags.mask == 0 {
+            InfoData::Vlan(info_data)
+        } else {
+            let mut data = encode(&info_data);
+            data.extend(encode(&[flags]));
+            InfoData::Other(data)
+        };
+        request
+            .link_info(InfoKind::Vlan, Some(data))
+            .execute()
+            .await
+    }
+
+    /// Set the VLAN protocol: either `0x8100` (802.1Q, the default) or
+    /// `0x88a8` (802.1ad). This is equivalent to `ip link add link LINK name
+    /// NAME type vlan protocol PROTOCOL id VLAN_ID`.
+    pub fn protocol(mut self, protocol: u16) -> Self {
+        // The kernel reads IFLA_VLAN_PROTOCOL in network order, but
+        // netlink-packet-route emits it in host order
+        self.info_data.push(InfoVlan::Protocol(protocol.to_be()));
+        self
+    }
+
+    /// Map the VLAN priority `vlan_prio` (0 to 7) of the received frames to
+    /// the packet priority `skb_prio` (equivalent to `ip link add ... type
+    /// vlan id VLAN_ID ingress-qos-map VLAN_PRIO:SKB_PRIO`)
+    pub fn ingress_qos_map(mut self, vlan_prio: u32, skb_prio: u32) -> Self {
+        self.ingress_qos.push(VlanQosMapping {
+            from: vlan_prio,
+            to: skb_prio,
+        });
+        self
+    }
+
+    /// Map the packet priority `skb_prio` of the sent frames to the VLAN
+    /// priority `vlan_prio` (0 to 7) (equivalent to `ip link add ... type
+    /// vlan id VLAN_ID egress-qos-map SKB_PRIO:VLAN_PRIO`)
+    pub fn egress_qos_map(mut self, skb_prio: u32, vlan_prio: u32) -> Self {
+        self.egress_qos.push(VlanQosMapping {
+            from: skb_prio,
+            to: vlan_prio,
+        });
+        self
+    }
+
+    /// Enable or disable the reordering of the VLAN header, which makes the
+    /// link look like an Ethernet link to the packet sockets. The kernel
+    /// enables it by default. This is equivalent to `ip link add ... type
+    /// vlan id VLAN_ID reorder_hdr on/off`.
+    pub fn reorder_hdr(mut self, enable: bool) -> Self {
+        self.flags.set(VLAN_FLAG_REORDER_HDR, enable);
+        self
+    }
+
+    /// Enable or disable the registration of the VLAN with GVRP. This is
+    /// equivalent to `ip link add ... type vlan id VLAN_ID gvrp on/off`.
+    pub fn gvrp(mut self, enable: bool) -> Self {
+        self.flags.set(VLAN_FLAG_GVRP, enable);
+        self
+    }
+
+    /// Enable or disable the registration of the VLAN with MVRP. This is
+    /// equivalent to `ip link add ... type vlan id VLAN_ID mvrp on/off`.
+    pub fn mvrp(mut self, enable: bool) -> Self {
+        self.flags.set(VLAN_FLAG_MVRP, enable);
+        self
+    }
+
+    /// Enable or disable loose binding: when enabled, the operational state
+    /// of the VLAN link does not follow the one of the underlying link. This
+    /// is equivalent to `ip link add ... type vlan id VLAN_ID loose_binding
+    /// on/off`.
+    pub fn loose_binding(mut self, enable: bool) -> Self {
+        self.flags.set(VLAN_FLAG_LOOSE_BINDING, enable);
+        self
+    }
+}
+
+/// `IFLA_VLAN_FLAGS` (`struct ifla_vlan_flags`)
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+struct VlanFlags {
+    flags: u32,
+    mask: u32,
+}
+
+impl VlanFlags {
+    fn set(&mut self, flag: u32, enable: bool) {
+        if enable {
+            self.flags |= flag;
+        } else {
+            self.flags &= !flag;
+        }
+        self.mask |= flag;
+    }
+}
+
+impl nla::Nla for VlanFlags {
+    fn value_len(&self) -> usize {
+        8
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        NativeEndian::write_u32(&mut buffer[0..4], self.flags);
+        NativeEndian::write_u32(&mut buffer[4..8], self.mask);
+    }
+
+    fn kind(&self) -> u16 {
+        IFLA_VLAN_FLAGS
+    }
+}
+
 /// A request to create a new vxlan link.
 ///  This is equivalent to `ip link add NAME vxlan id ID ...` commands.
 /// It provides methods to customize the creation of the vxlan interface
@@ -295,22 +652,32 @@ impl BondAddRequest {
 pub struct VxlanAddRequest {
     request: LinkAddRequest,
     info_data: Vec<InfoVxlan>,
+    flags: Vec<FlagNla>,
 }
 
 impl VxlanAddRequest {
     /// Execute the request.
     pub async fn execute(self) -> Result<(), Error> {
-        let s = self
-            .request
-            .link_info(InfoKind::Vxlan, Some(InfoData::Vxlan(self.info_data)));
-        s.execute().await
-    }
-
-    /// Sets the interface up
-    /// This is equivalent to `ip link set up dev NAME`.
-    pub fn up(mut self) -> Self {
-        self.request = self.request.up();
-        self
+        let VxlanAddRequest {
+            request,
+            info_data,
+            flags,
+        } = self;
+        // netlink-packet-route emits the flag attributes with a value, which
+        // the kernel rejects, so the data is encoded here when there are
+        // flags
+        let data = if flags.is_empty() {
+            InfoData::Vxlan(info_data)
+        } else {
+            validate_vxlan(&info_data)?;
+            let mut data = encode(&info_data);
+            data.extend(encode(&flags));
+            InfoData::Other(data)
+        };
+        request
+            .link_info(InfoKind::Vxlan, Some(data))
+            .execute()
+            .await
     }
 
     /// Adds the `dev` attribute to the VXLAN
@@ -495,6 +862,10 @@ impl VxlanAddRequest {
         self
     }
 
+    /// Adds the `external` attribute to the VXLAN
+    /// This is equivalent to `ip link add name NAME type vxlan id VNI
+    /// [no]external`. external - creates a flow based tunnel, whose VNI and
+    /// remote endpoint are given per packet by tc, BPF or Open vSwitch.
     pub fn collect_metadata(mut self, collect_metadata: u8) -> Self {
         self.info_data
             .push(InfoVxlan::CollectMetadata(collect_metadata));
@@ -509,159 +880,1540 @@ impl VxlanAddRequest {
         self.info_data.push(InfoVxlan::UDPCsum(udp_csum));
         self
     }
-}
 
-/// A request to create a new link. This is equivalent to the `ip link add`
-/// commands.
-///
-/// A few methods for common actions (creating a veth pair, creating a vlan
-/// interface, etc.) are provided, but custom requests can be made using the
-/// [`message_mut()`](#method.message_mut) accessor.
-pub struct LinkAddRequest {
-    handle: Handle,
-    message: LinkMessage,
-    replace: bool,
-}
+    /// Adds the `udp_zero_csum6_tx` attribute to the VXLAN
+    /// This is equivalent to `ip link add name NAME type vxlan id VNI
+    /// [no]udp6zerocsumtx`. [no]udp6zerocsumtx - skips (or computes) the
+    /// UDP checksum of the transmitted packets over IPv6.
+    pub fn udp_zero_csum6_tx(mut self, enable: u8) -> Self {
+        self.info_data.push(InfoVxlan::UDPZeroCsumTX(enable));
+        self
+    }
 
-impl LinkAddRequest {
-    pub(crate) fn new(handle: Handle) -> Self {
-        LinkAddRequest {
-            handle,
-            message: LinkMessage::default(),
-            replace: false,
-        }
+    /// Adds the `udp_zero_csum6_rx` attribute to the VXLAN
+    /// This is equivalent to `ip link add name NAME type vxlan id VNI
+    /// [no]udp6zerocsumrx`. [no]udp6zerocsumrx - allows (or not) received
+    /// packets over IPv6 without UDP checksum.
+    pub fn udp_zero_csum6_rx(mut self, enable: u8) -> Self {
+        self.info_data.push(InfoVxlan::UDPZeroCsumRX(enable));
+        self
     }
 
-    /// Execute the request.
-    pub async fn execute(self) -> Result<(), Error> {
-        let LinkAddRequest {
-            mut handle,
-            message,
-            replace,
-        } = self;
-        let mut req = NetlinkMessage::from(RtnlMessage::NewLink(message));
-        let replace = if replace { NLM_F_REPLACE } else { NLM_F_EXCL };
-        req.header.flags = NLM_F_REQUEST | NLM_F_ACK | replace | NLM_F_CREATE;
+    /// Adds the `remcsum_tx` attribute to the VXLAN
+    /// This is equivalent to `ip link add name NAME type vxlan id VNI
+    /// [no]remcsumtx`. [no]remcsumtx - enables (or not) remote checksum
+    /// offload for the transmitted packets.
+    pub fn remcsum_tx(mut self, enable: u8) -> Self {
+        self.info_data.push(InfoVxlan::RemCsumTX(enable));
+        self
+    }
 
-        let mut response = handle.request(req)?;
-        while let Some(message) = response.next().await {
-            try_nl!(message);
-        }
-        Ok(())
+    /// Adds the `remcsum_rx` attribute to the VXLAN
+    /// This is equivalent to `ip link add name NAME type vxlan id VNI
+    /// [no]remcsumrx`. [no]remcsumrx - enables (or not) remote checksum
+    /// offload for the received packets.
+    pub fn remcsum_rx(mut self, enable: u8) -> Self {
+        self.info_data.push(InfoVxlan::RemCsumRX(enable));
+        self
     }
 
-    /// Return a mutable reference to the request message.
-    ///
-    /// # Example
-    ///
-    /// Let's say we want to create a vlan interface on a link with id 6. By
-    /// default, the [`vlan()`](#method.vlan) method would create a request
-    /// with the `IFF_UP` link set, so that the interface is up after
-    /// creation. If we want to create a interface tha tis down by default we
-    /// could do:
-    ///
-    /// ```rust,no_run
-    /// use futures::Future;
-    /// use netlink_packet_route::IFF_UP;
-    /// use rtnetlink::{Handle, new_connection};
-    ///
-    /// async fn run(handle: Handle) -> Result<(), String> {
-    ///     let vlan_id = 100;
-    ///     let link_id = 6;
-    ///     let mut request = handle.link().add().vlan("my-vlan-itf".into(), link_id, vlan_id);
-    ///     // unset the IFF_UP flag before sending the request
-    ///     request.message_mut().header.flags &= !IFF_UP;
-    ///     request.message_mut().header.change_mask &= !IFF_UP;
-    ///     // send the request
-    ///     request.execute().await.map_err(|e| format!("{}", e))
-    /// }
-    pub fn message_mut(&mut self) -> &mut LinkMessage {
-        &mut self.message
+    /// Adds the `gbp` flag to the VXLAN
+    /// This is equivalent to `ip link add name NAME type vxlan id VNI gbp`.
+    /// gbp - enables the Group Policy extension, which carries the mark of
+    /// the packets in the VXLAN header.
+    pub fn gbp(mut self) -> Self {
+        self.flags.push(FlagNla(IFLA_VXLAN_GBP));
+        self
     }
 
-    /// Create a dummy link.
-    /// This is equivalent to `ip link add NAME type dummy`.
-    pub fn dummy(self, name: String) -> Self {
-        self.name(name).link_info(InfoKind::Dummy, None).up()
+    /// Adds the `gpe` flag to the VXLAN
+    /// This is equivalent to `ip link add name NAME type vxlan id VNI gpe`.
+    /// gpe - enables the Generic Protocol extension, which carries other
+    /// protocols than Ethernet (the link then has no link layer header).
+    /// It requires `collect_metadata`.
+    pub fn gpe(mut self) -> Self {
+        self.flags.push(FlagNla(IFLA_VXLAN_GPE));
+        self
     }
+}
 
-    /// Create a veth pair.
-    /// This is equivalent to `ip link add NAME1 type veth peer name NAME2`.
-    pub fn veth(self, name: String, peer_name: String) -> Self {
-        // NOTE: `name` is the name of the peer in the netlink message (ie the
-        // link created via the VethInfo::Peer attribute, and
-        // `peer_name` is the name in the main netlink message.
-        // This is a bit weird, but it's all hidden from the user.
+/// An attribute without value (`NLA_FLAG`)
+struct FlagNla(u16);
 
-        let mut peer = LinkMessage::default();
-        // FIXME: we get a -107 (ENOTCONN) (???) when trying to set `name` up.
-        // peer.header.flags = LinkFlags::from(IFF_UP);
-        // peer.header.change_mask = LinkFlags::from(IFF_UP);
-        peer.nlas.push(Nla::IfName(name));
-        let link_info_data = InfoData::Veth(VethInfo::Peer(peer));
-        self.name(peer_name)
-            .up() // iproute2 does not set this one up
-            .link_info(InfoKind::Veth, Some(link_info_data))
+impl nla::Nla for FlagNla {
+    fn value_len(&self) -> usize {
+        0
     }
 
-    /// Create VLAN on a link.
-    /// This is equivalent to `ip link add link LINK name NAME type vlan id
-    /// VLAN_ID`, but instead of specifying a link name (`LINK`), we specify
-    /// a link index.
-    pub fn vlan(self, name: String, index: u32, vlan_id: u16) -> Self {
-        self.name(name)
-            .link_info(
-                InfoKind::Vlan,
-                Some(InfoData::Vlan(vec![InfoVlan::Id(vlan_id)])),
-            )
-            .append_nla(Nla::Link(index))
-            .up()
+    fn emit_value(&self, _buffer: &mut [u8]) {}
+
+    fn kind(&self) -> u16 {
+        self.0
     }
+}
 
-    /// Create macvlan on a link.
-    /// This is equivalent to `ip link add name NAME link LINK type macvlan mode
-    /// MACVLAN_MODE`,   but instead of specifying a link name (`LINK`), we
-    /// specify a link index. The MACVLAN_MODE is an integer consisting of
-    /// flags from MACVLAN_MODE (netlink-packet-route/src/rtnl/constants.rs)
-    ///   being: _PRIVATE, _VEPA, _BRIDGE, _PASSTHRU, _SOURCE, which can be
-    /// *combined*.
-    pub fn macvlan(self, name: String, index: u32, mode: u32) -> Self {
-        self.name(name)
+/// A request to create a macvlan link.
+/// This is equivalent to `ip link add name NAME link LINK type macvlan mode
+/// MODE ...` commands.
+pub struct MacVlanAddRequest {
+    request: LinkAddRequest,
+    info_data: Vec<InfoMacVlan>,
+}
+
+impl MacVlanAddRequest {
+    /// Execute the request.
+    pub async fn execute(self) -> Result<(), Error> {
+        self.request
             .link_info(
                 InfoKind::MacVlan,
-                Some(InfoData::MacVlan(vec![InfoMacVlan::Mode(mode)])),
+                Some(InfoData::MacVlan(self.info_data)),
             )
-            .append_nla(Nla::Link(index))
-            .up()
+            .execute()
+            .await
+    }
+
+    /// Set the length of the queue of the broadcast and multicast frames
+    /// delivered to the macvlan links of the lower link (equivalent to `ip
+    /// link add name NAME link LINK type macvlan mode MODE bcqueuelen
+    /// LENGTH`). The kernel uses the largest length requested by these links.
+    pub fn bc_queue_len(mut self, length: u32) -> Self {
+        self.info_data.push(InfoMacVlan::Other(DefaultNla::new(
+            IFLA_MACVLAN_BC_QUEUE_LEN,
+            length.to_ne_bytes().to_vec(),
+        )));
+        self
     }
 
-    /// Create macvtap on a link.
-    /// This is equivalent to `ip link add name NAME link LINK type macvtap mode
-    /// MACVTAP_MODE`,   but instead of specifying a link name (`LINK`), we
-    /// specify a link index. The MACVTAP_MODE is an integer consisting of
-    /// flags from MACVTAP_MODE (netlink-packet-route/src/rtnl/constants.rs)
-    ///   being: _PRIVATE, _VEPA, _BRIDGE, _PASSTHRU, _SOURCE, which can be
-    /// *combined*.
-    pub fn macvtap(self, name: String, index: u32, mode: u32) -> Self {
-        self.name(name)
+    /// Don't put the lower link in promiscuous mode, which is only allowed
+    /// in `passthru` mode (equivalent to `ip link add name NAME link LINK
+    /// type macvlan mode passthru nopromisc`)
+    pub fn nopromisc(mut self) -> Self {
+        self.info_data
+            .push(InfoMacVlan::Flags(MACVLAN_FLAG_NOPROMISC));
+        self
+    }
+}
+
+/// A request to create a macvtap link.
+/// This is equivalent to `ip link add name NAME link LINK type macvtap mode
+/// MODE ...` commands.
+pub struct MacVtapAddRequest {
+    request: LinkAddRequest,
+    info_data: Vec<InfoMacVtap>,
+}
+
+impl MacVtapAddRequest {
+    /// Execute the request.
+    pub async fn execute(self) -> Result<(), Error> {
+        self.request
             .link_info(
                 InfoKind::MacVtap,
-                Some(InfoData::MacVtap(vec![InfoMacVtap::Mode(mode)])),
+                Some(InfoData::MacVtap(self.info_data)),
             )
-            .append_nla(Nla::Link(index))
-            .up()
+            .execute()
+            .await
+    }
+
+    /// Set the length of the queue of the broadcast and multicast frames
+    /// (equivalent to `ip link add name NAME link LINK type macvtap mode
+    /// MODE bcqueuelen LENGTH`), see [`MacVlanAddRequest::bc_queue_len`]
+    pub fn bc_queue_len(mut self, length: u32) -> Self {
+        self.info_data.push(InfoMacVtap::Other(DefaultNla::new(
+            IFLA_MACVLAN_BC_QUEUE_LEN,
+            length.to_ne_bytes().to_vec(),
+        )));
+        self
     }
 
-    /// Create a VxLAN
-    /// This is equivalent to `ip link add name NAME type vxlan id VNI`,
-    /// it returns a VxlanAddRequest to further customize the vxlan
-    /// interface creation.
-    pub fn vxlan(self, name: String, vni: u32) -> VxlanAddRequest {
-        let s = self.name(name);
-        VxlanAddRequest {
-            request: s,
-            info_data: vec![InfoVxlan::Id(vni)],
-        }
+    /// Don't put the lower link in promiscuous mode, which is only allowed
+    /// in `passthru` mode (equivalent to `ip link add name NAME link LINK
+    /// type macvtap mode passthru nopromisc`)
+    pub fn nopromisc(mut self) -> Self {
+        self.info_data
+            .push(InfoMacVtap::Flags(MACVLAN_FLAG_NOPROMISC));
+        self
+    }
+}
+
+/// The mode of an ipvlan link, which selects the layer at which its frames
+/// are switched to the other links of the lower link.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum IpVlanMode {
+    /// Switch the frames on the layer 2 address
+    L2,
+    /// Route the packets on the layer 3 address, without going through the
+    /// netfilter hooks of the namespace of the lower link
+    L3,
+    /// Like [`IpVlanMode::L3`], through the netfilter hooks
+    L3S,
+}
+
+impl From<IpVlanMode> for u16 {
+    fn from(mode: IpVlanMode) -> u16 {
+        match mode {
+            IpVlanMode::L2 => 0,
+            IpVlanMode::L3 => 1,
+            IpVlanMode::L3S => 2,
+        }
+    }
+}
+
+/// A request to create an ipvlan link.
+/// This is equivalent to `ip link add name NAME link LINK type ipvlan mode
+/// MODE ...` commands.
+pub struct IpVlanAddRequest {
+    request: LinkAddRequest,
+    info_data: Vec<InfoIpVlan>,
+}
+
+impl IpVlanAddRequest {
+    /// Execute the request.
+    pub async fn execute(self) -> Result<(), Error> {
+        self.request
+            .link_info(InfoKind::IpVlan, Some(InfoData::IpVlan(self.info_data)))
+            .execute()
+            .await
+    }
+
+    /// Let the links of the lower link communicate directly, which is the
+    /// default (equivalent to `ip link add name NAME link LINK type ipvlan
+    /// mode MODE bridge`)
+    pub fn bridge(self) -> Self {
+        self.flags(0)
+    }
+
+    /// Prevent the links of the lower link from communicating with each
+    /// other (equivalent to `ip link add name NAME link LINK type ipvlan
+    /// mode MODE private`)
+    pub fn private(self) -> Self {
+        self.flags(IPVLAN_F_PRIVATE)
+    }
+
+    /// Send all the traffic to the lower link, even between the links of
+    /// the lower link, so that an external switch can forward it back
+    /// (equivalent to `ip link add name NAME link LINK type ipvlan mode MODE
+    /// vepa`)
+    pub fn vepa(self) -> Self {
+        self.flags(IPVLAN_F_VEPA)
+    }
+
+    // The flags are exclusive, only the last one is kept
+    fn flags(mut self, flags: u16) -> Self {
+        self.info_data
+            .retain(|nla| !matches!(nla, InfoIpVlan::Flags(_)));
+        self.info_data.push(InfoIpVlan::Flags(flags));
+        self
+    }
+}
+
+/// A request to create a macsec link.
+/// This is equivalent to `ip link add link LINK name NAME type macsec ...`
+/// commands. The secure channels and their keys are configured through the
+/// `macsec` generic netlink family, which is out of the scope of this crate.
+pub struct MacSecAddRequest {
+    request: LinkAddRequest,
+    info_data: Vec<InfoMacSec>,
+}
+
+impl MacSecAddRequest {
+    /// Execute the request. An [`Error::InvalidRequest`] is returned if
+    /// both the SCI and the port are set.
+    pub async fn execute(self) -> Result<(), Error> {
+        let has_sci = self
+            .info_data
+            .iter()
+            .any(|nla| matches!(nla, InfoMacSec::Sci(_)));
+        let has_port = self
+            .info_data
+            .iter()
+            .any(|nla| matches!(nla, InfoMacSec::Port(_)));
+        if has_sci && has_port {
+            return Err(Error::InvalidRequest(
+                "the macsec SCI and port are mutually exclusive".into(),
+            ));
+        }
+        self.request
+            .link_info(
+                InfoKind::Other("macsec".into()),
+                Some(InfoData::Other(encode(&self.info_data))),
+            )
+            .execute()
+            .await
+    }
+
+    /// Set the secure channel identifier of the transmit channel
+    /// (equivalent to `ip link add link LINK name NAME type macsec sci
+    /// SCI`)
+    pub fn sci(mut self, sci: u64) -> Self {
+        self.info_data.push(InfoMacSec::Sci(sci));
+        self
+    }
+
+    /// Set the port of the transmit channel, its SCI being made of the
+    /// address of the link and of this port (equivalent to `ip link add
+    /// link LINK name NAME type macsec port PORT`)
+    pub fn port(mut self, port: u16) -> Self {
+        self.info_data.push(InfoMacSec::Port(port));
+        self
+    }
+
+    /// Set the cipher suite (equivalent to `ip link add link LINK name NAME
+    /// type macsec cipher CIPHER`)
+    pub fn cipher(mut self, cipher: MacSecCipherId) -> Self {
+        self.info_data.push(InfoMacSec::CipherSuite(cipher));
+        self
+    }
+
+    /// Set the length of the integrity check value, in bytes (equivalent to
+    /// `ip link add link LINK name NAME type macsec icvlen LENGTH`)
+    pub fn icv_len(mut self, length: u8) -> Self {
+        self.info_data.push(InfoMacSec::IcvLen(length));
+        self
+    }
+
+    /// Encrypt the frames, or only authenticate them (equivalent to `ip
+    /// link add link LINK name NAME type macsec encrypt { on | off }`)
+    pub fn encrypt(mut self, enable: bool) -> Self {
+        self.info_data.push(InfoMacSec::Encrypt(enable as u8));
+        self
+    }
+
+    /// Include the SCI in every frame (equivalent to `ip link add link LINK
+    /// name NAME type macsec send_sci { on | off }`)
+    pub fn send_sci(mut self, enable: bool) -> Self {
+        self.info_data.push(InfoMacSec::IncSci(enable as u8));
+        self
+    }
+
+    /// Protect the frames, which are otherwise sent without the security
+    /// tag (equivalent to `ip link add link LINK name NAME type macsec
+    /// protect { on | off }`)
+    pub fn protect(mut self, enable: bool) -> Self {
+        self.info_data.push(InfoMacSec::Protect(enable as u8));
+        self
+    }
+
+    /// Enable the replay protection, accepting the frames whose packet
+    /// number is at most `window` behind the expected one (equivalent to
+    /// `ip link add link LINK name NAME type macsec replay on window
+    /// WINDOW`)
+    pub fn replay_window(mut self, window: u32) -> Self {
+        self.info_data.push(InfoMacSec::ReplayProtect(1));
+        self.info_data.push(InfoMacSec::Window(window));
+        self
+    }
+
+    /// Set how the received frames are validated (equivalent to `ip link
+    /// add link LINK name NAME type macsec validate { strict | check |
+    /// disabled }`)
+    pub fn validate(mut self, validation: MacSecValidation) -> Self {
+        self.info_data.push(InfoMacSec::Validation(validation));
+        self
+    }
+
+    /// Offload the processing of the frames to the PHY or the MAC of the
+    /// lower link (equivalent to `ip link add link LINK name NAME type
+    /// macsec offload { off | phy | mac }`)
+    pub fn offload(mut self, offload: MacSecOffload) -> Self {
+        self.info_data.push(InfoMacSec::Offload(offload));
+        self
+    }
+}
+
+/// A request to create a new geneve link.
+/// This is equivalent to `ip link add NAME type geneve id VNI ...` commands.
+pub struct GeneveAddRequest {
+    request: LinkAddRequest,
+    info_data: Vec<InfoGeneve>,
+}
+
+impl GeneveAddRequest {
+    /// Execute the request. An [`Error::InvalidRequest`] is returned if the
+    /// VNI does not fit in 24 bits.
+    pub async fn execute(self) -> Result<(), Error> {
+        for nla in &self.info_data {
+            if let InfoGeneve::Id(vni) = nla {
+                if *vni > VXLAN_VNI_MAX {
+                    return Err(Error::InvalidRequest(format!(
+                        "geneve VNI {} is out of range",
+                        vni
+                    )));
+                }
+            }
+        }
+        let s = self.request.link_info(
+            InfoKind::Other("geneve".into()),
+            Some(InfoData::Other(encode(&self.info_data))),
+        );
+        s.execute().await
+    }
+
+    /// Set the IPv4 address of the remote tunnel endpoint (equivalent to `ip
+    /// link add NAME type geneve id VNI remote ADDR`)
+    pub fn remote(mut self, addr: Ipv4Addr) -> Self {
+        self.info_data.push(InfoGeneve::Remote(addr));
+        self
+    }
+
+    /// Set the IPv6 address of the remote tunnel endpoint (equivalent to `ip
+    /// link add NAME type geneve id VNI remote ADDR`)
+    pub fn remote6(mut self, addr: Ipv6Addr) -> Self {
+        self.info_data.push(InfoGeneve::Remote6(addr));
+        self
+    }
+
+    /// Set the UDP destination port (equivalent to `ip link add NAME type
+    /// geneve id VNI dstport PORT`)
+    pub fn port(mut self, port: u16) -> Self {
+        self.info_data.push(InfoGeneve::Port(port));
+        self
+    }
+
+    /// Set the TTL of the outgoing packets (equivalent to `ip link add NAME
+    /// type geneve id VNI ttl TTL`)
+    pub fn ttl(mut self, ttl: u8) -> Self {
+        self.info_data.push(InfoGeneve::Ttl(ttl));
+        self
+    }
+
+    /// Copy the TTL of the encapsulated packets to the outgoing ones
+    /// (equivalent to `ip link add NAME type geneve id VNI ttl inherit`)
+    pub fn ttl_inherit(mut self) -> Self {
+        self.info_data.push(InfoGeneve::TtlInherit(true));
+        self
+    }
+
+    /// Set the TOS of the outgoing packets (equivalent to `ip link add NAME
+    /// type geneve id VNI tos TOS`)
+    pub fn tos(mut self, tos: u8) -> Self {
+        self.info_data.push(InfoGeneve::Tos(tos));
+        self
+    }
+
+    /// Copy the TOS of the encapsulated packets to the outgoing ones
+    /// (equivalent to `ip link add NAME type geneve id VNI tos inherit`)
+    pub fn tos_inherit(self) -> Self {
+        // The kernel reads the reserved TOS value 1 as "inherit"
+        self.tos(1)
+    }
+
+    /// Set the "Don't Fragment" bit of the outgoing IPv4 packets
+    /// (equivalent to `ip link add NAME type geneve id VNI df
+    /// set/unset/inherit`)
+    pub fn df(mut self, df: GeneveDf) -> Self {
+        self.info_data.push(InfoGeneve::Df(df.into()));
+        self
+    }
+
+    /// Enable or disable the UDP checksum over IPv4 (equivalent to `ip link
+    /// add NAME type geneve id VNI [no]udpcsum`)
+    pub fn udp_csum(mut self, enable: bool) -> Self {
+        self.info_data.push(InfoGeneve::UdpCsum(enable));
+        self
+    }
+
+    /// Skip (or compute) the UDP checksum of the packets sent over IPv6
+    /// (equivalent to `ip link add NAME type geneve id VNI
+    /// [no]udp6zerocsumtx`)
+    pub fn udp_zero_csum6_tx(mut self, enable: bool) -> Self {
+        self.info_data.push(InfoGeneve::UdpZeroCsum6Tx(enable));
+        self
+    }
+
+    /// Accept (or not) the packets received over IPv6 without UDP checksum
+    /// (equivalent to `ip link add NAME type geneve id VNI
+    /// [no]udp6zerocsumrx`)
+    pub fn udp_zero_csum6_rx(mut self, enable: bool) -> Self {
+        self.info_data.push(InfoGeneve::UdpZeroCsum6Rx(enable));
+        self
+    }
+
+    /// Set the flow label of the packets sent over IPv6, on 20 bits
+    /// (equivalent to `ip link add NAME type geneve id VNI flowlabel LABEL`)
+    pub fn label(mut self, label: u32) -> Self {
+        self.info_data.push(InfoGeneve::Label(label));
+        self
+    }
+
+    /// Create a flow based tunnel, whose VNI and remote endpoint are given
+    /// per packet by tc, BPF or Open vSwitch (equivalent to `ip link add
+    /// NAME type geneve external`). The kernel then requires the VNI to be 0,
+    /// and no remote endpoint.
+    pub fn collect_metadata(mut self) -> Self {
+        self.info_data.push(InfoGeneve::CollectMetadata);
+        self
+    }
+}
+
+/// A request to create a new GRE link, either a `gre` (layer 3) or a
+/// `gretap` (Ethernet) one.
+/// This is equivalent to `ip link add NAME type gre ...` commands.
+pub struct GreAddRequest {
+    request: LinkAddRequest,
+    kind: InfoKind,
+    info_data: Vec<InfoGre>,
+    iflags: u16,
+    oflags: u16,
+    encap_flags: u16,
+}
+
+impl GreAddRequest {
+    /// Execute the request.
+    pub async fn execute(self) -> Result<(), Error> {
+        let GreAddRequest {
+            request,
+            kind,
+            mut info_data,
+            iflags,
+            oflags,
+            encap_flags,
+        } = self;
+        if iflags != 0 {
+            info_data.push(InfoGre::IFlags(iflags));
+        }
+        if oflags != 0 {
+            info_data.push(InfoGre::OFlags(oflags));
+        }
+        if encap_flags != 0 {
+            info_data.push(InfoGre::EncapFlags(encap_flags));
+        }
+        let data = encode(&info_data);
+        let data = match kind {
+            InfoKind::GreTap => InfoData::GreTap(data),
+            _ => InfoData::GreTun(data),
+        };
+        request.link_info(kind, Some(data)).execute().await
+    }
+
+    /// Set the address of the local tunnel endpoint (equivalent to `ip link
+    /// add NAME type gre local ADDR`)
+    pub fn local(mut self, addr: Ipv4Addr) -> Self {
+        self.info_data.push(InfoGre::Local(addr));
+        self
+    }
+
+    /// Set the address of the remote tunnel endpoint (equivalent to `ip link
+    /// add NAME type gre remote ADDR`)
+    pub fn remote(mut self, addr: Ipv4Addr) -> Self {
+        self.info_data.push(InfoGre::Remote(addr));
+        self
+    }
+
+    /// Set the key of both directions (equivalent to `ip link add NAME type
+    /// gre key KEY`)
+    pub fn key(self, key: u32) -> Self {
+        self.ikey(key).okey(key)
+    }
+
+    /// Set the key of the incoming packets (equivalent to `ip link add NAME
+    /// type gre ikey KEY`)
+    pub fn ikey(mut self, key: u32) -> Self {
+        self.info_data.push(InfoGre::IKey(key));
+        self.iflags |= GRE_KEY;
+        self
+    }
+
+    /// Set the key of the outgoing packets (equivalent to `ip link add NAME
+    /// type gre okey KEY`)
+    pub fn okey(mut self, key: u32) -> Self {
+        self.info_data.push(InfoGre::OKey(key));
+        self.oflags |= GRE_KEY;
+        self
+    }
+
+    /// Add GRE flags to the incoming packets, [`crate::GRE_CSUM`] or
+    /// [`crate::GRE_SEQ`] (equivalent to `ip link add NAME type gre icsum
+    /// iseq`). The incoming packets without them are dropped.
+    pub fn iflags(mut self, flags: u16) -> Self {
+        self.iflags |= flags;
+        self
+    }
+
+    /// Add GRE flags to the outgoing packets, [`crate::GRE_CSUM`] or
+    /// [`crate::GRE_SEQ`] (equivalent to `ip link add NAME type gre ocsum
+    /// oseq`)
+    pub fn oflags(mut self, flags: u16) -> Self {
+        self.oflags |= flags;
+        self
+    }
+
+    /// Enable or disable the path MTU discovery, which sets the "Don't
+    /// Fragment" bit of the outgoing packets. It is enabled by default, and
+    /// can only be disabled with a fixed TTL (equivalent to `ip link add
+    /// NAME type gre [no]pmtudisc`).
+    pub fn pmtudisc(mut self, enable: bool) -> Self {
+        self.info_data.push(InfoGre::PMtuDisc(enable));
+        self
+    }
+
+    /// Encapsulate the packets in UDP, sent to the port `dport` (equivalent
+    /// to `ip link add NAME type gre encap fou encap-sport SPORT encap-dport
+    /// DPORT`). The source port is chosen per flow when `sport` is 0.
+    pub fn encap(mut self, encap: TunnelEncap, sport: u16, dport: u16) -> Self {
+        self.info_data.extend([
+            InfoGre::EncapType(encap.into()),
+            InfoGre::EncapSport(sport),
+            InfoGre::EncapDport(dport),
+        ]);
+        self
+    }
+
+    /// Compute the UDP checksum of the encapsulated packets (equivalent to
+    /// `ip link add NAME type gre encap fou encap-csum`)
+    pub fn encap_csum(mut self) -> Self {
+        self.encap_flags |= TUNNEL_ENCAP_FLAG_CSUM;
+        self
+    }
+
+    /// Enable the remote checksum offload of the encapsulated packets, with
+    /// GUE (equivalent to `ip link add NAME type gre encap gue
+    /// encap-remcsum`)
+    pub fn encap_remcsum(mut self) -> Self {
+        self.encap_flags |= TUNNEL_ENCAP_FLAG_REMCSUM;
+        self
+    }
+
+    /// Set the TTL of the outgoing packets, 0 meaning inherit (equivalent to
+    /// `ip link add NAME type gre ttl TTL`)
+    pub fn ttl(mut self, ttl: u8) -> Self {
+        self.info_data.push(InfoGre::Ttl(ttl));
+        self
+    }
+
+    /// Set the TOS of the outgoing packets, 1 meaning inherit (equivalent to
+    /// `ip link add NAME type gre tos TOS`)
+    pub fn tos(mut self, tos: u8) -> Self {
+        self.info_data.push(InfoGre::Tos(tos));
+        self
+    }
+
+    /// Bind the tunnel to the link with the given index (equivalent to `ip
+    /// link add NAME type gre dev LINK`)
+    pub fn link(mut self, index: u32) -> Self {
+        self.info_data.push(InfoGre::Link(index));
+        self
+    }
+
+    /// Create a flow based tunnel, whose endpoints and key are given per
+    /// packet by tc, BPF or Open vSwitch (equivalent to `ip link add NAME
+    /// type gre external`)
+    pub fn collect_metadata(mut self) -> Self {
+        self.info_data.push(InfoGre::CollectMetadata);
+        self
+    }
+}
+
+/// A request to create a new IP in IP tunnel, either an `ipip` (IPv4 over
+/// IPv4) or a `sit` (IPv6 over IPv4) one.
+/// This is equivalent to `ip link add NAME type ipip ...` commands.
+pub struct IpTunAddRequest {
+    request: LinkAddRequest,
+    kind: InfoKind,
+    info_data: Vec<InfoIpTun>,
+}
+
+impl IpTunAddRequest {
+    /// Execute the request. An [`Error::InvalidRequest`] is returned if a
+    /// 6rd prefix is set on an `ipip` tunnel.
+    pub async fn execute(self) -> Result<(), Error> {
+        let data = encode(&self.info_data);
+        let data = match self.kind {
+            InfoKind::SitTun => InfoData::SitTun(data),
+            _ => {
+                if self.info_data.iter().any(|nla| {
+                    matches!(
+                        nla,
+                        InfoIpTun::Ip6rdPrefix(_)
+                            | InfoIpTun::Ip6rdRelayPrefix(_)
+                    )
+                }) {
+                    return Err(Error::InvalidRequest(
+                        "6rd prefixes are only supported by SIT tunnels".into(),
+                    ));
+                }
+                InfoData::IpTun(data)
+            }
+        };
+        self.request
+            .link_info(self.kind, Some(data))
+            .execute()
+            .await
+    }
+
+    /// Set the address of the local tunnel endpoint (equivalent to `ip link
+    /// add NAME type ipip local ADDR`)
+    pub fn local(mut self, addr: Ipv4Addr) -> Self {
+        self.info_data.push(InfoIpTun::Local(addr));
+        self
+    }
+
+    /// Set the address of the remote tunnel endpoint (equivalent to `ip link
+    /// add NAME type ipip remote ADDR`)
+    pub fn remote(mut self, addr: Ipv4Addr) -> Self {
+        self.info_data.push(InfoIpTun::Remote(addr));
+        self
+    }
+
+    /// Set the TTL of the outgoing packets, 0 meaning inherit (equivalent to
+    /// `ip link add NAME type ipip ttl TTL`)
+    pub fn ttl(mut self, ttl: u8) -> Self {
+        self.info_data.push(InfoIpTun::Ttl(ttl));
+        self
+    }
+
+    /// Set the TOS of the outgoing packets, 1 meaning inherit (equivalent to
+    /// `ip link add NAME type ipip tos TOS`)
+    pub fn tos(mut self, tos: u8) -> Self {
+        self.info_data.push(InfoIpTun::Tos(tos));
+        self
+    }
+
+    /// Bind the tunnel to the link with the given index (equivalent to `ip
+    /// link add NAME type ipip dev LINK`)
+    pub fn link(mut self, index: u32) -> Self {
+        self.info_data.push(InfoIpTun::Link(index));
+        self
+    }
+
+    /// Enable or disable the path MTU discovery, which sets the "Don't
+    /// Fragment" bit of the outgoing packets. It is enabled by default, and
+    /// can only be disabled with a fixed TTL (equivalent to `ip link add
+    /// NAME type ipip [no]pmtudisc`).
+    pub fn pmtudisc(mut self, enable: bool) -> Self {
+        self.info_data.push(InfoIpTun::PMtuDisc(enable));
+        self
+    }
+
+    /// Set the IPv6 prefix of a 6rd domain, in which the IPv4 addresses are
+    /// embedded, on a `sit` tunnel (equivalent to `ip link add NAME type sit
+    /// 6rd-prefix PREFIX/PREFIX_LEN`)
+    pub fn ip6rd_prefix(mut self, prefix: Ipv6Addr, prefix_len: u16) -> Self {
+        self.info_data.extend([
+            InfoIpTun::Ip6rdPrefix(prefix),
+            InfoIpTun::Ip6rdPrefixLen(prefix_len),
+        ]);
+        self
+    }
+
+    /// Set the IPv4 prefix shared by the addresses of a 6rd domain, which
+    /// is not embedded in the IPv6 addresses, on a `sit` tunnel (equivalent
+    /// to `ip link add NAME type sit 6rd-relay_prefix PREFIX/PREFIX_LEN`)
+    pub fn ip6rd_relay_prefix(
+        mut self,
+        prefix: Ipv4Addr,
+        prefix_len: u16,
+    ) -> Self {
+        self.info_data.extend([
+            InfoIpTun::Ip6rdRelayPrefix(prefix),
+            InfoIpTun::Ip6rdRelayPrefixLen(prefix_len),
+        ]);
+        self
+    }
+}
+
+/// A request to create a new IPv6 tunnel (`ip6tnl`), carrying IPv4 or IPv6
+/// packets.
+/// This is equivalent to `ip link add NAME type ip6tnl ...` commands.
+pub struct Ip6TnlAddRequest {
+    request: LinkAddRequest,
+    info_data: Vec<InfoIpTun>,
+    flags: u32,
+}
+
+impl Ip6TnlAddRequest {
+    /// Execute the request.
+    pub async fn execute(self) -> Result<(), Error> {
+        let Ip6TnlAddRequest {
+            request,
+            mut info_data,
+            flags,
+        } = self;
+        if flags != 0 {
+            info_data.push(InfoIpTun::Flags(flags));
+        }
+        request
+            .link_info(
+                InfoKind::Other("ip6tnl".into()),
+                Some(InfoData::Other(encode(&info_data))),
+            )
+            .execute()
+            .await
+    }
+
+    /// Set the packets carried by the tunnel (equivalent to `ip link add
+    /// NAME type ip6tnl mode MODE`)
+    pub fn mode(mut self, mode: Ip6TnlMode) -> Self {
+        self.info_data.push(InfoIpTun::Proto(mode.into()));
+        self
+    }
+
+    /// Set the address of the local tunnel endpoint (equivalent to `ip link
+    /// add NAME type ip6tnl local ADDR`)
+    pub fn local(mut self, addr: Ipv6Addr) -> Self {
+        self.info_data.push(InfoIpTun::Local6(addr));
+        self
+    }
+
+    /// Set the address of the remote tunnel endpoint (equivalent to `ip link
+    /// add NAME type ip6tnl remote ADDR`)
+    pub fn remote(mut self, addr: Ipv6Addr) -> Self {
+        self.info_data.push(InfoIpTun::Remote6(addr));
+        self
+    }
+
+    /// Set the hop limit of the outgoing packets (equivalent to `ip link add
+    /// NAME type ip6tnl hoplimit HOP_LIMIT`)
+    pub fn hop_limit(mut self, hop_limit: u8) -> Self {
+        self.info_data.push(InfoIpTun::Ttl(hop_limit));
+        self
+    }
+
+    /// Set the tunnel encapsulation limit of the outgoing packets, the
+    /// number of nested encapsulations they may go through (equivalent to
+    /// `ip link add NAME type ip6tnl encaplimit LIMIT`)
+    pub fn encap_limit(mut self, limit: u8) -> Self {
+        self.info_data.push(InfoIpTun::EncapLimit(limit));
+        self
+    }
+
+    /// Don't add a tunnel encapsulation limit option to the outgoing packets
+    /// (equivalent to `ip link add NAME type ip6tnl encaplimit none`)
+    pub fn no_encap_limit(mut self) -> Self {
+        self.flags |= IP6_TNL_F_IGN_ENCAP_LIMIT;
+        self
+    }
+
+    /// Set the traffic class and flow label of the outgoing packets, as the
+    /// 28 low bits of the first word of the IPv6 header (equivalent to `ip
+    /// link add NAME type ip6tnl tclass TCLASS flowlabel FLOWLABEL`)
+    pub fn flowinfo(mut self, flowinfo: u32) -> Self {
+        self.info_data.push(InfoIpTun::FlowInfo(flowinfo));
+        self
+    }
+
+    /// Bind the tunnel to the link with the given index (equivalent to `ip
+    /// link add NAME type ip6tnl dev LINK`)
+    pub fn link(mut self, index: u32) -> Self {
+        self.info_data.push(InfoIpTun::Link(index));
+        self
+    }
+}
+
+/// A request to create a new GRE over IPv6 link, either an `ip6gre` (layer
+/// 3) or an `ip6gretap` (Ethernet) one.
+/// This is equivalent to `ip link add NAME type ip6gre ...` commands.
+pub struct Ip6GreAddRequest {
+    request: LinkAddRequest,
+    kind: InfoKind,
+    info_data: Vec<InfoGre>,
+    iflags: u16,
+    oflags: u16,
+    flags: u32,
+}
+
+impl Ip6GreAddRequest {
+    /// Execute the request.
+    pub async fn execute(self) -> Result<(), Error> {
+        let Ip6GreAddRequest {
+            request,
+            kind,
+            mut info_data,
+            iflags,
+            oflags,
+            flags,
+        } = self;
+        if iflags != 0 {
+            info_data.push(InfoGre::IFlags(iflags));
+        }
+        if oflags != 0 {
+            info_data.push(InfoGre::OFlags(oflags));
+        }
+        if flags != 0 {
+            info_data.push(InfoGre::Flags(flags));
+        }
+        let data = encode(&info_data);
+        let data = match kind {
+            InfoKind::GreTap6 => InfoData::GreTap6(data),
+            _ => InfoData::GreTun6(data),
+        };
+        request.link_info(kind, Some(data)).execute().await
+    }
+
+    /// Set the address of the local tunnel endpoint (equivalent to `ip link
+    /// add NAME type ip6gre local ADDR`)
+    pub fn local(mut self, addr: Ipv6Addr) -> Self {
+        self.info_data.push(InfoGre::Local6(addr));
+        self
+    }
+
+    /// Set the address of the remote tunnel endpoint (equivalent to `ip link
+    /// add NAME type ip6gre remote ADDR`)
+    pub fn remote(mut self, addr: Ipv6Addr) -> Self {
+        self.info_data.push(InfoGre::Remote6(addr));
+        self
+    }
+
+    /// Set the key of both directions (equivalent to `ip link add NAME type
+    /// ip6gre key KEY`)
+    pub fn key(mut self, key: u32) -> Self {
+        self.info_data
+            .extend([InfoGre::IKey(key), InfoGre::OKey(key)]);
+        self.iflags |= GRE_KEY;
+        self.oflags |= GRE_KEY;
+        self
+    }
+
+    /// Set the hop limit of the outgoing packets (equivalent to `ip link add
+    /// NAME type ip6gre hoplimit HOP_LIMIT`)
+    pub fn hop_limit(mut self, hop_limit: u8) -> Self {
+        self.info_data.push(InfoGre::Ttl(hop_limit));
+        self
+    }
+
+    /// Set the tunnel encapsulation limit of the outgoing packets (equivalent
+    /// to `ip link add NAME type ip6gre encaplimit LIMIT`)
+    pub fn encap_limit(mut self, limit: u8) -> Self {
+        self.info_data.push(InfoGre::EncapLimit(limit));
+        self
+    }
+
+    /// Don't add a tunnel encapsulation limit option to the outgoing packets
+    /// (equivalent to `ip link add NAME type ip6gre encaplimit none`)
+    pub fn no_encap_limit(mut self) -> Self {
+        self.flags |= IP6_TNL_F_IGN_ENCAP_LIMIT;
+        self
+    }
+
+    /// Set the traffic class and flow label of the outgoing packets, see
+    /// [`Ip6TnlAddRequest::flowinfo`] (equivalent to `ip link add NAME type
+    /// ip6gre tclass TCLASS flowlabel FLOWLABEL`)
+    pub fn flowinfo(mut self, flowinfo: u32) -> Self {
+        self.info_data.push(InfoGre::FlowInfo(flowinfo));
+        self
+    }
+
+    /// Bind the tunnel to the link with the given index (equivalent to `ip
+    /// link add NAME type ip6gre dev LINK`)
+    pub fn link(mut self, index: u32) -> Self {
+        self.info_data.push(InfoGre::Link(index));
+        self
+    }
+}
+
+/// A request to create a new VTI link, either a `vti` (IPv4) or a `vti6`
+/// (IPv6) one, which sends the packets routed to it through the IPsec
+/// policies matching its output key.
+/// This is equivalent to `ip link add NAME type vti ...` commands.
+pub struct VtiAddRequest {
+    request: LinkAddRequest,
+    kind: InfoKind,
+    info_data: Vec<InfoVti>,
+}
+
+impl VtiAddRequest {
+    /// Execute the request. An [`Error::InvalidRequest`] is returned if an
+    /// endpoint is not of the address family of the link.
+    pub async fn execute(self) -> Result<(), Error> {
+        let ipv6 = self.kind != InfoKind::Vti;
+        for nla in &self.info_data {
+            if let InfoVti::Local(address) | InfoVti::Remote(address) = nla {
+                if address.is_ipv6() != ipv6 {
+                    return Err(Error::InvalidRequest(format!(
+                        "{} is not a valid endpoint of a {} link",
+                        address,
+                        if ipv6 { "vti6" } else { "vti" }
+                    )));
+                }
+            }
+        }
+        let data = encode(&self.info_data);
+        let data = if ipv6 {
+            InfoData::Other(data)
+        } else {
+            InfoData::Vti(data)
+        };
+        self.request
+            .link_info(self.kind, Some(data))
+            .execute()
+            .await
+    }
+
+    /// Set the address of the local tunnel endpoint (equivalent to `ip link
+    /// add NAME type vti local ADDR`)
+    pub fn local(mut self, addr: IpAddr) -> Self {
+        self.info_data.push(InfoVti::Local(addr));
+        self
+    }
+
+    /// Set the address of the remote tunnel endpoint (equivalent to `ip link
+    /// add NAME type vti remote ADDR`)
+    pub fn remote(mut self, addr: IpAddr) -> Self {
+        self.info_data.push(InfoVti::Remote(addr));
+        self
+    }
+
+    /// Set the key of both directions (equivalent to `ip link add NAME type
+    /// vti key KEY`)
+    pub fn key(self, key: u32) -> Self {
+        self.ikey(key).okey(key)
+    }
+
+    /// Set the key matching the IPsec policies of the incoming packets
+    /// (equivalent to `ip link add NAME type vti ikey KEY`)
+    pub fn ikey(mut self, key: u32) -> Self {
+        self.info_data.push(InfoVti::IKey(key));
+        self
+    }
+
+    /// Set the key matching the IPsec policies of the outgoing packets
+    /// (equivalent to `ip link add NAME type vti okey KEY`)
+    pub fn okey(mut self, key: u32) -> Self {
+        self.info_data.push(InfoVti::OKey(key));
+        self
+    }
+
+    /// Set the firewall mark of the encapsulated packets (equivalent to `ip
+    /// link add NAME type vti fwmark MARK`)
+    pub fn fwmark(mut self, mark: u32) -> Self {
+        self.info_data.push(InfoVti::FwMark(mark));
+        self
+    }
+
+    /// Bind the tunnel to the link with the given index (equivalent to `ip
+    /// link add NAME type vti dev LINK`)
+    pub fn link(mut self, index: u32) -> Self {
+        self.info_data.push(InfoVti::Link(index));
+        self
+    }
+}
+
+/// A request to create a new gtp link, which carries the packets of mobile
+/// subscribers in GTP-U tunnels.
+/// This is equivalent to `ip link add NAME type gtp ...` commands.
+///
+/// The UDP sockets of the tunnels are created and bound by the caller, and
+/// the PDP contexts are managed through the `gtp` generic netlink family,
+/// which this crate does not implement.
+pub struct GtpAddRequest {
+    request: LinkAddRequest,
+    info_data: Vec<InfoGtp>,
+}
+
+impl GtpAddRequest {
+    /// Execute the request. An [`Error::InvalidRequest`] is returned if
+    /// neither a GTPv0 nor a GTPv1-U socket is set.
+    pub async fn execute(self) -> Result<(), Error> {
+        if !self
+            .info_data
+            .iter()
+            .any(|nla| matches!(nla, InfoGtp::Fd0(_) | InfoGtp::Fd1(_)))
+        {
+            return Err(Error::InvalidRequest(
+                "a gtp link requires a GTPv0 or a GTPv1-U socket".into(),
+            ));
+        }
+        let s = self.request.link_info(
+            InfoKind::Gtp,
+            Some(InfoData::Gtp(encode(&self.info_data))),
+        );
+        s.execute().await
+    }
+
+    /// Set the UDP socket of the GTPv0 tunnels, bound to port 3386
+    pub fn fd0(mut self, fd: RawFd) -> Self {
+        self.info_data.push(InfoGtp::Fd0(fd as u32));
+        self
+    }
+
+    /// Set the UDP socket of the GTPv1-U tunnels, bound to port 2152
+    pub fn fd1(mut self, fd: RawFd) -> Self {
+        self.info_data.push(InfoGtp::Fd1(fd as u32));
+        self
+    }
+
+    /// Set the size of the hash tables of the PDP contexts (equivalent to
+    /// `ip link add NAME type gtp hsize SIZE`)
+    pub fn pdp_hashsize(mut self, size: u32) -> Self {
+        self.info_data.push(InfoGtp::PdpHashsize(size));
+        self
+    }
+
+    /// Set the side of the tunnels, [`GtpRole::Ggsn`] by default (equivalent
+    /// to `ip link add NAME type gtp role { ggsn | sgsn }`)
+    pub fn role(mut self, role: GtpRole) -> Self {
+        self.info_data.push(InfoGtp::Role(role.into()));
+        self
+    }
+}
+
+/// A request to create a new bareudp link, which encapsulates MPLS or IP
+/// packets directly in UDP.
+/// This is equivalent to `ip link add NAME type bareudp dstport PORT
+/// ethertype ETHERTYPE ...` commands.
+///
+/// Bareudp links are always flow based: the remote endpoint is given per
+/// packet by tc, BPF or Open vSwitch, so there is no option for it.
+pub struct BareUdpAddRequest {
+    request: LinkAddRequest,
+    info_data: Vec<InfoBareUdp>,
+}
+
+impl BareUdpAddRequest {
+    /// Execute the request. An [`Error::InvalidRequest`] is returned if the
+    /// multiproto mode is enabled with another ethertype than IPv4 or MPLS
+    /// unicast.
+    pub async fn execute(self) -> Result<(), Error> {
+        let multiproto = self
+            .info_data
+            .iter()
+            .any(|nla| matches!(nla, InfoBareUdp::MultiprotoMode));
+        let ethertype = self.info_data.iter().find_map(|nla| match nla {
+            InfoBareUdp::Ethertype(ethertype) => Some(*ethertype),
+            _ => None,
+        });
+        if let (true, Some(ethertype)) = (multiproto, ethertype) {
+            if ethertype != ETH_P_IP && ethertype != ETH_P_MPLS_UC {
+                return Err(Error::InvalidRequest(format!(
+                    "bareudp multiproto mode is not supported with \
+                     ethertype {:#06x}",
+                    ethertype
+                )));
+            }
+        }
+        let s = self.request.link_info(
+            InfoKind::Other("bareudp".into()),
+            Some(InfoData::Other(encode(&self.info_data))),
+        );
+        s.execute().await
+    }
+
+    /// Set the lowest UDP source port (equivalent to `ip link add NAME type
+    /// bareudp ... srcportmin PORT`)
+    pub fn srcport_min(mut self, port: u16) -> Self {
+        self.info_data.push(InfoBareUdp::SrcPortMin(port));
+        self
+    }
+
+    /// Also handle the multicast MPLS ethertype, or IPv6 when the ethertype
+    /// is IPv4 (equivalent to `ip link add NAME type bareudp ...
+    /// multiproto`)
+    pub fn multiproto_mode(mut self) -> Self {
+        if !self
+            .info_data
+            .iter()
+            .any(|nla| matches!(nla, InfoBareUdp::MultiprotoMode))
+        {
+            self.info_data.push(InfoBareUdp::MultiprotoMode);
+        }
+        self
+    }
+}
+
+/// A request to create a new link. This is equivalent to the `ip link add`
+/// commands.
+///
+/// A few methods for common actions (creating a veth pair, creating a vlan
+/// interface, etc.) are provided, but custom requests can be made using the
+/// [`message_mut()`](#method.message_mut) accessor.
+pub struct LinkAddRequest {
+    handle: Handle,
+    message: LinkMessage,
+    replace: bool,
+}
+
+impl LinkAddRequest {
+    pub(crate) fn new(handle: Handle) -> Self {
+        LinkAddRequest {
+            handle,
+            message: LinkMessage::default(),
+            replace: false,
+        }
+    }
+
+    /// Execute the request. The request is checked before it is sent, and
+    /// an [`Error::InvalidRequest`] is returned for the mistakes the kernel
+    /// would only report as `EINVAL`: invalid interface name, VxLAN without
+    /// VNI or with a VNI above 2^24, VLAN id above 4094 or VLAN priority above
+    /// 7 in a QoS map, more than 16 ARP or NS targets for a bond.
+    pub async fn execute(self) -> Result<(), Error> {
+        validate(&self.message)?;
+        let LinkAddRequest {
+            mut handle,
+            message,
+            replace,
+        } = self;
+        let mut req = NetlinkMessage::from(RtnlMessage::NewLink(message));
+        let replace = if replace { NLM_F_REPLACE } else { NLM_F_EXCL };
+        req.header.flags = NLM_F_REQUEST | NLM_F_ACK | replace | NLM_F_CREATE;
+
+        let mut response = handle.try_request(req)?;
+        while let Some(message) = response.next().await {
+            try_nl!(message?);
+        }
+        Ok(())
+    }
+
+    /// Return a mutable reference to the request message.
+    ///
+    /// # Example
+    ///
+    /// Let's say we want to create a dummy interface.
+    /// By default, the [`dummy()`](#method.dummy) method would create a
+    /// request with the `IFF_UP` link set, so that the interface is up after
+    /// creation. If we want to create a interface that is down by default we
+    /// could call [`down()`](#method.down), or unset the flag ourselves:
+    ///
+    /// ```rust,no_run
+    /// use futures::Future;
+    /// use netlink_packet_route::IFF_UP;
+    /// use rtnetlink::{Handle, new_connection};
+    ///
+    /// async fn run(handle: Handle) -> Result<(), String> {
+    ///     let mut request = handle.link().add().dummy("my-dummy-itf".into());
+    ///     // unset the IFF_UP flag before sending the request
+    ///     request.message_mut().header.flags &= !IFF_UP;
+    ///     request.message_mut().header.change_mask &= !IFF_UP;
+    ///     // send the request
+    ///     request.execute().await.map_err(|e| format!("{}", e))
+    /// }
+    pub fn message_mut(&mut self) -> &mut LinkMessage {
+        &mut self.message
+    }
+
+    /// Create a dummy link.
+    /// This is equivalent to `ip link add NAME type dummy`.
+    pub fn dummy(self, name: String) -> Self {
+        self.name(name).link_info(InfoKind::Dummy, None).up()
+    }
+
+    /// Create an ifb link, to which the traffic of other links can be
+    /// redirected with the `mirred` tc action in order to shape it.
+    /// This is equivalent to `ip link add NAME type ifb`. The link is set
+    /// up, since it drops the redirected traffic otherwise.
+    pub fn ifb(self, name: String) -> Self {
+        self.name(name).link_info(InfoKind::Ifb, None).up()
+    }
+
+    /// Create a VRF device, whose routes are looked up in the given table.
+    /// This is equivalent to `ip link add NAME type vrf table TABLE_ID`. The
+    /// links are enslaved to the VRF with
+    /// [`crate::LinkSetRequest::controller_vrf`].
+    pub fn vrf(self, name: String, table_id: u32) -> Self {
+        self.name(name).link_info(
+            InfoKind::Vrf,
+            Some(InfoData::Vrf(vec![InfoVrf::TableId(table_id)])),
+        )
+    }
+
+    /// Create a WireGuard link.
+    /// This is equivalent to `ip link add NAME type wireguard`. The keys and
+    /// the peers of the link are configured through the `wireguard` generic
+    /// netlink family, which this crate does not implement.
+    pub fn wireguard(self, name: String) -> Self {
+        self.name(name).link_info(InfoKind::Wireguard, None)
+    }
+
+    /// Create a veth pair.
+    /// This is equivalent to `ip link add NAME1 type veth peer name NAME2`,
+    /// it returns a VethAddRequest to further customize the peer.
+    ///
+    /// As before, `peer_name` is the name in the main netlink message, and
+    /// this link is set up, unless [`VethAddRequest::down`] is called. `name`
+    /// is the name of the peer, which the `peer_*` methods of
+    /// [`VethAddRequest`] apply to.
+    ///
+    /// **Breaking change:** this used to return `Self`, it now returns a
+    /// [`VethAddRequest`].
+    pub fn veth(self, name: String, peer_name: String) -> VethAddRequest {
+        let mut peer = LinkMessage::default();
+        // FIXME: we get a -107 (ENOTCONN) (???) when trying to set the peer
+        // up.
+        peer.nlas.push(Nla::IfName(name));
+        VethAddRequest {
+            // iproute2 does not set this one up
+            request: self.name(peer_name).up(),
+            peer,
+        }
+    }
+
+    /// Create VLAN on a link.
+    /// This is equivalent to `ip link add link LINK name NAME type vlan id
+    /// VLAN_ID`, but instead of specifying a link name (`LINK`), we specify
+    /// a link index. It returns a VlanAddRequest to further customize the
+    /// VLAN.
+    ///
+    /// As before, the VLAN is set up, unless [`VlanAddRequest::down`] is
+    /// called.
+    ///
+    /// **Breaking change:** this used to return `Self`, it now returns a
+    /// [`VlanAddRequest`].
+    pub fn vlan(
+        self,
+        name: String,
+        index: u32,
+        vlan_id: u16,
+    ) -> VlanAddRequest {
+        VlanAddRequest {
+            request: self.name(name).append_nla(Nla::Link(index)).up(),
+            info_data: vec![InfoVlan::Id(vlan_id)],
+            ingress_qos: vec![],
+            egress_qos: vec![],
+            flags: VlanFlags::default(),
+        }
+    }
+
+    /// Create VLAN on a link, with the given VLAN protocol: either `0x8100`
+    /// (802.1Q) or `0x88a8` (802.1ad).
+    /// This is equivalent to `ip link add link LINK name NAME type vlan
+    /// protocol PROTOCOL id VLAN_ID`.
+    ///
+    /// Stacked 802.1ad (QinQ) VLANs are made of an 802.1ad S-VLAN on the
+    /// physical link, and of 802.1Q C-VLANs created with [`Self::vlan`] on
+    /// the S-VLAN link.
+    pub fn vlan_with_protocol(
+        self,
+        name: String,
+        index: u32,
+        vlan_id: u16,
+        protocol: u16,
+    ) -> VlanAddRequest {
+        self.vlan(name, index, vlan_id).protocol(protocol)
+    }
+
+    /// Create macvlan on a link.
+    /// This is equivalent to `ip link add name NAME link LINK type macvlan mode
+    /// MACVLAN_MODE`,   but instead of specifying a link name (`LINK`), we
+    /// specify a link index. The MACVLAN_MODE is an integer consisting of
+    /// flags from MACVLAN_MODE (netlink-packet-route/src/rtnl/constants.rs)
+    ///   being: _PRIVATE, _VEPA, _BRIDGE, _PASSTHRU, _SOURCE, which can be
+    /// *combined*. It returns a MacVlanAddRequest to further customize the
+    /// macvlan. The link is set up, unless [`MacVlanAddRequest::down`] is
+    /// called.
+    pub fn macvlan(
+        self,
+        name: String,
+        index: u32,
+        mode: u32,
+    ) -> MacVlanAddRequest {
+        MacVlanAddRequest {
+            request: self.name(name).append_nla(Nla::Link(index)).up(),
+            info_data: vec![InfoMacVlan::Mode(mode)],
+        }
+    }
+
+    /// Create macvtap on a link.
+    /// This is equivalent to `ip link add name NAME link LINK type macvtap mode
+    /// MACVTAP_MODE`,   but instead of specifying a link name (`LINK`), we
+    /// specify a link index. The MACVTAP_MODE is an integer consisting of
+    /// flags from MACVTAP_MODE (netlink-packet-route/src/rtnl/constants.rs)
+    ///   being: _PRIVATE, _VEPA, _BRIDGE, _PASSTHRU, _SOURCE, which can be
+    /// *combined*. It returns a MacVtapAddRequest to further customize the
+    /// macvtap. The link is set up, unless [`MacVtapAddRequest::down`] is
+    /// called.
+    pub fn macvtap(
+        self,
+        name: String,
+        index: u32,
+        mode: u32,
+    ) -> MacVtapAddRequest {
+        MacVtapAddRequest {
+            request: self.name(name).append_nla(Nla::Link(index)).up(),
+            info_data: vec![InfoMacVtap::Mode(mode)],
+        }
+    }
+
+    /// Create ipvlan on a link.
+    /// This is equivalent to `ip link add name NAME link LINK type ipvlan mode
+    /// MODE`, but instead of specifying a link name (`LINK`), we specify a
+    /// link index. It returns an IpVlanAddRequest to further customize the
+    /// ipvlan. The link is set up, unless [`IpVlanAddRequest::down`] is
+    /// called.
+    pub fn ipvlan(
+        self,
+        name: String,
+        index: u32,
+        mode: IpVlanMode,
+    ) -> IpVlanAddRequest {
+        IpVlanAddRequest {
+            request: self.name(name).append_nla(Nla::Link(index)).up(),
+            info_data: vec![InfoIpVlan::Mode(mode.into())],
+        }
+    }
+
+    /// Create a macsec link on a link.
+    /// This is equivalent to `ip link add link LINK name NAME type macsec`,
+    /// but instead of specifying a link name (`LINK`), we specify a link
+    /// index. It returns a MacSecAddRequest to further customize the macsec
+    /// link.
+    pub fn macsec(self, name: String, index: u32) -> MacSecAddRequest {
+        MacSecAddRequest {
+            request: self.name(name).append_nla(Nla::Link(index)),
+            info_data: vec![],
+        }
+    }
+
+    /// Create a VxLAN
+    /// This is equivalent to `ip link add name NAME type vxlan id VNI`,
+    /// it returns a VxlanAddRequest to further customize the vxlan
+    /// interface creation.
+    pub fn vxlan(self, name: String, vni: u32) -> VxlanAddRequest {
+        let s = self.name(name);
+        VxlanAddRequest {
+            request: s,
+            info_data: vec![InfoVxlan::Id(vni)],
+            flags: vec![],
+        }
+    }
+
+    /// Create a geneve link
+    /// This is equivalent to `ip link add NAME type geneve id VNI`, it
+    /// returns a GeneveAddRequest to further customize the link creation.
+    pub fn geneve(self, name: String, vni: u32) -> GeneveAddRequest {
+        GeneveAddRequest {
+            request: self.name(name),
+            info_data: vec![InfoGeneve::Id(vni)],
+        }
+    }
+
+    /// Create a GRE tunnel
+    /// This is equivalent to `ip link add NAME type gre`, it returns a
+    /// GreAddRequest to further customize the link creation.
+    pub fn gre(self, name: String) -> GreAddRequest {
+        GreAddRequest {
+            request: self.name(name),
+            kind: InfoKind::GreTun,
+            info_data: vec![],
+            iflags: 0,
+            oflags: 0,
+            encap_flags: 0,
+        }
+    }
+
+    /// Create a GRE tunnel carrying Ethernet frames
+    /// This is equivalent to `ip link add NAME type gretap`, it returns a
+    /// GreAddRequest to further customize the link creation.
+    pub fn gretap(self, name: String) -> GreAddRequest {
+        GreAddRequest {
+            request: self.name(name),
+            kind: InfoKind::GreTap,
+            info_data: vec![],
+            iflags: 0,
+            oflags: 0,
+            encap_flags: 0,
+        }
+    }
+
+    /// Create an IPv4 over IPv4 tunnel
+    /// This is equivalent to `ip link add NAME type ipip`, it returns an
+    /// IpTunAddRequest to further customize the link creation.
+    pub fn ipip(self, name: String) -> IpTunAddRequest {
+        IpTunAddRequest {
+            request: self.name(name),
+            kind: InfoKind::IpTun,
+            info_data: vec![],
+        }
+    }
+
+    /// Create an IPv6 over IPv4 tunnel
+    /// This is equivalent to `ip link add NAME type sit`, it returns an
+    /// IpTunAddRequest to further customize the link creation.
+    pub fn sit(self, name: String) -> IpTunAddRequest {
+        IpTunAddRequest {
+            request: self.name(name),
+            kind: InfoKind::SitTun,
+            info_data: vec![],
+        }
+    }
+
+    /// Create an IPv6 tunnel
+    /// This is equivalent to `ip link add NAME type ip6tnl`, it returns an
+    /// Ip6TnlAddRequest to further customize the link creation.
+    pub fn ip6tnl(self, name: String) -> Ip6TnlAddRequest {
+        Ip6TnlAddRequest {
+            request: self.name(name),
+            info_data: vec![],
+            flags: 0,
+        }
+    }
+
+    /// Create a GRE over IPv6 tunnel
+    /// This is equivalent to `ip link add NAME type ip6gre`, it returns an
+    /// Ip6GreAddRequest to further customize the link creation.
+    pub fn ip6gre(self, name: String) -> Ip6GreAddRequest {
+        Ip6GreAddRequest {
+            request: self.name(name),
+            kind: InfoKind::GreTun6,
+            info_data: vec![],
+            iflags: 0,
+            oflags: 0,
+            flags: 0,
+        }
+    }
+
+    /// Create a GRE over IPv6 tunnel carrying Ethernet frames
+    /// This is equivalent to `ip link add NAME type ip6gretap`, it returns an
+    /// Ip6GreAddRequest to further customize the link creation.
+    pub fn ip6gretap(self, name: String) -> Ip6GreAddRequest {
+        Ip6GreAddRequest {
+            request: self.name(name),
+            kind: InfoKind::GreTap6,
+            info_data: vec![],
+            iflags: 0,
+            oflags: 0,
+            flags: 0,
+        }
+    }
+
+    /// Create a VTI link over IPv4
+    /// This is equivalent to `ip link add NAME type vti`, it returns a
+    /// VtiAddRequest to further customize the link creation.
+    pub fn vti(self, name: String) -> VtiAddRequest {
+        VtiAddRequest {
+            request: self.name(name),
+            kind: InfoKind::Vti,
+            info_data: vec![],
+        }
+    }
+
+    /// Create a VTI link over IPv6
+    /// This is equivalent to `ip link add NAME type vti6`, it returns a
+    /// VtiAddRequest to further customize the link creation.
+    pub fn vti6(self, name: String) -> VtiAddRequest {
+        VtiAddRequest {
+            request: self.name(name),
+            kind: InfoKind::Other("vti6".into()),
+            info_data: vec![],
+        }
+    }
+
+    /// Create a gtp link
+    /// This is equivalent to `ip link add NAME type gtp`, it returns a
+    /// GtpAddRequest to set the sockets of the tunnels and further customize
+    /// the link.
+    pub fn gtp(self, name: String) -> GtpAddRequest {
+        GtpAddRequest {
+            request: self.name(name),
+            info_data: vec![],
+        }
+    }
+
+    /// Create a bareudp link
+    /// This is equivalent to `ip link add NAME type bareudp dstport PORT
+    /// ethertype ETHERTYPE`, it returns a BareUdpAddRequest to further
+    /// customize the link creation.
+    pub fn bareudp(
+        self,
+        name: String,
+        port: u16,
+        ethertype: u16,
+    ) -> BareUdpAddRequest {
+        BareUdpAddRequest {
+            request: self.name(name),
+            info_data: vec![
+                InfoBareUdp::Port(port),
+                InfoBareUdp::Ethertype(ethertype),
+            ],
+        }
     }
 
     /// Create xfrm tunnel
@@ -687,11 +2439,13 @@ impl LinkAddRequest {
     }
 
     /// Create a new bridge.
-    /// This is equivalent to `ip link add link NAME type bridge`.
-    pub fn bridge(self, name: String) -> Self {
-        self.name(name.clone())
-            .link_info(InfoKind::Bridge, None)
-            .append_nla(Nla::IfName(name))
+    /// This is equivalent to `ip link add link NAME type bridge`, it returns
+    /// a BridgeAddRequest to further customize the bridge.
+    pub fn bridge(self, name: String) -> BridgeAddRequest {
+        BridgeAddRequest {
+            request: self.name(name.clone()).append_nla(Nla::IfName(name)),
+            info_data: vec![],
+        }
     }
 
     /// Replace existing matching link.
@@ -702,12 +2456,61 @@ impl LinkAddRequest {
         }
     }
 
-    fn up(mut self) -> Self {
-        self.message.header.flags = IFF_UP;
-        self.message.header.change_mask = IFF_UP;
+    /// Set the number of transmit queues of the new link, for the kinds
+    /// supporting several ones such as veth (equivalent to `ip link add NAME
+    /// numtxqueues COUNT type ...`).
+    pub fn num_tx_queues(self, count: u32) -> Self {
+        self.append_nla(Nla::NumTxQueues(count))
+    }
+
+    /// Set the number of receive queues of the new link (equivalent to `ip
+    /// link add NAME numrxqueues COUNT type ...`), see
+    /// [`Self::num_tx_queues`].
+    pub fn num_rx_queues(self, count: u32) -> Self {
+        self.append_nla(Nla::NumRxQueues(count))
+    }
+
+    /// Set the MTU of the new link (equivalent to `ip link add NAME mtu MTU
+    /// type ...`), so that it is created with it instead of being changed
+    /// afterwards.
+    pub fn mtu(self, mtu: u32) -> Self {
+        self.append_nla(Nla::Mtu(mtu))
+    }
+
+    /// Set the hardware address of the new link (equivalent to `ip link add
+    /// NAME address ADDRESS type ...`)
+    pub fn address(self, address: Vec<u8>) -> Self {
+        self.append_nla(Nla::Address(address))
+    }
+
+    /// Set the Ethernet address of the new link (equivalent to `ip link add
+    /// NAME address ADDRESS type ...`)
+    pub fn mac_address(self, address: MacAddress) -> Self {
+        self.address(address.into())
+    }
+
+    /// Set the new link up (equivalent to `ip link add NAME up type ...`).
+    pub fn up(mut self) -> Self {
+        self.set_up(true);
+        self
+    }
+
+    /// Create the new link down, for the kinds which are set up by default
+    /// such as dummy or ifb links.
+    pub fn down(mut self) -> Self {
+        self.set_up(false);
         self
     }
 
+    fn set_up(&mut self, up: bool) {
+        if up {
+            self.message.header.flags |= IFF_UP;
+        } else {
+            self.message.header.flags &= !IFF_UP;
+        }
+        self.message.header.change_mask |= IFF_UP;
+    }
+
     fn link_info(self, kind: InfoKind, data: Option<InfoData>) -> Self {
         let mut link_info_nlas = vec![Info::Kind(kind)];
         if let Some(data) = data {
@@ -726,3 +2529,875 @@ impl LinkAddRequest {
         self
     }
 }
+
+// The settings shared by the requests creating a link of a given kind,
+// which are applied to the `LinkAddRequest` they wrap
+macro_rules! impl_link_add_settings {
+    ($($request:ty),*) => {
+        $(
+            impl $request {
+                /// Set the link up (equivalent to `ip link add NAME up type
+                /// ...`).
+                pub fn up(mut self) -> Self {
+                    self.request = self.request.up();
+                    self
+                }
+
+                /// Create the link down. Only the veth, vlan, macvlan,
+                /// macvtap and ipvlan links are set up by default, the other
+                /// kinds are created down unless [`Self::up`] is called.
+                pub fn down(mut self) -> Self {
+                    self.request = self.request.down();
+                    self
+                }
+
+                /// Set the MTU of the link (equivalent to `ip link add NAME
+                /// mtu MTU type ...`)
+                pub fn mtu(mut self, mtu: u32) -> Self {
+                    self.request = self.request.mtu(mtu);
+                    self
+                }
+
+                /// Set the hardware address of the link (equivalent to `ip
+                /// link add NAME address ADDRESS type ...`)
+                pub fn address(mut self, address: Vec<u8>) -> Self {
+                    self.request = self.request.address(address);
+                    self
+                }
+
+                /// Set the Ethernet address of the link (equivalent to `ip
+                /// link add NAME address ADDRESS type ...`)
+                pub fn mac_address(mut self, address: MacAddress) -> Self {
+                    self.request = self.request.mac_address(address);
+                    self
+                }
+
+                /// Set the number of transmit queues of the link (equivalent
+                /// to `ip link add NAME numtxqueues COUNT type ...`)
+                pub fn num_tx_queues(mut self, count: u32) -> Self {
+                    self.request = self.request.num_tx_queues(count);
+                    self
+                }
+
+                /// Set the number of receive queues of the link (equivalent
+                /// to `ip link add NAME numrxqueues COUNT type ...`)
+                pub fn num_rx_queues(mut self, count: u32) -> Self {
+                    self.request = self.request.num_rx_queues(count);
+                    self
+                }
+            }
+        )*
+    };
+}
+
+impl_link_add_settings!(
+    BondAddRequest,
+    BridgeAddRequest,
+    VethAddRequest,
+    VlanAddRequest,
+    VxlanAddRequest,
+    MacVlanAddRequest,
+    MacVtapAddRequest,
+    IpVlanAddRequest,
+    MacSecAddRequest,
+    GeneveAddRequest,
+    GreAddRequest,
+    IpTunAddRequest,
+    Ip6TnlAddRequest,
+    Ip6GreAddRequest,
+    VtiAddRequest,
+    GtpAddRequest,
+    BareUdpAddRequest
+);
+
+fn validate(message: &LinkMessage) -> Result<(), Error> {
+    for nla in &message.nlas {
+        match nla {
+            Nla::IfName(name) => validate_name(name)?,
+            Nla::Info(infos) => {
+                for info in infos {
+                    match info {
+                        Info::Data(InfoData::Vxlan(nlas)) => {
+                            validate_vxlan(nlas)?
+                        }
+                        Info::Data(InfoData::Vlan(nlas)) => {
+                            validate_vlan(nlas)?
+                        }
+                        Info::Data(InfoData::Bond(nlas)) => {
+                            validate_bond(nlas)?
+                        }
+                        Info::Data(InfoData::Veth(VethInfo::Peer(peer))) => {
+                            validate(peer)?
+                        }
+                        _ => (),
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+    Ok(())
+}
+
+fn validate_vxlan(nlas: &[InfoVxlan]) -> Result<(), Error> {
+    match nlas.iter().find_map(|nla| match nla {
+        InfoVxlan::Id(vni) => Some(*vni),
+        _ => None,
+    }) {
+        None => Err(Error::InvalidRequest("missing VxLAN VNI".into())),
+        Some(vni) if vni > VXLAN_VNI_MAX => Err(Error::InvalidRequest(
+            format!("VxLAN VNI {} is out of range", vni),
+        )),
+        Some(_) => Ok(()),
+    }
+}
+
+fn validate_vlan(nlas: &[InfoVlan]) -> Result<(), Error> {
+    for nla in nlas {
+        if let InfoVlan::Id(id) = nla {
+            if *id > VLAN_ID_MAX {
+                return Err(Error::InvalidRequest(format!(
+                    "VLAN id {} is out of range",
+                    id
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn validate_bond(nlas: &[InfoBond]) -> Result<(), Error> {
+    for nla in nlas {
+        let (targets, count) = match nla {
+            InfoBond::ArpIpTarget(targets) => ("ARP", targets.len()),
+            InfoBond::NsIp6Target(targets) => ("NS", targets.len()),
+            _ => continue,
+        };
+        if count > BOND_MAX_TARGETS {
+            return Err(Error::InvalidRequest(format!(
+                "{} bond {} targets, at most {} are supported",
+                count, targets, BOND_MAX_TARGETS
+            )));
+        }
+    }
+    Ok(())
+}
+
+// Interface names are at most 15 bytes long, and cannot contain `/`, `:` or
+// whitespaces (see `dev_valid_name()` in the kernel).
+fn validate_name(name: &str) -> Result<(), Error> {
+    if name.is_empty()
+        || name.len() >= IFNAMSIZ
+        || name == "."
+        || name == ".."
+        || name
+            .chars()
+            .any(|c| c == '/' || c == ':' || c.is_whitespace())
+    {
+        return Err(Error::InvalidRequest(format!(
+            "invalid interface name {:?}",
+            name
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use netlink_packet_route::MACVLAN_MODE_BRIDGE;
+
+    use super::*;
+    use crate::{
+        link::test::{dry_run_link, info_data},
+        GRE_CSUM,
+    };
+
+    #[test]
+    fn test_wireguard() {
+        let message = dry_run_link(|handle| {
+            handle.link().add().wireguard("wg0".into()).execute()
+        })
+        .unwrap();
+        assert!(message.nlas.contains(&Nla::IfName("wg0".into())));
+        assert!(message
+            .nlas
+            .contains(&Nla::Info(vec![Info::Kind(InfoKind::Wireguard)])));
+    }
+
+    #[test]
+    fn test_veth_peer_settings() {
+        let res = dry_run_link(|handle| {
+            handle
+                .link()
+                .add()
+                .veth("veth:peer".into(), "veth0".into())
+                .execute()
+        });
+        assert!(matches!(res, Err(Error::InvalidRequest(_))));
+
+        let address = vec![0x02, 0, 0, 0, 0x12, 0x34];
+        let message = dry_run_link(|handle| {
+            handle
+                .link()
+                .add()
+                .veth("veth1".into(), "veth0".into())
+                .peer_mtu(1400)
+                .peer_address(address.clone())
+                .execute()
+        })
+        .unwrap();
+        assert!(message.nlas.contains(&Nla::IfName("veth0".into())));
+        assert_eq!(message.header.flags, IFF_UP);
+        match info_data(&message) {
+            InfoData::Veth(VethInfo::Peer(peer)) => assert_eq!(
+                peer.nlas,
+                vec![
+                    Nla::IfName("veth1".into()),
+                    Nla::Mtu(1400),
+                    Nla::Address(address),
+                ]
+            ),
+            data => panic!("unexpected info data {:?}", data),
+        }
+    }
+
+    #[test]
+    fn test_up_and_down() {
+        let message = dry_run_link(|handle| {
+            handle
+                .link()
+                .add()
+                .veth("veth0".into(), "veth1".into())
+                .execute()
+        })
+        .unwrap();
+        assert_eq!(message.header.flags, IFF_UP);
+        assert_eq!(message.header.change_mask, IFF_UP);
+
+        let message = dry_run_link(|handle| {
+            handle.link().add().vlan("vlan0".into(), 1, 10).execute()
+        })
+        .unwrap();
+        assert_eq!(message.header.flags, IFF_UP);
+
+        let message = dry_run_link(|handle| {
+            handle
+                .link()
+                .add()
+                .vlan("vlan0".into(), 1, 10)
+                .down()
+                .mtu(1400)
+                .execute()
+        })
+        .unwrap();
+        assert_eq!(message.header.flags, 0);
+        assert_eq!(message.header.change_mask, IFF_UP);
+        assert!(message.nlas.contains(&Nla::Mtu(1400)));
+
+        let message = dry_run_link(|handle| {
+            handle.link().add().bridge("br0".into()).up().execute()
+        })
+        .unwrap();
+        assert_eq!(message.header.flags, IFF_UP);
+
+        let message = dry_run_link(|handle| {
+            handle.link().add().dummy("dummy0".into()).down().execute()
+        })
+        .unwrap();
+        assert_eq!(message.header.flags, 0);
+    }
+
+    #[test]
+    fn test_invalid_requests_are_not_sent() {
+        let res = dry_run_link(|handle| {
+            handle
+                .link()
+                .add()
+                .vxlan("vxlan0".into(), 1 << 24)
+                .execute()
+        });
+        assert!(matches!(res, Err(Error::InvalidRequest(_))));
+        let res = dry_run_link(|handle| {
+            handle
+                .link()
+                .add()
+                .dummy("a-very-long-name0".into())
+                .execute()
+        });
+        assert!(matches!(res, Err(Error::InvalidRequest(_))));
+
+        let message = dry_run_link(|handle| {
+            handle.link().add().dummy("dummy0".into()).execute()
+        })
+        .unwrap();
+        assert!(message.nlas.contains(&Nla::IfName("dummy0".into())));
+    }
+
+    #[test]
+    fn test_vlan_flags_and_qos_maps() {
+        let res = dry_run_link(|handle| {
+            handle
+                .link()
+                .add()
+                .vlan("vlan0".into(), 4242, 100)
+                .egress_qos_map(1, 8)
+                .execute()
+        });
+        assert!(matches!(res, Err(Error::InvalidRequest(_))));
+
+        let message = dry_run_link(|handle| {
+            handle
+                .link()
+                .add()
+                .vlan("vlan0".into(), 4242, 100)
+                .ingress_qos_map(3, 5)
+                .reorder_hdr(false)
+                .mvrp(true)
+                .execute()
+        })
+        .unwrap();
+        let mut data = encode(&[
+            InfoVlan::Id(100),
+            InfoVlan::IngressQos(encode(&[VlanQosMapping { from: 3, to: 5 }])),
+        ]);
+        data.extend(encode(&[VlanFlags {
+            flags: VLAN_FLAG_MVRP,
+            mask: VLAN_FLAG_MVRP | VLAN_FLAG_REORDER_HDR,
+        }]));
+        assert_eq!(info_data(&message), &InfoData::Other(data));
+    }
+
+    #[test]
+    fn test_macsec_options() {
+        let res = dry_run_link(|handle| {
+            handle
+                .link()
+                .add()
+                .macsec("macsec0".into(), 4242)
+                .sci(1)
+                .port(1)
+                .execute()
+        });
+        assert!(matches!(res, Err(Error::InvalidRequest(_))));
+
+        let message = dry_run_link(|handle| {
+            handle
+                .link()
+                .add()
+                .macsec("macsec0".into(), 4242)
+                .port(11)
+                .cipher(MacSecCipherId::GcmAes256)
+                .encrypt(true)
+                .replay_window(32)
+                .offload(MacSecOffload::Off)
+                .execute()
+        })
+        .unwrap();
+        assert!(message.nlas.contains(&Nla::Link(4242)));
+        assert_eq!(
+            info_data(&message),
+            &InfoData::Other(encode(&[
+                InfoMacSec::Port(11),
+                InfoMacSec::CipherSuite(MacSecCipherId::GcmAes256),
+                InfoMacSec::Encrypt(1),
+                InfoMacSec::ReplayProtect(1),
+                InfoMacSec::Window(32),
+                InfoMacSec::Offload(MacSecOffload::Off),
+            ]))
+        );
+    }
+
+    #[test]
+    fn test_too_many_bond_targets() {
+        let targets: Vec<_> =
+            (1..=17).map(|i| Ipv4Addr::new(192, 0, 2, i)).collect();
+        let res = dry_run_link(|handle| {
+            handle
+                .link()
+                .add()
+                .bond("bond0".into())
+                .arp_ip_target(targets.clone())
+                .execute()
+        });
+        assert!(matches!(res, Err(Error::InvalidRequest(_))));
+
+        let message = dry_run_link(|handle| {
+            handle
+                .link()
+                .add()
+                .bond("bond0".into())
+                .arp_ip_target(targets[..16].to_vec())
+                .execute()
+        })
+        .unwrap();
+        assert_eq!(
+            info_data(&message),
+            &InfoData::Bond(vec![InfoBond::ArpIpTarget(
+                targets[..16].to_vec()
+            )])
+        );
+    }
+
+    #[test]
+    fn test_vlan_protocol() {
+        let message = dry_run_link(|handle| {
+            handle
+                .link()
+                .add()
+                .vlan_with_protocol("svlan0".into(), 4242, 100, 0x88a8)
+                .execute()
+        })
+        .unwrap();
+        assert!(message.nlas.contains(&Nla::Link(4242)));
+        // netlink-packet-route emits the protocol in native endian, so it is
+        // byte swapped in order to be sent in network byte order
+        assert_eq!(
+            info_data(&message),
+            &InfoData::Vlan(vec![
+                InfoVlan::Id(100),
+                InfoVlan::Protocol(0x88a8u16.to_be()),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_vxlan_flag_attributes() {
+        let res = dry_run_link(|handle| {
+            handle
+                .link()
+                .add()
+                .vxlan("vxlan0".into(), 1 << 24)
+                .gbp()
+                .execute()
+        });
+        assert!(matches!(res, Err(Error::InvalidRequest(_))));
+
+        let message = dry_run_link(|handle| {
+            handle
+                .link()
+                .add()
+                .vxlan("vxlan0".into(), 42)
+                .port(4789)
+                .udp_zero_csum6_tx(1)
+                .gbp()
+                .execute()
+        })
+        .unwrap();
+        let mut data = encode(&[
+            InfoVxlan::Id(42),
+            InfoVxlan::Port(4789),
+            InfoVxlan::UDPZeroCsumTX(1),
+        ]);
+        data.extend(encode(&[FlagNla(IFLA_VXLAN_GBP)]));
+        assert_eq!(info_data(&message), &InfoData::Other(data));
+        // IFLA_VXLAN_GBP is sent without value
+        assert_eq!(encode(&[FlagNla(IFLA_VXLAN_GBP)]), vec![4, 0, 23, 0]);
+    }
+
+    #[test]
+    fn test_ipvlan_mode_and_flags() {
+        let message = dry_run_link(|handle| {
+            handle
+                .link()
+                .add()
+                .ipvlan("ipvlan0".into(), 4242, IpVlanMode::L3S)
+                .private()
+                .vepa()
+                .execute()
+        })
+        .unwrap();
+        assert!(message.nlas.contains(&Nla::Link(4242)));
+        // The flags of the last call replace the previous ones
+        assert_eq!(
+            info_data(&message),
+            &InfoData::IpVlan(vec![
+                InfoIpVlan::Mode(2),
+                InfoIpVlan::Flags(IPVLAN_F_VEPA),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_gre_keys_flags_and_encap() {
+        let message = dry_run_link(|handle| {
+            handle
+                .link()
+                .add()
+                .gre("gre0".into())
+                .ikey(1)
+                .oflags(GRE_CSUM)
+                .okey(2)
+                .encap(TunnelEncap::Fou, 0, 5555)
+                .encap_csum()
+                .execute()
+        })
+        .unwrap();
+        assert_eq!(
+            info_data(&message),
+            &InfoData::GreTun(encode(&[
+                InfoGre::IKey(1),
+                InfoGre::OKey(2),
+                InfoGre::EncapType(TunnelEncap::Fou.into()),
+                InfoGre::EncapSport(0),
+                InfoGre::EncapDport(5555),
+                InfoGre::IFlags(GRE_KEY),
+                InfoGre::OFlags(GRE_CSUM | GRE_KEY),
+                InfoGre::EncapFlags(TUNNEL_ENCAP_FLAG_CSUM),
+            ]))
+        );
+    }
+
+    #[test]
+    fn test_sit_6rd_prefixes() {
+        let prefix: Ipv6Addr = "2001:db8::".parse().unwrap();
+        let res = dry_run_link(|handle| {
+            handle
+                .link()
+                .add()
+                .ipip("ipip0".into())
+                .ip6rd_prefix(prefix, 32)
+                .execute()
+        });
+        assert!(matches!(res, Err(Error::InvalidRequest(_))));
+
+        let message = dry_run_link(|handle| {
+            handle
+                .link()
+                .add()
+                .sit("sit0".into())
+                .remote(Ipv4Addr::new(192, 0, 2, 1))
+                .ttl(64)
+                .ip6rd_prefix(prefix, 32)
+                .ip6rd_relay_prefix(Ipv4Addr::new(192, 0, 0, 0), 8)
+                .execute()
+        })
+        .unwrap();
+        assert!(message.nlas.contains(&Nla::Info(vec![
+            Info::Kind(InfoKind::SitTun),
+            Info::Data(InfoData::SitTun(encode(&[
+                InfoIpTun::Remote(Ipv4Addr::new(192, 0, 2, 1)),
+                InfoIpTun::Ttl(64),
+                InfoIpTun::Ip6rdPrefix(prefix),
+                InfoIpTun::Ip6rdPrefixLen(32),
+                InfoIpTun::Ip6rdRelayPrefix(Ipv4Addr::new(192, 0, 0, 0)),
+                InfoIpTun::Ip6rdRelayPrefixLen(8),
+            ]))),
+        ])));
+    }
+
+    #[test]
+    fn test_ip6tnl_and_ip6gre_attributes() {
+        let remote: Ipv6Addr = "2001:db8::1".parse().unwrap();
+        let message = dry_run_link(|handle| {
+            handle
+                .link()
+                .add()
+                .ip6tnl("ip6tnl0".into())
+                .mode(Ip6TnlMode::IpIp6)
+                .remote(remote)
+                .no_encap_limit()
+                .flowinfo(0x12345)
+                .execute()
+        })
+        .unwrap();
+        assert!(message.nlas.contains(&Nla::Info(vec![
+            Info::Kind(InfoKind::Other("ip6tnl".into())),
+            Info::Data(InfoData::Other(encode(&[
+                InfoIpTun::Proto(Ip6TnlMode::IpIp6.into()),
+                InfoIpTun::Remote6(remote),
+                InfoIpTun::FlowInfo(0x12345),
+                InfoIpTun::Flags(IP6_TNL_F_IGN_ENCAP_LIMIT),
+            ]))),
+        ])));
+
+        let message = dry_run_link(|handle| {
+            handle
+                .link()
+                .add()
+                .ip6gretap("ip6gretap0".into())
+                .remote(remote)
+                .key(42)
+                .hop_limit(16)
+                .encap_limit(2)
+                .execute()
+        })
+        .unwrap();
+        assert!(message.nlas.contains(&Nla::Info(vec![
+            Info::Kind(InfoKind::GreTap6),
+            Info::Data(InfoData::GreTap6(encode(&[
+                InfoGre::Remote6(remote),
+                InfoGre::IKey(42),
+                InfoGre::OKey(42),
+                InfoGre::Ttl(16),
+                InfoGre::EncapLimit(2),
+                InfoGre::IFlags(GRE_KEY),
+                InfoGre::OFlags(GRE_KEY),
+            ]))),
+        ])));
+    }
+
+    #[test]
+    fn test_geneve_vni_and_label() {
+        let res = dry_run_link(|handle| {
+            handle
+                .link()
+                .add()
+                .geneve("geneve0".into(), 1 << 24)
+                .execute()
+        });
+        assert!(matches!(res, Err(Error::InvalidRequest(_))));
+
+        let message = dry_run_link(|handle| {
+            handle
+                .link()
+                .add()
+                .geneve("geneve0".into(), 42)
+                .remote6(Ipv6Addr::LOCALHOST)
+                .label(0x12345)
+                .udp_zero_csum6_tx(true)
+                .execute()
+        })
+        .unwrap();
+        assert!(message.nlas.contains(&Nla::Info(vec![
+            Info::Kind(InfoKind::Other("geneve".into())),
+            Info::Data(InfoData::Other(encode(&[
+                InfoGeneve::Id(42),
+                InfoGeneve::Remote6(Ipv6Addr::LOCALHOST),
+                InfoGeneve::Label(0x12345),
+                InfoGeneve::UdpZeroCsum6Tx(true),
+            ]))),
+        ])));
+    }
+
+    #[test]
+    fn test_vti_endpoints_and_keys() {
+        let res = dry_run_link(|handle| {
+            handle
+                .link()
+                .add()
+                .vti6("vti0".into())
+                .remote(Ipv4Addr::new(192, 0, 2, 1).into())
+                .execute()
+        });
+        assert!(matches!(res, Err(Error::InvalidRequest(_))));
+
+        let message = dry_run_link(|handle| {
+            handle
+                .link()
+                .add()
+                .vti("vti0".into())
+                .remote(Ipv4Addr::new(192, 0, 2, 1).into())
+                .ikey(1)
+                .okey(2)
+                .execute()
+        })
+        .unwrap();
+        assert!(message.nlas.contains(&Nla::Info(vec![
+            Info::Kind(InfoKind::Vti),
+            Info::Data(InfoData::Vti(encode(&[
+                InfoVti::Remote(Ipv4Addr::new(192, 0, 2, 1).into()),
+                InfoVti::IKey(1),
+                InfoVti::OKey(2),
+            ]))),
+        ])));
+    }
+
+    #[test]
+    fn test_vrf_table() {
+        let message = dry_run_link(|handle| {
+            handle.link().add().vrf("vrf-blue".into(), 10).execute()
+        })
+        .unwrap();
+        assert!(message.nlas.contains(&Nla::Info(vec![
+            Info::Kind(InfoKind::Vrf),
+            Info::Data(InfoData::Vrf(vec![InfoVrf::TableId(10)])),
+        ])));
+    }
+
+    #[test]
+    fn test_bareudp_multiproto_mode() {
+        let res = dry_run_link(|handle| {
+            handle
+                .link()
+                .add()
+                .bareudp("bareudp0".into(), 6635, 0x86dd)
+                .multiproto_mode()
+                .execute()
+        });
+        assert!(matches!(res, Err(Error::InvalidRequest(_))));
+
+        let message = dry_run_link(|handle| {
+            handle
+                .link()
+                .add()
+                .bareudp("bareudp0".into(), 6635, ETH_P_MPLS_UC)
+                .srcport_min(49153)
+                .multiproto_mode()
+                .multiproto_mode()
+                .execute()
+        })
+        .unwrap();
+        // IFLA_BAREUDP_MULTIPROTO_MODE is only sent once
+        assert_eq!(
+            info_data(&message),
+            &InfoData::Other(encode(&[
+                InfoBareUdp::Port(6635),
+                InfoBareUdp::Ethertype(ETH_P_MPLS_UC),
+                InfoBareUdp::SrcPortMin(49153),
+                InfoBareUdp::MultiprotoMode,
+            ]))
+        );
+    }
+
+    #[test]
+    fn test_gtp_sockets_and_role() {
+        let res = dry_run_link(|handle| {
+            handle
+                .link()
+                .add()
+                .gtp("gtp0".into())
+                .role(GtpRole::Sgsn)
+                .execute()
+        });
+        assert!(matches!(res, Err(Error::InvalidRequest(_))));
+
+        let message = dry_run_link(|handle| {
+            handle
+                .link()
+                .add()
+                .gtp("gtp0".into())
+                .fd1(7)
+                .pdp_hashsize(2048)
+                .role(GtpRole::Sgsn)
+                .execute()
+        })
+        .unwrap();
+        assert!(message.nlas.contains(&Nla::Info(vec![
+            Info::Kind(InfoKind::Gtp),
+            Info::Data(InfoData::Gtp(encode(&[
+                InfoGtp::Fd1(7),
+                InfoGtp::PdpHashsize(2048),
+                InfoGtp::Role(GtpRole::Sgsn.into()),
+            ]))),
+        ])));
+    }
+
+    #[test]
+    fn test_macvlan_bc_queue_len() {
+        let message = dry_run_link(|handle| {
+            handle
+                .link()
+                .add()
+                .macvlan("macvlan0".into(), 4242, MACVLAN_MODE_BRIDGE)
+                .bc_queue_len(2000)
+                .execute()
+        })
+        .unwrap();
+        assert_eq!(
+            info_data(&message),
+            &InfoData::MacVlan(vec![
+                InfoMacVlan::Mode(MACVLAN_MODE_BRIDGE),
+                // IFLA_MACVLAN_BC_QUEUE_LEN
+                InfoMacVlan::Other(DefaultNla::new(
+                    7,
+                    2000u32.to_ne_bytes().to_vec()
+                )),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_bridge_options() {
+        let message = dry_run_link(|handle| {
+            handle
+                .link()
+                .add()
+                .bridge("br0".into())
+                .priority(4096)
+                .ageing_time(1000)
+                .mcast_snooping(false)
+                .execute()
+        })
+        .unwrap();
+        assert!(message.nlas.contains(&Nla::Info(vec![
+            Info::Kind(InfoKind::Bridge),
+            Info::Data(InfoData::Bridge(vec![
+                InfoBridge::Priority(4096),
+                InfoBridge::AgeingTime(1000),
+                InfoBridge::MulticastSnooping(0),
+            ])),
+        ])));
+    }
+
+    #[test]
+    fn test_ifb() {
+        let message = dry_run_link(|handle| {
+            handle.link().add().ifb("ifb0".into()).execute()
+        })
+        .unwrap();
+        assert_eq!(message.header.flags, IFF_UP);
+        assert!(message.nlas.contains(&Nla::IfName("ifb0".into())));
+        assert!(message
+            .nlas
+            .contains(&Nla::Info(vec![Info::Kind(InfoKind::Ifb)])));
+    }
+
+    #[test]
+    fn test_veth_queues() {
+        let message = dry_run_link(|handle| {
+            handle
+                .link()
+                .add()
+                .num_tx_queues(4)
+                .veth("veth1".into(), "veth0".into())
+                .num_rx_queues(2)
+                .peer_num_tx_queues(2)
+                .peer_num_rx_queues(4)
+                .execute()
+        })
+        .unwrap();
+        assert!(message.nlas.contains(&Nla::NumTxQueues(4)));
+        assert!(message.nlas.contains(&Nla::NumRxQueues(2)));
+        match info_data(&message) {
+            InfoData::Veth(VethInfo::Peer(peer)) => {
+                assert!(peer.nlas.contains(&Nla::NumTxQueues(2)));
+                assert!(peer.nlas.contains(&Nla::NumRxQueues(4)));
+            }
+            data => panic!("unexpected info data {:?}", data),
+        }
+    }
+
+    #[test]
+    fn test_mtu_and_address() {
+        let address = MacAddress([0x02, 0, 0, 0, 0x35, 1]);
+        let message = dry_run_link(|handle| {
+            handle
+                .link()
+                .add()
+                .bridge("br0".into())
+                .mtu(1400)
+                .mac_address(address)
+                .up()
+                .execute()
+        })
+        .unwrap();
+        assert_eq!(message.header.flags, IFF_UP);
+        assert!(message.nlas.contains(&Nla::Mtu(1400)));
+        assert!(message.nlas.contains(&Nla::Address(address.into())));
+
+        let message = dry_run_link(|handle| {
+            handle
+                .link()
+                .add()
+                .veth("veth1".into(), "veth0".into())
+                .mtu(9000)
+                .address(vec![0x02, 0, 0, 0, 0x35, 2])
+                .execute()
+        })
+        .unwrap();
+        assert!(message.nlas.contains(&Nla::Mtu(9000)));
+        assert!(message
+            .nlas
+            .contains(&Nla::Address(vec![0x02, 0, 0, 0, 0x35, 2])));
+    }
+}
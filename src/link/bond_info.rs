@@ -0,0 +1,143 @@
+// SPDX-License-Identifier: MIT
+
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use netlink_packet_route::{
+    link::nlas::{BondAdInfo, Info, InfoBond, InfoData, Nla},
+    LinkMessage,
+};
+
+/// The state of a bond, as reported by the kernel in the link information of
+/// a bond.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct BondInfo {
+    /// The bonding mode, a `BOND_MODE_*` value
+    pub mode: u8,
+    /// The index of the port currently used to transmit, in the modes with
+    /// a single active port (active-backup, balance-tlb and balance-alb).
+    /// `None` in the other modes, or when no port is usable.
+    pub active_slave: Option<u32>,
+    /// The index of the preferred port, if any
+    pub primary: Option<u32>,
+    pub miimon: u32,
+    pub arp_ip_target: Vec<Ipv4Addr>,
+    pub ns_ip6_target: Vec<Ipv6Addr>,
+    pub ad_actor_sys_prio: u16,
+    pub ad_user_port_key: u16,
+    pub ad_actor_system: [u8; 6],
+    /// The active 802.3ad aggregator, `None` in the other modes or before
+    /// an aggregator is selected
+    pub ad_info: Option<BondAggregatorInfo>,
+}
+
+/// The active aggregator of a bond in 802.3ad mode (`IFLA_BOND_AD_INFO`)
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct BondAggregatorInfo {
+    pub aggregator_id: u16,
+    pub num_ports: u16,
+    pub actor_key: u16,
+    pub partner_key: u16,
+    /// The system identifier of the LACP partner
+    pub partner_mac: [u8; 6],
+}
+
+impl From<&[BondAdInfo]> for BondAggregatorInfo {
+    fn from(nlas: &[BondAdInfo]) -> Self {
+        let mut info = BondAggregatorInfo::default();
+        for nla in nlas {
+            match nla {
+                BondAdInfo::Aggregator(v) => info.aggregator_id = *v,
+                BondAdInfo::NumPorts(v) => info.num_ports = *v,
+                BondAdInfo::ActorKey(v) => info.actor_key = *v,
+                BondAdInfo::PartnerKey(v) => info.partner_key = *v,
+                BondAdInfo::PartnerMac(v) => info.partner_mac = *v,
+                _ => (),
+            }
+        }
+        info
+    }
+}
+
+impl BondInfo {
+    /// Extract the bond information from a link message. Returns `None` if
+    /// the link is not a bond, or if the message does not carry the bond
+    /// attributes.
+    pub fn from_link_message(message: &LinkMessage) -> Option<Self> {
+        let nlas = message.nlas.iter().find_map(|nla| match nla {
+            Nla::Info(infos) => infos.iter().find_map(|info| match info {
+                Info::Data(InfoData::Bond(nlas)) => Some(nlas),
+                _ => None,
+            }),
+            _ => None,
+        })?;
+
+        let mut info = BondInfo::default();
+        for nla in nlas {
+            match nla {
+                InfoBond::Mode(v) => info.mode = *v,
+                InfoBond::ActiveSlave(v) if *v != 0 => {
+                    info.active_slave = Some(*v)
+                }
+                InfoBond::Primary(v) if *v != 0 => info.primary = Some(*v),
+                InfoBond::MiiMon(v) => info.miimon = *v,
+                InfoBond::ArpIpTarget(v) => info.arp_ip_target = v.clone(),
+                InfoBond::NsIp6Target(v) => info.ns_ip6_target = v.clone(),
+                InfoBond::AdActorSysPrio(v) => info.ad_actor_sys_prio = *v,
+                InfoBond::AdUserPortKey(v) => info.ad_user_port_key = *v,
+                InfoBond::AdActorSystem(v) => info.ad_actor_system = *v,
+                InfoBond::AdInfo(v) => info.ad_info = Some(v[..].into()),
+                _ => (),
+            }
+        }
+        Some(info)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use netlink_packet_route::link::nlas::InfoKind;
+
+    use super::*;
+
+    #[test]
+    fn test_bond_info_from_link_message() {
+        let mut message = LinkMessage::default();
+        message.nlas.push(Nla::Info(vec![
+            Info::Kind(InfoKind::Bond),
+            Info::Data(InfoData::Bond(vec![
+                InfoBond::Mode(1),
+                InfoBond::ActiveSlave(7),
+                InfoBond::MiiMon(100),
+                InfoBond::ArpIpTarget(vec![Ipv4Addr::new(192, 0, 2, 1)]),
+                InfoBond::AdActorSysPrio(65535),
+                InfoBond::AdInfo(vec![
+                    BondAdInfo::Aggregator(2),
+                    BondAdInfo::NumPorts(2),
+                    BondAdInfo::PartnerMac([0, 0x11, 0x22, 0x33, 0x44, 0x55]),
+                ]),
+            ])),
+        ]));
+        let info = BondInfo::from_link_message(&message).unwrap();
+        assert_eq!(
+            info,
+            BondInfo {
+                mode: 1,
+                active_slave: Some(7),
+                primary: None,
+                miimon: 100,
+                arp_ip_target: vec![Ipv4Addr::new(192, 0, 2, 1)],
+                ns_ip6_target: vec![],
+                ad_actor_sys_prio: 65535,
+                ad_info: Some(BondAggregatorInfo {
+                    aggregator_id: 2,
+                    num_ports: 2,
+                    partner_mac: [0, 0x11, 0x22, 0x33, 0x44, 0x55],
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }
+        );
+
+        assert_eq!(BondInfo::from_link_message(&LinkMessage::default()), None);
+    }
+}
@@ -0,0 +1,124 @@
+// SPDX-License-Identifier: MIT
+
+use netlink_packet_route::{
+    link::nlas::{Info, InfoBridge, InfoData, Nla},
+    LinkMessage,
+};
+
+/// A bridge identifier, made of the bridge priority and MAC address.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct BridgeId {
+    pub priority: u16,
+    pub address: [u8; 6],
+}
+
+impl From<(u16, [u8; 6])> for BridgeId {
+    fn from((priority, address): (u16, [u8; 6])) -> Self {
+        BridgeId { priority, address }
+    }
+}
+
+/// Spanning tree status of a bridge, as reported by the kernel in the link
+/// information of a bridge. Timers are in hundredths of a second.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct BridgeInfo {
+    pub stp_state: u32,
+    pub priority: u16,
+    pub root_id: BridgeId,
+    pub bridge_id: BridgeId,
+    pub root_port: u16,
+    pub root_path_cost: u32,
+    pub topology_change: bool,
+    pub topology_change_detected: bool,
+    pub forward_delay: u32,
+    pub hello_time: u32,
+    pub max_age: u32,
+    pub ageing_time: u32,
+    pub hello_timer: u64,
+    pub tcn_timer: u64,
+    pub topology_change_timer: u64,
+    pub gc_timer: u64,
+}
+
+impl BridgeInfo {
+    /// Extract the bridge information from a link message. Returns `None`
+    /// if the link is not a bridge, or if the message does not carry the
+    /// bridge attributes.
+    pub fn from_link_message(message: &LinkMessage) -> Option<Self> {
+        let nlas = message.nlas.iter().find_map(|nla| match nla {
+            Nla::Info(infos) => infos.iter().find_map(|info| match info {
+                Info::Data(InfoData::Bridge(nlas)) => Some(nlas),
+                _ => None,
+            }),
+            _ => None,
+        })?;
+
+        let mut info = BridgeInfo::default();
+        for nla in nlas {
+            match nla {
+                InfoBridge::StpState(v) => info.stp_state = *v,
+                InfoBridge::Priority(v) => info.priority = *v,
+                InfoBridge::RootId(v) => info.root_id = (*v).into(),
+                InfoBridge::BridgeId(v) => info.bridge_id = (*v).into(),
+                InfoBridge::RootPort(v) => info.root_port = *v,
+                InfoBridge::RootPathCost(v) => info.root_path_cost = *v,
+                InfoBridge::TopologyChange(v) => info.topology_change = *v != 0,
+                InfoBridge::TopologyChangeDetected(v) => {
+                    info.topology_change_detected = *v != 0
+                }
+                InfoBridge::ForwardDelay(v) => info.forward_delay = *v,
+                InfoBridge::HelloTime(v) => info.hello_time = *v,
+                InfoBridge::MaxAge(v) => info.max_age = *v,
+                InfoBridge::AgeingTime(v) => info.ageing_time = *v,
+                InfoBridge::HelloTimer(v) => info.hello_timer = *v,
+                InfoBridge::TcnTimer(v) => info.tcn_timer = *v,
+                InfoBridge::TopologyChangeTimer(v) => {
+                    info.topology_change_timer = *v
+                }
+                InfoBridge::GcTimer(v) => info.gc_timer = *v,
+                _ => (),
+            }
+        }
+        Some(info)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use netlink_packet_route::link::nlas::InfoKind;
+
+    use super::*;
+
+    #[test]
+    fn test_bridge_info_from_link_message() {
+        let mut message = LinkMessage::default();
+        message.nlas.push(Nla::Info(vec![
+            Info::Kind(InfoKind::Bridge),
+            Info::Data(InfoData::Bridge(vec![
+                InfoBridge::StpState(1),
+                InfoBridge::RootId((0x8000, [0, 1, 2, 3, 4, 5])),
+                InfoBridge::RootPort(2),
+                InfoBridge::TopologyChange(1),
+                InfoBridge::HelloTimer(150),
+            ])),
+        ]));
+        let info = BridgeInfo::from_link_message(&message).unwrap();
+        assert_eq!(info.stp_state, 1);
+        assert_eq!(
+            info.root_id,
+            BridgeId {
+                priority: 0x8000,
+                address: [0, 1, 2, 3, 4, 5]
+            }
+        );
+        assert_eq!(info.root_port, 2);
+        assert!(info.topology_change);
+        assert!(!info.topology_change_detected);
+        assert_eq!(info.hello_timer, 150);
+
+        assert_eq!(
+            BridgeInfo::from_link_message(&LinkMessage::default()),
+            None
+        );
+    }
+}
@@ -0,0 +1,145 @@
+// SPDX-License-Identifier: MIT
+
+use netlink_packet_utils::{
+    byteorder::{ByteOrder, NativeEndian},
+    nla::{DefaultNla, Nla},
+};
+
+const IFLA_CAN_BITTIMING: u16 = 1;
+const IFLA_CAN_CTRLMODE: u16 = 5;
+const IFLA_CAN_RESTART_MS: u16 = 6;
+
+// struct can_bittiming is made of 8 u32
+const CAN_BITTIMING_LEN: usize = 32;
+
+/// The control modes of a CAN device, set through `IFLA_CAN_CTRLMODE`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum CanCtrlMode {
+    /// `loopback`: loop the sent frames back
+    Loopback,
+    /// `listen-only`: only receive, without acknowledging the frames
+    ListenOnly,
+    /// `triple-sampling`: sample the bus three times per bit
+    TripleSampling,
+    /// `one-shot`: don't retransmit the frames which were not acknowledged
+    OneShot,
+    /// `berr-reporting`: report the bus errors
+    BerrReporting,
+    /// `fd`: CAN FD mode
+    Fd,
+    /// `presume-ack`: ignore the missing acknowledgments
+    PresumeAck,
+    /// `fd-non-iso`: CAN FD in the non-ISO mode
+    FdNonIso,
+    /// `cc-len8-dlc`: classical CAN frames with a DLC above 8
+    CcLen8Dlc,
+}
+
+impl CanCtrlMode {
+    pub(crate) fn bit(self) -> u32 {
+        match self {
+            CanCtrlMode::Loopback => 1 << 0,
+            CanCtrlMode::ListenOnly => 1 << 1,
+            CanCtrlMode::TripleSampling => 1 << 2,
+            CanCtrlMode::OneShot => 1 << 3,
+            CanCtrlMode::BerrReporting => 1 << 4,
+            CanCtrlMode::Fd => 1 << 5,
+            CanCtrlMode::PresumeAck => 1 << 6,
+            CanCtrlMode::FdNonIso => 1 << 7,
+            CanCtrlMode::CcLen8Dlc => 1 << 8,
+        }
+    }
+}
+
+/// CAN attributes (`IFLA_CAN_*`), which `netlink-packet-route` does not
+/// implement.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum InfoCan {
+    /// `struct can_bittiming` with only the bitrate, in bit/s, and the
+    /// sample point, in tenths of a percent: the kernel computes the other
+    /// timing parameters.
+    BitTiming {
+        bitrate: u32,
+        sample_point: u32,
+    },
+    /// `struct can_ctrlmode`, the modes of `mask` being set or cleared
+    /// according to `flags`
+    CtrlMode {
+        mask: u32,
+        flags: u32,
+    },
+    RestartMs(u32),
+    Other(DefaultNla),
+}
+
+impl Nla for InfoCan {
+    fn value_len(&self) -> usize {
+        use self::InfoCan::*;
+        match self {
+            BitTiming { .. } => CAN_BITTIMING_LEN,
+            CtrlMode { .. } => 8,
+            RestartMs(_) => 4,
+            Other(nla) => nla.value_len(),
+        }
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        use self::InfoCan::*;
+        match self {
+            BitTiming {
+                bitrate,
+                sample_point,
+            } => {
+                buffer.fill(0);
+                NativeEndian::write_u32(&mut buffer[..4], *bitrate);
+                NativeEndian::write_u32(&mut buffer[4..8], *sample_point);
+            }
+            CtrlMode { mask, flags } => {
+                NativeEndian::write_u32(&mut buffer[..4], *mask);
+                NativeEndian::write_u32(&mut buffer[4..8], *flags);
+            }
+            RestartMs(value) => NativeEndian::write_u32(buffer, *value),
+            Other(nla) => nla.emit_value(buffer),
+        }
+    }
+
+    fn kind(&self) -> u16 {
+        use self::InfoCan::*;
+        match self {
+            BitTiming { .. } => IFLA_CAN_BITTIMING,
+            CtrlMode { .. } => IFLA_CAN_CTRLMODE,
+            RestartMs(_) => IFLA_CAN_RESTART_MS,
+            Other(nla) => nla.kind(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::link::nla::encode;
+
+    #[test]
+    fn test_encode_can_attributes() {
+        let mut expected = vec![36, 0, 1, 0]; // IFLA_CAN_BITTIMING
+        expected.extend_from_slice(&500_000u32.to_ne_bytes());
+        expected.extend_from_slice(&875u32.to_ne_bytes());
+        expected.extend_from_slice(&[0; 24]);
+        expected.extend_from_slice(&[12, 0, 5, 0]); // IFLA_CAN_CTRLMODE
+        expected.extend_from_slice(&9u32.to_ne_bytes());
+        expected.extend_from_slice(&8u32.to_ne_bytes());
+        expected.extend_from_slice(&[8, 0, 6, 0]); // IFLA_CAN_RESTART_MS
+        expected.extend_from_slice(&100u32.to_ne_bytes());
+        assert_eq!(
+            encode(&[
+                InfoCan::BitTiming {
+                    bitrate: 500_000,
+                    sample_point: 875,
+                },
+                InfoCan::CtrlMode { mask: 9, flags: 8 },
+                InfoCan::RestartMs(100),
+            ]),
+            expected
+        );
+    }
+}
@@ -27,9 +27,9 @@ impl LinkDelRequest {
         let mut req = NetlinkMessage::from(RtnlMessage::DelLink(message));
         req.header.flags = NLM_F_REQUEST | NLM_F_ACK;
 
-        let mut response = handle.request(req)?;
+        let mut response = handle.try_request(req)?;
         while let Some(message) = response.next().await {
-            try_nl!(message)
+            try_nl!(message?)
         }
         Ok(())
     }
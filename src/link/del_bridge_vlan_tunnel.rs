@@ -0,0 +1,59 @@
+// SPDX-License-Identifier: MIT
+
+use futures::stream::StreamExt;
+use netlink_packet_core::{NetlinkMessage, NLM_F_ACK, NLM_F_REQUEST};
+use netlink_packet_route::{
+    link::nlas::{AfSpecBridge, Nla},
+    LinkMessage, RtnlMessage, AF_BRIDGE,
+};
+
+use super::set_bridge_port::vlan_tunnel_info;
+use crate::{try_nl, Error, Handle};
+
+/// A request to remove VLAN to tunnel mappings from a bridge port. This is
+/// equivalent to the `bridge vlan del dev DEV vid VID tunnel_info id
+/// TUNNEL_ID` commands.
+pub struct BridgeVlanTunnelDelRequest {
+    handle: Handle,
+    index: u32,
+    af_spec: Vec<AfSpecBridge>,
+}
+
+impl BridgeVlanTunnelDelRequest {
+    pub(crate) fn new(handle: Handle, index: u32) -> Self {
+        BridgeVlanTunnelDelRequest {
+            handle,
+            index,
+            af_spec: Vec::new(),
+        }
+    }
+
+    /// Execute the request
+    pub async fn execute(self) -> Result<(), Error> {
+        let BridgeVlanTunnelDelRequest {
+            mut handle,
+            index,
+            af_spec,
+        } = self;
+
+        let mut message = LinkMessage::default();
+        message.header.interface_family = AF_BRIDGE as u8;
+        message.header.index = index;
+        message.nlas.push(Nla::AfSpecBridge(af_spec));
+
+        let mut req = NetlinkMessage::from(RtnlMessage::DelLink(message));
+        req.header.flags = NLM_F_REQUEST | NLM_F_ACK;
+
+        let mut response = handle.try_request(req)?;
+        while let Some(message) = response.next().await {
+            try_nl!(message?);
+        }
+        Ok(())
+    }
+
+    /// Remove the mapping of the VLAN `vid` to the tunnel id `tunnel_id`.
+    pub fn vlan_tunnel_info(mut self, vid: u16, tunnel_id: u32) -> Self {
+        self.af_spec.push(vlan_tunnel_info(vid, tunnel_id));
+        self
+    }
+}
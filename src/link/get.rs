@@ -8,7 +8,7 @@ use futures::{
 use netlink_packet_core::{NetlinkMessage, NLM_F_DUMP, NLM_F_REQUEST};
 use netlink_packet_route::{link::nlas::Nla, LinkMessage, RtnlMessage};
 
-use crate::{try_rtnl, Error, Handle};
+use crate::{collect::collect, try_rtnl, CollectOptions, Error, Handle};
 
 pub struct LinkGetRequest {
     handle: Handle,
@@ -20,6 +20,8 @@ pub struct LinkGetRequest {
     // Otherwise, only the link that match the given index or name
     // is fetched.
     dump: bool,
+    // The kernel does not filter the links by group, so it is done here
+    group: Option<u32>,
 }
 
 impl LinkGetRequest {
@@ -28,6 +30,7 @@ impl LinkGetRequest {
             handle,
             message: LinkMessage::default(),
             dump: true,
+            group: None,
         }
     }
 
@@ -38,12 +41,22 @@ impl LinkGetRequest {
         self
     }
 
+    /// Execute the request, gathering the results into a `Vec` according
+    /// to `options`, for dumps small enough not to need a stream.
+    pub async fn execute_collect(
+        self,
+        options: CollectOptions,
+    ) -> Result<Vec<LinkMessage>, Error> {
+        collect(self.execute(), options).await
+    }
+
     /// Execute the request
     pub fn execute(self) -> impl TryStream<Ok = LinkMessage, Error = Error> {
         let LinkGetRequest {
             mut handle,
             message,
             dump,
+            group,
         } = self;
 
         let mut req = NetlinkMessage::from(RtnlMessage::GetLink(message));
@@ -54,10 +67,18 @@ impl LinkGetRequest {
             req.header.flags = NLM_F_REQUEST;
         }
 
-        match handle.request(req) {
+        match handle.try_request(req) {
             Ok(response) => Either::Left(
                 response
-                    .map(move |msg| Ok(try_rtnl!(msg, RtnlMessage::NewLink))),
+                    .map(move |msg| Ok(try_rtnl!(msg?, RtnlMessage::NewLink)))
+                    .filter(move |link| {
+                        future::ready(match (link, group) {
+                            (Ok(link), Some(group)) => {
+                                link.nlas.contains(&Nla::Group(group))
+                            }
+                            _ => true,
+                        })
+                    }),
             ),
             Err(e) => Either::Right(
                 future::err::<LinkMessage, Error>(e).into_stream(),
@@ -86,4 +107,43 @@ impl LinkGetRequest {
         self.message.nlas.push(Nla::IfName(name));
         self
     }
+
+    /// Only return the links of the given device group (equivalent to `ip
+    /// link show group GROUP`), to operate on all of them. The links are
+    /// filtered by this crate, since the kernel doesn't support it.
+    pub fn match_group(mut self, group: u32) -> Self {
+        self.group = Some(group);
+        self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use tokio::runtime::Runtime;
+
+    use super::*;
+    use crate::MockNetwork;
+
+    #[test]
+    fn test_match_group() {
+        let rt = Runtime::new().unwrap();
+        rt.block_on(async {
+            let link = MockNetwork::new().handle().link();
+            let mut indexes = Vec::new();
+            for name in ["br0", "br1"] {
+                link.add().bridge(name.into()).execute().await.unwrap();
+                indexes.push(link.resolve(name).await.unwrap().unwrap());
+            }
+            link.set(indexes[0]).group(4242).execute().await.unwrap();
+
+            let links = link
+                .get()
+                .match_group(4242)
+                .execute_collect(CollectOptions::new())
+                .await
+                .unwrap();
+            assert_eq!(links.len(), 1);
+            assert_eq!(links[0].header.index, indexes[0]);
+        });
+    }
 }
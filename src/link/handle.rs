@@ -1,10 +1,23 @@
 // SPDX-License-Identifier: MIT
 
 use super::{
-    LinkAddRequest, LinkDelPropRequest, LinkDelRequest, LinkGetRequest,
-    LinkNewPropRequest, LinkSetRequest,
+    BridgePortSetRequest, BridgeVlanGlobalSetRequest,
+    BridgeVlanTunnelDelRequest, LinkAddRequest, LinkDelPropRequest,
+    LinkDelRequest, LinkGetRequest, LinkNewPropRequest, LinkRenameRequest,
+    LinkSetRequest, LinkXstatsRequest,
 };
-use crate::Handle;
+use std::time::Duration;
+
+use futures::Stream;
+use netlink_sys::AsyncSocket;
+
+use super::{
+    promiscuity::{promiscuity, promiscuity_dec, promiscuity_inc},
+    resolve::resolve,
+    stats_stream::stats_stream,
+    wait_removed::wait_removed,
+};
+use crate::{Error, Handle, LinkStatsSample};
 
 pub struct LinkHandle(Handle);
 
@@ -17,6 +30,48 @@ impl LinkHandle {
         LinkSetRequest::new(self.0.clone(), index)
     }
 
+    /// Change the link `name` (or having `name` as alternative name), which
+    /// the kernel looks up itself: unlike [`LinkHandle::resolve`] followed by
+    /// [`LinkHandle::set`], this takes a single request. The link cannot be
+    /// renamed with [`LinkSetRequest::name`], see [`LinkHandle::rename`]
+    /// instead.
+    pub fn set_by_name(&self, name: String) -> LinkSetRequest {
+        LinkSetRequest::new_by_name(self.0.clone(), name)
+    }
+
+    /// Rename a link, keeping its alternative names. Unlike
+    /// `set(index).name(name)`, the new name can be one of the alternative
+    /// names of the link, and old kernels can rename a link which is up,
+    /// see [`LinkRenameRequest::down_if_busy`].
+    pub fn rename(&self, index: u32, name: String) -> LinkRenameRequest {
+        LinkRenameRequest::new(self.0.clone(), index, name)
+    }
+
+    /// Change the settings of a bridge port (equivalent to `bridge link set
+    /// dev DEV`)
+    pub fn set_bridge_port(&self, index: u32) -> BridgePortSetRequest {
+        BridgePortSetRequest::new(self.0.clone(), index)
+    }
+
+    /// Remove VLAN to tunnel mappings from a bridge port (equivalent to
+    /// `bridge vlan del dev DEV vid VID tunnel_info id TUNNEL_ID`)
+    pub fn del_bridge_vlan_tunnel(
+        &self,
+        index: u32,
+    ) -> BridgeVlanTunnelDelRequest {
+        BridgeVlanTunnelDelRequest::new(self.0.clone(), index)
+    }
+
+    /// Change the global options of the VLAN `vid` of the bridge with the
+    /// given index (equivalent to `bridge vlan global set dev DEV vid VID`)
+    pub fn set_bridge_vlan_global(
+        &self,
+        index: u32,
+        vid: u16,
+    ) -> BridgeVlanGlobalSetRequest {
+        BridgeVlanGlobalSetRequest::new(self.0.clone(), index, vid)
+    }
+
     pub fn add(&self) -> LinkAddRequest {
         LinkAddRequest::new(self.0.clone())
     }
@@ -29,12 +84,115 @@ impl LinkHandle {
         LinkDelPropRequest::new(self.0.clone(), index)
     }
 
-    pub fn del(&mut self, index: u32) -> LinkDelRequest {
+    pub fn del(&self, index: u32) -> LinkDelRequest {
         LinkDelRequest::new(self.0.clone(), index)
     }
 
     /// Retrieve the list of links (equivalent to `ip link show`)
-    pub fn get(&mut self) -> LinkGetRequest {
+    pub fn get(&self) -> LinkGetRequest {
         LinkGetRequest::new(self.0.clone())
     }
+
+    /// Return the index of the link `name` (or having `name` as
+    /// alternative name), or `None` if there is no such link. The answer
+    /// comes from the [`crate::LinkIndexCache`] of the handle if it knows
+    /// the link.
+    pub async fn resolve(&self, name: &str) -> Result<Option<u32>, Error> {
+        resolve(self.0.clone(), name.to_string()).await
+    }
+
+    /// Wait until the link `index` is gone, for instance before reusing its
+    /// name after [`LinkHandle::del`]: the kernel may finish tearing a link
+    /// down after acknowledging its removal. The link is looked up once, and
+    /// its removal is then noticed from the link events, received on a
+    /// dedicated netlink socket, in the network namespace of the connection
+    /// of the handle. [`Error::Timeout`] is returned if the link still exists
+    /// after `timeout`. It fails on a dry-run, mock or replay handle.
+    #[cfg(feature = "tokio_socket")]
+    pub async fn wait_removed(
+        &self,
+        index: u32,
+        timeout: Duration,
+    ) -> Result<(), Error> {
+        self.wait_removed_with_socket::<netlink_sys::TokioSocket>(
+            index, timeout,
+        )
+        .await
+    }
+
+    /// Same as [`LinkHandle::wait_removed`], using the socket type `S` (for
+    /// instance `SmolSocket`).
+    pub async fn wait_removed_with_socket<S>(
+        &self,
+        index: u32,
+        timeout: Duration,
+    ) -> Result<(), Error>
+    where
+        S: AsyncSocket,
+    {
+        wait_removed::<S>(self.0.clone(), index, timeout).await
+    }
+
+    /// Take a reference on the promiscuous mode of a link: the first one
+    /// enables it (equivalent to `ip link set dev DEV promisc on`).
+    ///
+    /// The kernel only tracks `IFF_PROMISC` as a single user of the
+    /// promiscuous mode, so the references are counted by this handle, and
+    /// shared with its clones. Other processes, or other connections, setting
+    /// or clearing the flag are not aware of them.
+    pub async fn promiscuity_inc(&self, index: u32) -> Result<(), Error> {
+        promiscuity_inc(self.0.clone(), index).await
+    }
+
+    /// Release a reference taken with
+    /// [`LinkHandle::promiscuity_inc`]: the last one disables the
+    /// promiscuous mode (equivalent to `ip link set dev DEV promisc off`).
+    pub async fn promiscuity_dec(&self, index: u32) -> Result<(), Error> {
+        promiscuity_dec(self.0.clone(), index).await
+    }
+
+    /// Return the number of users of the promiscuous mode of a link
+    /// (`IFLA_PROMISCUITY`), as counted by the kernel: the packet sockets,
+    /// the bridges and bonds the link belongs to, and `IFF_PROMISC`.
+    pub async fn promiscuity(&self, index: u32) -> Result<u32, Error> {
+        promiscuity(self.0.clone(), index).await
+    }
+
+    /// Retrieve the statistics specific to the kind of the links, for
+    /// instance the 802.3ad counters of bonds or the STP counters of bridge
+    /// ports (equivalent to `ip stats show group xstats`)
+    pub fn xstats(&self) -> LinkXstatsRequest {
+        LinkXstatsRequest::new(self.0.clone())
+    }
+
+    /// Sample the counters of the links `indices` (of all the links if
+    /// `indices` is empty) every `interval`, starting immediately. Each
+    /// sample comes from a single dump of the links.
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    ///
+    /// use futures::stream::TryStreamExt;
+    /// use rtnetlink::Handle;
+    ///
+    /// async fn print_rx_rate(handle: Handle) -> Result<(), rtnetlink::Error> {
+    ///     let samples = handle.link().stats_stream(Duration::from_secs(1), &[]);
+    ///     futures::pin_mut!(samples);
+    ///     while let Some(samples) = samples.try_next().await? {
+    ///         for sample in samples {
+    ///             if let Some(delta) = sample.delta {
+    ///                 println!("{}: {} B/s", sample.name, delta.rx_bytes);
+    ///             }
+    ///         }
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn stats_stream(
+        &self,
+        interval: Duration,
+        indices: &[u32],
+    ) -> impl Stream<Item = Result<Vec<LinkStatsSample>, Error>> {
+        stats_stream(self.0.clone(), interval, indices)
+    }
 }
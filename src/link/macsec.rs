@@ -0,0 +1,175 @@
+// SPDX-License-Identifier: MIT
+
+use netlink_packet_utils::{
+    byteorder::{BigEndian, ByteOrder, NativeEndian},
+    nla::{DefaultNla, Nla},
+};
+
+const IFLA_MACSEC_SCI: u16 = 1;
+const IFLA_MACSEC_PORT: u16 = 2;
+const IFLA_MACSEC_ICV_LEN: u16 = 3;
+const IFLA_MACSEC_CIPHER_SUITE: u16 = 4;
+const IFLA_MACSEC_WINDOW: u16 = 5;
+const IFLA_MACSEC_ENCRYPT: u16 = 7;
+const IFLA_MACSEC_PROTECT: u16 = 8;
+const IFLA_MACSEC_INC_SCI: u16 = 9;
+const IFLA_MACSEC_REPLAY_PROTECT: u16 = 12;
+const IFLA_MACSEC_VALIDATION: u16 = 13;
+const IFLA_MACSEC_OFFLOAD: u16 = 15;
+
+/// The cipher suite of a macsec link
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum MacSecCipherId {
+    /// `gcm-aes-128`, the default
+    GcmAes128,
+    /// `gcm-aes-256`
+    GcmAes256,
+    /// `gcm-aes-xpn-128`, with extended packet numbers
+    GcmAesXpn128,
+    /// `gcm-aes-xpn-256`, with extended packet numbers
+    GcmAesXpn256,
+}
+
+impl From<MacSecCipherId> for u64 {
+    fn from(cipher: MacSecCipherId) -> u64 {
+        match cipher {
+            MacSecCipherId::GcmAes128 => 0x0080_c200_0100_0001,
+            MacSecCipherId::GcmAes256 => 0x0080_c200_0100_0002,
+            MacSecCipherId::GcmAesXpn128 => 0x0080_c200_0100_0003,
+            MacSecCipherId::GcmAesXpn256 => 0x0080_c200_0100_0004,
+        }
+    }
+}
+
+/// How a macsec link validates the received frames
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum MacSecValidation {
+    /// Accept all the frames
+    Disabled,
+    /// Accept the invalid frames, but count them
+    Check,
+    /// Drop the invalid frames
+    Strict,
+}
+
+impl From<MacSecValidation> for u8 {
+    fn from(validation: MacSecValidation) -> u8 {
+        match validation {
+            MacSecValidation::Disabled => 0,
+            MacSecValidation::Check => 1,
+            MacSecValidation::Strict => 2,
+        }
+    }
+}
+
+/// Where the frames of a macsec link are processed
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum MacSecOffload {
+    /// In software
+    Off,
+    /// By the PHY of the lower link
+    Phy,
+    /// By the MAC of the lower link
+    Mac,
+}
+
+impl From<MacSecOffload> for u8 {
+    fn from(offload: MacSecOffload) -> u8 {
+        match offload {
+            MacSecOffload::Off => 0,
+            MacSecOffload::Phy => 1,
+            MacSecOffload::Mac => 2,
+        }
+    }
+}
+
+/// Macsec attributes (`IFLA_MACSEC_*`), which `netlink-packet-route` does
+/// not implement.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum InfoMacSec {
+    Sci(u64),
+    Port(u16),
+    IcvLen(u8),
+    CipherSuite(MacSecCipherId),
+    Window(u32),
+    Encrypt(u8),
+    Protect(u8),
+    IncSci(u8),
+    ReplayProtect(u8),
+    Validation(MacSecValidation),
+    Offload(MacSecOffload),
+    Other(DefaultNla),
+}
+
+impl Nla for InfoMacSec {
+    fn value_len(&self) -> usize {
+        use self::InfoMacSec::*;
+        match self {
+            IcvLen(_) | Encrypt(_) | Protect(_) | IncSci(_)
+            | ReplayProtect(_) | Validation(_) | Offload(_) => 1,
+            Port(_) => 2,
+            Window(_) => 4,
+            Sci(_) | CipherSuite(_) => 8,
+            Other(nla) => nla.value_len(),
+        }
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        use self::InfoMacSec::*;
+        match self {
+            IcvLen(value) | Encrypt(value) | Protect(value) | IncSci(value)
+            | ReplayProtect(value) => buffer[0] = *value,
+            Validation(validation) => buffer[0] = (*validation).into(),
+            Offload(offload) => buffer[0] = (*offload).into(),
+            // The SCI and the port are in network order
+            Port(value) => BigEndian::write_u16(buffer, *value),
+            Sci(value) => BigEndian::write_u64(buffer, *value),
+            Window(value) => NativeEndian::write_u32(buffer, *value),
+            CipherSuite(cipher) => {
+                NativeEndian::write_u64(buffer, (*cipher).into())
+            }
+            Other(nla) => nla.emit_value(buffer),
+        }
+    }
+
+    fn kind(&self) -> u16 {
+        use self::InfoMacSec::*;
+        match self {
+            Sci(_) => IFLA_MACSEC_SCI,
+            Port(_) => IFLA_MACSEC_PORT,
+            IcvLen(_) => IFLA_MACSEC_ICV_LEN,
+            CipherSuite(_) => IFLA_MACSEC_CIPHER_SUITE,
+            Window(_) => IFLA_MACSEC_WINDOW,
+            Encrypt(_) => IFLA_MACSEC_ENCRYPT,
+            Protect(_) => IFLA_MACSEC_PROTECT,
+            IncSci(_) => IFLA_MACSEC_INC_SCI,
+            ReplayProtect(_) => IFLA_MACSEC_REPLAY_PROTECT,
+            Validation(_) => IFLA_MACSEC_VALIDATION,
+            Offload(_) => IFLA_MACSEC_OFFLOAD,
+            Other(nla) => nla.kind(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::link::nla::encode;
+
+    #[test]
+    fn test_encode_macsec_attributes() {
+        assert_eq!(
+            encode(&[
+                InfoMacSec::Port(11),
+                InfoMacSec::CipherSuite(MacSecCipherId::GcmAes256),
+                InfoMacSec::Validation(MacSecValidation::Strict),
+            ]),
+            vec![
+                6, 0, 2, 0, 0, 11, 0, 0, // IFLA_MACSEC_PORT
+                12, 0, 4, 0, // IFLA_MACSEC_CIPHER_SUITE
+                2, 0, 0, 1, 0, 0xc2, 0x80, 0, // GCM-AES-256
+                5, 0, 13, 0, 2, 0, 0, 0, // IFLA_MACSEC_VALIDATION
+            ]
+        );
+    }
+}
@@ -15,6 +15,35 @@ pub use self::get::*;
 mod set;
 pub use self::set::*;
 
+mod bridge_info;
+pub use self::bridge_info::*;
+
+mod bond_info;
+pub use self::bond_info::*;
+
+mod tunnel;
+pub use self::tunnel::*;
+
+mod nla;
+
+mod can;
+pub use self::can::*;
+
+mod macsec;
+pub use self::macsec::*;
+
+mod vf;
+pub use self::vf::VfLinkState;
+
+mod set_bridge_port;
+pub use self::set_bridge_port::*;
+
+mod del_bridge_vlan_tunnel;
+pub use self::del_bridge_vlan_tunnel::*;
+
+mod set_bridge_vlan_global;
+pub use self::set_bridge_vlan_global::*;
+
 mod property_add;
 pub use self::property_add::*;
 
@@ -23,3 +52,26 @@ pub use self::property_del::*;
 
 #[cfg(test)]
 mod test;
+
+mod view;
+pub use self::view::*;
+
+mod snmp6;
+pub use self::snmp6::*;
+
+mod xstats;
+pub use self::xstats::*;
+
+mod stats_stream;
+pub use self::stats_stream::*;
+
+mod rename;
+pub use self::rename::*;
+
+mod resolve;
+pub use self::resolve::*;
+
+mod wait_removed;
+
+mod promiscuity;
+pub(crate) use self::promiscuity::PromiscuityRefs;
@@ -0,0 +1,11 @@
+// SPDX-License-Identifier: MIT
+
+use netlink_packet_utils::{nla::Nla, Emitable};
+
+/// Encode attributes into the raw value of a nested attribute, e.g. to be
+/// sent as raw `IFLA_INFO_DATA`
+pub(crate) fn encode<T: Nla>(nlas: &[T]) -> Vec<u8> {
+    let mut buf = vec![0; nlas.buffer_len()];
+    nlas.emit(&mut buf);
+    buf
+}
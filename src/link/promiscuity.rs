@@ -0,0 +1,148 @@
+// SPDX-License-Identifier: MIT
+
+use std::{collections::HashMap, sync::Arc};
+
+use futures::{lock::Mutex, stream::TryStreamExt};
+use netlink_packet_route::link::nlas::Nla;
+
+use crate::{Error, Handle};
+
+/// The promiscuity references taken through a handle and its clones, by
+/// link index
+#[derive(Clone, Debug, Default)]
+pub(crate) struct PromiscuityRefs(Arc<Mutex<HashMap<u32, usize>>>);
+
+// The kernel counts the users of the promiscuous mode of a link (packet
+// sockets, bridges, ...), but `IFF_PROMISC` is a single one of them: two
+// tools setting and clearing the flag stomp each other. The references are
+// therefore counted here, and the flag only changes for the first and the
+// last one. The lock is held while the flag changes so that the requests
+// can't be reordered.
+
+pub(crate) async fn promiscuity_inc(
+    handle: Handle,
+    index: u32,
+) -> Result<(), Error> {
+    let refs = handle.promiscuity_refs().0.clone();
+    let mut refs = refs.lock().await;
+    let count = refs.get(&index).copied().unwrap_or(0);
+    if count == 0 {
+        handle.link().set(index).promiscuous(true).execute().await?;
+    }
+    refs.insert(index, count + 1);
+    Ok(())
+}
+
+pub(crate) async fn promiscuity_dec(
+    handle: Handle,
+    index: u32,
+) -> Result<(), Error> {
+    let refs = handle.promiscuity_refs().0.clone();
+    let mut refs = refs.lock().await;
+    match refs.get(&index).copied() {
+        None => Err(Error::InvalidRequest(format!(
+            "the promiscuity of link {} was not increased through this handle",
+            index
+        ))),
+        Some(1) => {
+            handle
+                .link()
+                .set(index)
+                .promiscuous(false)
+                .execute()
+                .await?;
+            refs.remove(&index);
+            Ok(())
+        }
+        Some(count) => {
+            refs.insert(index, count - 1);
+            Ok(())
+        }
+    }
+}
+
+pub(crate) async fn promiscuity(
+    handle: Handle,
+    index: u32,
+) -> Result<u32, Error> {
+    let link = handle
+        .link()
+        .get()
+        .match_index(index)
+        .execute()
+        .try_next()
+        .await?
+        .ok_or(Error::RequestFailed)?;
+    Ok(link
+        .nlas
+        .iter()
+        .find_map(|nla| match nla {
+            Nla::Promiscuity(count) => Some(*count),
+            _ => None,
+        })
+        .unwrap_or(0))
+}
+
+#[cfg(test)]
+mod test {
+    use futures::stream::TryStreamExt;
+    use netlink_packet_route::IFF_PROMISC;
+    use tokio::runtime::Runtime;
+
+    use crate::{Link, MockNetwork};
+
+    #[test]
+    fn test_promiscuity_refs() {
+        let rt = Runtime::new().unwrap();
+        rt.block_on(async {
+            let network = MockNetwork::new();
+            let handle = network.handle();
+            let link = handle.link();
+            link.add().bridge("br0".into()).execute().await.unwrap();
+            let index = link.resolve("br0").await.unwrap().unwrap();
+            let is_promiscuous = || async {
+                let msg = link
+                    .get()
+                    .match_index(index)
+                    .execute()
+                    .try_next()
+                    .await
+                    .unwrap()
+                    .unwrap();
+                Link::from(msg).flags & IFF_PROMISC != 0
+            };
+
+            link.promiscuity_inc(index).await.unwrap();
+            // Another user of the same handle
+            handle.clone().link().promiscuity_inc(index).await.unwrap();
+            assert!(is_promiscuous().await);
+            link.promiscuity_dec(index).await.unwrap();
+            assert!(is_promiscuous().await);
+            link.promiscuity_dec(index).await.unwrap();
+            assert!(!is_promiscuous().await);
+            assert!(link.promiscuity_dec(index).await.is_err());
+        });
+    }
+
+    #[test]
+    fn test_dry_run_promiscuity_refs() {
+        let rt = Runtime::new().unwrap();
+        rt.block_on(async {
+            let network = MockNetwork::new();
+            let handle = network.handle();
+            let link = handle.link();
+            link.add().bridge("br0".into()).execute().await.unwrap();
+            let index = link.resolve("br0").await.unwrap().unwrap();
+
+            let (dry_handle, dry_run) = handle.dry_run();
+            dry_handle.link().promiscuity_inc(index).await.unwrap();
+            assert_eq!(dry_run.messages().len(), 1);
+            // The reference taken in dry-run mode does not count for the
+            // handle it was derived from
+            link.promiscuity_inc(index).await.unwrap();
+            let links = network.links();
+            let br0 = links.iter().find(|link| link.index == index).unwrap();
+            assert_ne!(br0.flags & IFF_PROMISC, 0);
+        });
+    }
+}
@@ -12,6 +12,8 @@ use netlink_packet_route::{
 
 use crate::{Error, Handle};
 
+const ALTIFNAMSIZ: usize = 128;
+
 pub struct LinkNewPropRequest {
     handle: Handle,
     message: LinkMessage,
@@ -30,6 +32,7 @@ impl LinkNewPropRequest {
             mut handle,
             message,
         } = self;
+        validate_alt_ifnames(&message)?;
         let mut req = NetlinkMessage::from(RtnlMessage::NewLinkProp(message));
         req.header.flags = NLM_F_REQUEST
             | NLM_F_ACK
@@ -37,10 +40,11 @@ impl LinkNewPropRequest {
             | NLM_F_CREATE
             | NLM_F_APPEND;
 
-        let mut response = handle.request(req)?;
+        let mut response = handle.try_request(req)?;
         while let Some(message) = response.next().await {
+            let message = message?;
             if let NetlinkPayload::Error(err) = message.payload {
-                return Err(Error::NetlinkError(err));
+                return Err(Error::netlink(message.header.flags, err));
             }
         }
         Ok(())
@@ -52,14 +56,85 @@ impl LinkNewPropRequest {
     }
 
     /// Add alternative name to the link. This is equivalent to `ip link
-    /// property add altname ALT_IFNAME dev LINK`.
+    /// property add altname ALT_IFNAME dev LINK`. An [`Error::InvalidRequest`]
+    /// is returned on execution if a name is empty or longer than 127 bytes.
+    /// The names of successive calls are sent together.
     pub fn alt_ifname(mut self, alt_ifnames: &[&str]) -> Self {
-        let mut props = Vec::new();
-        for alt_ifname in alt_ifnames {
-            props.push(Prop::AltIfName(alt_ifname.to_string()));
+        push_alt_ifnames(&mut self.message, alt_ifnames);
+        self
+    }
+}
+
+// The kernel only takes the last IFLA_PROP_LIST into account, so the names
+// are added to the existing one
+pub(crate) fn push_alt_ifnames(
+    message: &mut LinkMessage,
+    alt_ifnames: &[&str],
+) {
+    let props = alt_ifnames
+        .iter()
+        .map(|alt_ifname| Prop::AltIfName(alt_ifname.to_string()));
+    let list = message.nlas.iter_mut().find_map(|nla| match nla {
+        Nla::PropList(list) => Some(list),
+        _ => None,
+    });
+    match list {
+        Some(list) => list.extend(props),
+        None => message.nlas.push(Nla::PropList(props.collect())),
+    }
+}
+
+pub(crate) fn validate_alt_ifnames(message: &LinkMessage) -> Result<(), Error> {
+    for nla in &message.nlas {
+        if let Nla::PropList(props) = nla {
+            for prop in props {
+                if let Prop::AltIfName(name) = prop {
+                    if name.is_empty() || name.len() >= ALTIFNAMSIZ {
+                        return Err(Error::InvalidRequest(format!(
+                            "invalid alternative interface name {:?}",
+                            name
+                        )));
+                    }
+                }
+            }
         }
+    }
+    Ok(())
+}
 
-        self.message.nlas.push(Nla::PropList(props));
-        self
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::link::test::dry_run_link;
+
+    #[test]
+    fn test_alt_ifnames_in_one_list() {
+        let long_name = "a".repeat(128);
+        let res = dry_run_link(|handle| {
+            handle
+                .link()
+                .property_del(4242)
+                .alt_ifname(&[&long_name])
+                .execute()
+        });
+        assert!(matches!(res, Err(Error::InvalidRequest(_))));
+
+        let message = dry_run_link(|handle| {
+            handle
+                .link()
+                .property_add(4242)
+                .alt_ifname(&["alt0"])
+                .alt_ifname(&["alt1"])
+                .execute()
+        })
+        .unwrap();
+        assert_eq!(message.header.index, 4242);
+        assert_eq!(
+            message.nlas,
+            vec![Nla::PropList(vec![
+                Prop::AltIfName("alt0".into()),
+                Prop::AltIfName("alt1".into()),
+            ])]
+        );
     }
 }
@@ -2,13 +2,11 @@
 
 use futures::stream::StreamExt;
 use netlink_packet_core::{
-    NetlinkMessage, NetlinkPayload, NLM_F_ACK, NLM_F_EXCL, NLM_F_REQUEST,
-};
-use netlink_packet_route::{
-    link::nlas::{Nla, Prop},
-    LinkMessage, RtnlMessage,
+    NetlinkMessage, NetlinkPayload, NLM_F_ACK, NLM_F_REQUEST,
 };
+use netlink_packet_route::{LinkMessage, RtnlMessage};
 
+use super::property_add::{push_alt_ifnames, validate_alt_ifnames};
 use crate::{Error, Handle};
 
 pub struct LinkDelPropRequest {
@@ -29,13 +27,17 @@ impl LinkDelPropRequest {
             mut handle,
             message,
         } = self;
+        validate_alt_ifnames(&message)?;
         let mut req = NetlinkMessage::from(RtnlMessage::DelLinkProp(message));
-        req.header.flags = NLM_F_REQUEST | NLM_F_ACK | NLM_F_EXCL;
+        // NLM_F_EXCL would be NLM_F_BULK for a deletion, which the kernel
+        // rejects
+        req.header.flags = NLM_F_REQUEST | NLM_F_ACK;
 
-        let mut response = handle.request(req)?;
+        let mut response = handle.try_request(req)?;
         while let Some(message) = response.next().await {
+            let message = message?;
             if let NetlinkPayload::Error(err) = message.payload {
-                return Err(Error::NetlinkError(err));
+                return Err(Error::netlink(message.header.flags, err));
             }
         }
         Ok(())
@@ -47,14 +49,10 @@ impl LinkDelPropRequest {
     }
 
     /// Remove alternative name to the link. This is equivalent to `ip link
-    /// property del altname ALT_IFNAME dev LINK`.
+    /// property del altname ALT_IFNAME dev LINK`. The names of successive
+    /// calls are sent together.
     pub fn alt_ifname(mut self, alt_ifnames: &[&str]) -> Self {
-        let mut props = Vec::new();
-        for alt_ifname in alt_ifnames {
-            props.push(Prop::AltIfName(alt_ifname.to_string()));
-        }
-
-        self.message.nlas.push(Nla::PropList(props));
+        push_alt_ifnames(&mut self.message, alt_ifnames);
         self
     }
 }
@@ -0,0 +1,132 @@
+// SPDX-License-Identifier: MIT
+
+use futures::stream::TryStreamExt;
+use netlink_packet_route::{
+    link::nlas::{Nla, Prop},
+    IFF_UP,
+};
+
+use crate::{Error, Handle};
+
+/// Rename a link, see [`crate::LinkHandle::rename`]
+pub struct LinkRenameRequest {
+    handle: Handle,
+    index: u32,
+    name: String,
+    down_if_busy: bool,
+}
+
+impl LinkRenameRequest {
+    pub(crate) fn new(handle: Handle, index: u32, name: String) -> Self {
+        LinkRenameRequest {
+            handle,
+            index,
+            name,
+            down_if_busy: false,
+        }
+    }
+
+    /// Before Linux 6.2, most links can't be renamed while they are up (the
+    /// kernel answers `EBUSY`). With this option, such a link is brought
+    /// down, renamed, and brought back up. This briefly interrupts its
+    /// traffic.
+    pub fn down_if_busy(mut self, enable: bool) -> Self {
+        self.down_if_busy = enable;
+        self
+    }
+
+    /// Execute the request. If a step fails, the previous ones are undone
+    /// (as far as possible) and the error of the failed step is returned.
+    pub async fn execute(self) -> Result<(), Error> {
+        let LinkRenameRequest {
+            handle,
+            index,
+            name,
+            down_if_busy,
+        } = self;
+
+        let link = handle
+            .link()
+            .get()
+            .match_index(index)
+            .execute()
+            .try_next()
+            .await?
+            .ok_or(Error::RequestFailed)?;
+        let mut old_name = None;
+        let mut altnames = Vec::new();
+        for nla in &link.nlas {
+            match nla {
+                Nla::IfName(name) => old_name = Some(name.clone()),
+                Nla::PropList(props) => altnames.extend(
+                    props.iter().filter_map(|prop| match prop {
+                        Prop::AltIfName(name) => Some(name.clone()),
+                        _ => None,
+                    }),
+                ),
+                _ => {}
+            }
+        }
+        let old_name = old_name.ok_or(Error::RequestFailed)?;
+        if old_name == name {
+            return Ok(());
+        }
+
+        // The kernel refuses a name which is already in use, even as an
+        // alternative name of the link itself. The other alternative names
+        // are kept.
+        let is_altname = altnames.contains(&name);
+        if is_altname {
+            handle
+                .link()
+                .property_del(index)
+                .alt_ifname(&[&name])
+                .execute()
+                .await?;
+        }
+        let restore_altname = || async {
+            if is_altname {
+                let _ = handle
+                    .link()
+                    .property_add(index)
+                    .alt_ifname(&[&name])
+                    .execute()
+                    .await;
+            }
+        };
+
+        let err =
+            match handle.link().set(index).name(name.clone()).execute().await {
+                Ok(()) => return Ok(()),
+                Err(err) => err,
+            };
+        if !(err.is_busy() && down_if_busy && link.header.flags & IFF_UP != 0) {
+            restore_altname().await;
+            return Err(err);
+        }
+
+        if let Err(err) = handle.link().set(index).down().execute().await {
+            restore_altname().await;
+            return Err(err);
+        }
+        let result =
+            match handle.link().set(index).name(name.clone()).execute().await {
+                Ok(()) => match handle.link().set(index).up().execute().await {
+                    Ok(()) => return Ok(()),
+                    Err(err) => {
+                        let _ = handle
+                            .link()
+                            .set(index)
+                            .name(old_name)
+                            .execute()
+                            .await;
+                        Err(err)
+                    }
+                },
+                Err(err) => Err(err),
+            };
+        let _ = handle.link().set(index).up().execute().await;
+        restore_altname().await;
+        result
+    }
+}
@@ -0,0 +1,181 @@
+// SPDX-License-Identifier: MIT
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use futures::stream::TryStreamExt;
+use netlink_packet_core::{NetlinkMessage, NetlinkPayload};
+use netlink_packet_route::{link::nlas::Nla, RtnlMessage};
+
+use crate::{Error, Handle};
+
+/// A cache of the indices of the links, by name, used by
+/// [`crate::LinkHandle::resolve`] once set on a handle with
+/// [`Handle::set_link_cache`].
+///
+/// The cache is filled by the lookups. To notice the links which are
+/// renamed or removed, it must be fed the link events with
+/// [`LinkIndexCache::update`], or be created with
+/// [`LinkIndexCache::monitored`] which does it on its own. Since the
+/// events are asynchronous, a lookup may still return the former index of a
+/// name for a short while after a change.
+#[derive(Clone, Debug, Default)]
+pub struct LinkIndexCache(Arc<Mutex<HashMap<String, u32>>>);
+
+impl LinkIndexCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a cache kept up to date by a task listening to the link events
+    /// on its own netlink socket. The task is spawned on the tokio runtime,
+    /// and stops on the first event after all the clones of the cache are
+    /// dropped.
+    #[cfg(feature = "tokio_socket")]
+    pub fn monitored() -> Self {
+        use futures::StreamExt;
+
+        use crate::{
+            constants::RTMGRP_LINK, new_supervised_monitor, MonitorEvent,
+        };
+
+        let cache = Self::new();
+        let weak = Arc::downgrade(&cache.0);
+        let mut events = new_supervised_monitor(RTMGRP_LINK);
+        tokio::spawn(async move {
+            while let Some(event) = events.next().await {
+                let cache = match weak.upgrade() {
+                    Some(cache) => LinkIndexCache(cache),
+                    None => return,
+                };
+                match event {
+                    MonitorEvent::Message(message) => cache.update(&message),
+                    MonitorEvent::Reconnected => cache.clear(),
+                }
+            }
+        });
+        cache
+    }
+
+    /// Update the cache with a message of the kernel. `RTM_NEWLINK` and
+    /// `RTM_DELLINK` messages forget the former names of the link; other
+    /// messages are ignored.
+    pub fn update(&self, message: &NetlinkMessage<RtnlMessage>) {
+        let link = match &message.payload {
+            NetlinkPayload::InnerMessage(RtnlMessage::NewLink(link))
+            | NetlinkPayload::InnerMessage(RtnlMessage::DelLink(link)) => link,
+            _ => return,
+        };
+        let index = link.header.index;
+        let mut names = self.0.lock().unwrap();
+        names.retain(|_, i| *i != index);
+        if let NetlinkPayload::InnerMessage(RtnlMessage::NewLink(_)) =
+            message.payload
+        {
+            for nla in &link.nlas {
+                if let Nla::IfName(name) = nla {
+                    names.insert(name.clone(), index);
+                }
+            }
+        }
+    }
+
+    /// Forget all the links, for instance after events were lost
+    pub fn clear(&self) {
+        self.0.lock().unwrap().clear();
+    }
+
+    fn get(&self, name: &str) -> Option<u32> {
+        self.0.lock().unwrap().get(name).copied()
+    }
+
+    fn insert(&self, name: String, index: u32) {
+        self.0.lock().unwrap().insert(name, index);
+    }
+}
+
+pub(crate) async fn resolve(
+    handle: Handle,
+    name: String,
+) -> Result<Option<u32>, Error> {
+    let cache = handle.link_cache();
+    if let Some(index) = cache.and_then(|cache| cache.get(&name)) {
+        return Ok(Some(index));
+    }
+    let link = handle
+        .link()
+        .get()
+        .match_name(name.clone())
+        .execute()
+        .try_next()
+        .await;
+    match link {
+        Ok(Some(link)) => {
+            if let Some(cache) = cache {
+                cache.insert(name, link.header.index);
+            }
+            Ok(Some(link.header.index))
+        }
+        Ok(None) => Ok(None),
+        Err(err) if err.is_not_found() => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use netlink_packet_route::LinkMessage;
+    use tokio::runtime::Runtime;
+
+    use super::*;
+    use crate::MockNetwork;
+
+    #[test]
+    fn test_resolve() {
+        let rt = Runtime::new().unwrap();
+        rt.block_on(async {
+            let network = MockNetwork::new();
+            let mut handle = network.handle();
+            let cache = LinkIndexCache::new();
+            handle.set_link_cache(Some(cache.clone()));
+
+            let link = handle.link();
+            link.add().bridge("br0".into()).execute().await.unwrap();
+            let index = link.resolve("br0").await.unwrap().unwrap();
+            assert_eq!(cache.get("br0"), Some(index));
+            assert_eq!(link.resolve("br1").await.unwrap(), None);
+
+            // The cache answers even though the link is gone, until it gets
+            // the event
+            link.del(index).execute().await.unwrap();
+            assert_eq!(link.resolve("br0").await.unwrap(), Some(index));
+            let mut message = LinkMessage::default();
+            message.header.index = index;
+            cache.update(&NetlinkMessage::from(RtnlMessage::DelLink(message)));
+            assert_eq!(link.resolve("br0").await.unwrap(), None);
+        });
+    }
+
+    #[test]
+    fn test_mock_does_not_share_cache() {
+        let rt = Runtime::new().unwrap();
+        rt.block_on(async {
+            let mut handle = MockNetwork::new().handle();
+            let cache = LinkIndexCache::new();
+            handle.set_link_cache(Some(cache.clone()));
+            let link = handle.link();
+            link.add().bridge("br0".into()).execute().await.unwrap();
+            let index = link.resolve("br0").await.unwrap().unwrap();
+
+            let other = MockNetwork::new();
+            let mock = handle.mock(&other).link();
+            assert_eq!(mock.resolve("br0").await.unwrap(), None);
+            mock.add().bridge("br1".into()).execute().await.unwrap();
+            assert!(mock.resolve("br1").await.unwrap().is_some());
+            assert_eq!(cache.get("br0"), Some(index));
+            assert_eq!(cache.get("br1"), None);
+        });
+    }
+}
@@ -1,42 +1,1023 @@
 // SPDX-License-Identifier: MIT
 
-use std::os::unix::io::RawFd;
+use std::{
+    net::{Ipv4Addr, Ipv6Addr},
+    os::unix::io::RawFd,
+};
 
-use futures::stream::StreamExt;
+use futures::stream::{StreamExt, TryStreamExt};
 use netlink_packet_core::{
     NetlinkMessage, NLM_F_ACK, NLM_F_CREATE, NLM_F_EXCL, NLM_F_REQUEST,
 };
 use netlink_packet_route::{
-    link::nlas::Nla, LinkMessage, RtnlMessage, IFF_NOARP, IFF_PROMISC, IFF_UP,
+    link::nlas::{
+        Info, InfoBond, InfoBridge, InfoData, InfoKind, InfoMacVlan, InfoVlan,
+        InfoVxlan, Nla,
+    },
+    LinkMessage, RtnlMessage, IFF_NOARP, IFF_PROMISC, IFF_UP,
+};
+use netlink_packet_utils::{
+    byteorder::{ByteOrder, NativeEndian},
+    nla,
+};
+
+use super::{
+    add::validate_bond,
+    can::{CanCtrlMode, InfoCan},
+    nla::encode,
+    resolve::resolve,
+    tunnel::{self, InfoGre, InfoIpTun},
+    vf::{vf_info_list, VfLinkState, VfSetting},
 };
+use crate::{try_nl, Error, Handle, MacAddress};
+
+/// A request to change the options of an existing bridge. This is equivalent
+/// to `ip link set dev NAME type bridge ...` commands. The global per-VLAN
+/// options (`bridge vlan global set`) are changed with
+/// [`crate::LinkHandle::set_bridge_vlan_global`] instead.
+pub struct BridgeSetRequest {
+    request: LinkSetRequest,
+    info_data: Vec<InfoBridge>,
+    boolopt_value: u32,
+    boolopt_mask: u32,
+}
+
+/// Bridge boolean options, set through `IFLA_BR_MULTI_BOOLOPT` rather than
+/// dedicated attributes.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum BridgeBoolOpt {
+    /// `no_linklocal_learn`: do not learn source addresses from link-local
+    /// frames.
+    NoLinkLocalLearn,
+    /// `mcast_vlan_snooping`: per-VLAN multicast snooping.
+    McastVlanSnooping,
+    /// `mst_enabled`: multiple spanning tree support.
+    MstEnabled,
+}
+
+impl BridgeBoolOpt {
+    fn bit(self) -> u32 {
+        match self {
+            BridgeBoolOpt::NoLinkLocalLearn => 1 << 0,
+            BridgeBoolOpt::McastVlanSnooping => 1 << 1,
+            BridgeBoolOpt::MstEnabled => 1 << 2,
+        }
+    }
+}
+
+impl BridgeSetRequest {
+    /// Execute the request.
+    pub async fn execute(self) -> Result<(), Error> {
+        let BridgeSetRequest {
+            request,
+            mut info_data,
+            boolopt_value,
+            boolopt_mask,
+        } = self;
+        if boolopt_mask != 0 {
+            // struct br_boolopt_multi { __u32 optval; __u32 optmask; }
+            let mut buf = [0; 8];
+            NativeEndian::write_u32(&mut buf[..4], boolopt_value);
+            NativeEndian::write_u32(&mut buf[4..], boolopt_mask);
+            info_data
+                .push(InfoBridge::MultiBoolOpt(NativeEndian::read_u64(&buf)));
+        }
+        request
+            .link_info(InfoKind::Bridge, Some(InfoData::Bridge(info_data)))
+            .execute()
+            .await
+    }
+
+    /// Enable or disable a bridge boolean option. Options that are not set
+    /// are left unchanged. This is equivalent to `ip link set dev NAME type
+    /// bridge OPTION 0/1` for the options listed in [`BridgeBoolOpt`].
+    pub fn boolopt(mut self, option: BridgeBoolOpt, enable: bool) -> Self {
+        let bit = option.bit();
+        self.boolopt_mask |= bit;
+        if enable {
+            self.boolopt_value |= bit;
+        } else {
+            self.boolopt_value &= !bit;
+        }
+        self
+    }
+
+    /// Enable or disable the spanning tree protocol. This is equivalent to
+    /// `ip link set dev NAME type bridge stp_state STP_STATE`.
+    pub fn stp_state(mut self, stp_state: u32) -> Self {
+        self.info_data.push(InfoBridge::StpState(stp_state));
+        self
+    }
+
+    /// Set the bridge priority used by the spanning tree protocol. This is
+    /// equivalent to `ip link set dev NAME type bridge priority PRIORITY`.
+    pub fn priority(mut self, priority: u16) -> Self {
+        self.info_data.push(InfoBridge::Priority(priority));
+        self
+    }
+
+    /// Set the STP forward delay, in hundredths of a second. This is
+    /// equivalent to `ip link set dev NAME type bridge forward_delay
+    /// FORWARD_DELAY`.
+    pub fn forward_delay(mut self, forward_delay: u32) -> Self {
+        self.info_data.push(InfoBridge::ForwardDelay(forward_delay));
+        self
+    }
+
+    /// Set the STP hello time, in hundredths of a second. This is equivalent
+    /// to `ip link set dev NAME type bridge hello_time HELLO_TIME`.
+    pub fn hello_time(mut self, hello_time: u32) -> Self {
+        self.info_data.push(InfoBridge::HelloTime(hello_time));
+        self
+    }
+
+    /// Set the STP maximum message age, in hundredths of a second. This is
+    /// equivalent to `ip link set dev NAME type bridge max_age MAX_AGE`.
+    pub fn max_age(mut self, max_age: u32) -> Self {
+        self.info_data.push(InfoBridge::MaxAge(max_age));
+        self
+    }
+
+    /// Set the lifetime of learnt FDB entries, in hundredths of a second.
+    /// This is equivalent to `ip link set dev NAME type bridge ageing_time
+    /// AGEING_TIME`.
+    pub fn ageing_time(mut self, ageing_time: u32) -> Self {
+        self.info_data.push(InfoBridge::AgeingTime(ageing_time));
+        self
+    }
+
+    /// Enable or disable VLAN filtering. This is equivalent to `ip link set
+    /// dev NAME type bridge vlan_filtering 0/1`.
+    pub fn vlan_filtering(mut self, enable: bool) -> Self {
+        self.info_data.push(InfoBridge::VlanFiltering(enable as u8));
+        self
+    }
+
+    /// Set the protocol used for VLAN filtering, either `0x8100` (802.1Q) or
+    /// `0x88a8` (802.1ad). This is equivalent to `ip link set dev NAME type
+    /// bridge vlan_protocol VLAN_PROTOCOL`.
+    pub fn vlan_protocol(mut self, vlan_protocol: u16) -> Self {
+        self.info_data.push(InfoBridge::VlanProtocol(vlan_protocol));
+        self
+    }
+
+    /// Set the default PVID of newly enslaved ports. This is equivalent to
+    /// `ip link set dev NAME type bridge vlan_default_pvid PVID`.
+    pub fn vlan_default_pvid(mut self, pvid: u16) -> Self {
+        self.info_data.push(InfoBridge::VlanDefaultPvid(pvid));
+        self
+    }
+
+    /// Enable or disable multicast snooping. This is equivalent to `ip link
+    /// set dev NAME type bridge mcast_snooping 0/1`.
+    pub fn mcast_snooping(mut self, enable: bool) -> Self {
+        self.info_data
+            .push(InfoBridge::MulticastSnooping(enable as u8));
+        self
+    }
+
+    /// Enable or disable the per-VLAN counters of the bridge, reported by
+    /// [`crate::LinkHandle::xstats`] in [`crate::LinkXstats::bridge_vlans`].
+    /// This is equivalent to `ip link set dev NAME type bridge
+    /// vlan_stats_enabled 0/1`.
+    pub fn vlan_stats_enabled(mut self, enable: bool) -> Self {
+        self.info_data
+            .push(InfoBridge::VlanStatsEnabled(enable as u8));
+        self
+    }
+
+    /// Count the VLAN traffic of each port separately, instead of only for
+    /// the whole bridge. It can only be changed while the bridge has no
+    /// ports. This is equivalent to `ip link set dev NAME type bridge
+    /// vlan_stats_per_port 0/1`.
+    pub fn vlan_stats_per_port(mut self, enable: bool) -> Self {
+        self.info_data
+            .push(InfoBridge::VlanStatsPerHost(enable as u8));
+        self
+    }
+}
+
+/// A request to change the options of an existing bond. This is equivalent
+/// to `ip link set dev NAME type bond ...` commands.
+pub struct BondSetRequest {
+    request: LinkSetRequest,
+    info_data: Vec<InfoBond>,
+}
+
+impl BondSetRequest {
+    /// Execute the request.
+    pub async fn execute(self) -> Result<(), Error> {
+        let BondSetRequest { request, info_data } = self;
+        validate_bond(&info_data)?;
+        request
+            .link_info(InfoKind::Bond, Some(InfoData::Bond(info_data)))
+            .execute()
+            .await
+    }
+
+    /// Make the port with the given index the active one, in active-backup,
+    /// balance-tlb and balance-alb modes. This is equivalent to `ip link set
+    /// dev NAME type bond active_slave PORT`. The port must be up and have a
+    /// carrier, and `0` clears the active port. The current one can be read
+    /// with [`crate::BondInfo::from_link_message`].
+    pub fn active_slave(mut self, index: u32) -> Self {
+        self.info_data.push(InfoBond::ActiveSlave(index));
+        self
+    }
+
+    /// Set the LACP system priority, in 802.3ad mode. This is equivalent to
+    /// `ip link set dev NAME type bond ad_actor_sys_prio PRIO`.
+    pub fn ad_actor_sys_prio(mut self, priority: u16) -> Self {
+        self.info_data.push(InfoBond::AdActorSysPrio(priority));
+        self
+    }
+
+    /// Set the LACP system identifier, in 802.3ad mode. This is equivalent
+    /// to `ip link set dev NAME type bond ad_actor_system ADDRESS`.
+    pub fn ad_actor_system(mut self, address: [u8; 6]) -> Self {
+        self.info_data.push(InfoBond::AdActorSystem(address));
+        self
+    }
+
+    /// Set the upper 10 bits of the LACP port key, in 802.3ad mode. This is
+    /// equivalent to `ip link set dev NAME type bond ad_user_port_key KEY`.
+    pub fn ad_user_port_key(mut self, key: u16) -> Self {
+        self.info_data.push(InfoBond::AdUserPortKey(key));
+        self
+    }
+
+    /// Replace the IPv4 addresses monitored by ARP, at most 16, an empty
+    /// list removing them all. This is equivalent to `ip link set dev NAME
+    /// type bond arp_ip_target LIST`.
+    pub fn arp_ip_target(mut self, targets: Vec<Ipv4Addr>) -> Self {
+        self.info_data.push(InfoBond::ArpIpTarget(targets));
+        self
+    }
+
+    /// Replace the IPv6 addresses monitored by neighbour solicitations, at
+    /// most 16, an empty list removing them all. This is equivalent to `ip
+    /// link set dev NAME type bond ns_ip6_target LIST`.
+    pub fn ns_ip6_target(mut self, targets: Vec<Ipv6Addr>) -> Self {
+        self.info_data.push(InfoBond::NsIp6Target(targets));
+        self
+    }
+}
+
+/// A request to change the options of an existing VLAN link. This is
+/// equivalent to `ip link set dev NAME type vlan ...` commands.
+///
+/// The priority maps are changed entry by entry: the entries which are not
+/// mentioned are kept.
+pub struct VlanSetRequest {
+    request: LinkSetRequest,
+    ingress_qos: Vec<VlanQosMapping>,
+    egress_qos: Vec<VlanQosMapping>,
+}
+
+impl VlanSetRequest {
+    /// Execute the request.
+    pub async fn execute(self) -> Result<(), Error> {
+        let VlanSetRequest {
+            request,
+            ingress_qos,
+            egress_qos,
+        } = self;
+        let mut info_data = Vec::new();
+        push_vlan_qos(&mut info_data, ingress_qos, egress_qos)?;
+        request
+            .link_info(InfoKind::Vlan, Some(InfoData::Vlan(info_data)))
+            .execute()
+            .await
+    }
+
+    /// Map the VLAN priority `vlan_prio` (0 to 7) of the received frames to
+    /// the packet priority `skb_prio` (equivalent to `ip link set dev NAME
+    /// type vlan ingress-qos-map VLAN_PRIO:SKB_PRIO`)
+    pub fn ingress_qos_map(mut self, vlan_prio: u32, skb_prio: u32) -> Self {
+        self.ingress_qos.push(VlanQosMapping {
+            from: vlan_prio,
+            to: skb_prio,
+        });
+        self
+    }
+
+    /// Remove the ingress mapping of the VLAN priority `vlan_prio`, the
+    /// frames then getting the packet priority 0
+    pub fn del_ingress_qos_map(self, vlan_prio: u32) -> Self {
+        self.ingress_qos_map(vlan_prio, 0)
+    }
+
+    /// Map the packet priority `skb_prio` of the sent frames to the VLAN
+    /// priority `vlan_prio` (0 to 7) (equivalent to `ip link set dev NAME
+    /// type vlan egress-qos-map SKB_PRIO:VLAN_PRIO`)
+    pub fn egress_qos_map(mut self, skb_prio: u32, vlan_prio: u32) -> Self {
+        self.egress_qos.push(VlanQosMapping {
+            from: skb_prio,
+            to: vlan_prio,
+        });
+        self
+    }
+
+    /// Remove the egress mapping of the packet priority `skb_prio`, the
+    /// frames then getting the VLAN priority 0
+    pub fn del_egress_qos_map(self, skb_prio: u32) -> Self {
+        // The kernel drops the egress entries mapped to 0
+        self.egress_qos_map(skb_prio, 0)
+    }
+}
+
+/// An entry of a VLAN priority map: `IFLA_VLAN_QOS_MAPPING`, nested in
+/// `IFLA_VLAN_INGRESS_QOS` or `IFLA_VLAN_EGRESS_QOS`
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub(crate) struct VlanQosMapping {
+    pub(crate) from: u32,
+    pub(crate) to: u32,
+}
+
+impl nla::Nla for VlanQosMapping {
+    fn value_len(&self) -> usize {
+        8
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        NativeEndian::write_u32(&mut buffer[0..4], self.from);
+        NativeEndian::write_u32(&mut buffer[4..8], self.to);
+    }
+
+    fn kind(&self) -> u16 {
+        IFLA_VLAN_QOS_MAPPING
+    }
+}
+
+/// Check the VLAN priorities of the priority maps and push them to
+/// `info_data`, netlink-packet-route only taking their encoded entries
+pub(crate) fn push_vlan_qos(
+    info_data: &mut Vec<InfoVlan>,
+    ingress_qos: Vec<VlanQosMapping>,
+    egress_qos: Vec<VlanQosMapping>,
+) -> Result<(), Error> {
+    check_vlan_prio(&ingress_qos, |from, _| from)?;
+    check_vlan_prio(&egress_qos, |_, to| to)?;
+    if !ingress_qos.is_empty() {
+        info_data.push(InfoVlan::IngressQos(encode(&ingress_qos)));
+    }
+    if !egress_qos.is_empty() {
+        info_data.push(InfoVlan::EgressQos(encode(&egress_qos)));
+    }
+    Ok(())
+}
+
+fn check_vlan_prio(
+    mappings: &[VlanQosMapping],
+    vlan_prio: impl Fn(u32, u32) -> u32,
+) -> Result<(), Error> {
+    for &VlanQosMapping { from, to } in mappings {
+        if vlan_prio(from, to) > VLAN_PRIO_MAX {
+            return Err(Error::InvalidRequest(format!(
+                "invalid QoS mapping {}:{}, VLAN priorities range from 0 to {}",
+                from, to, VLAN_PRIO_MAX
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// A request to change the options of an existing VXLAN link. This is
+/// equivalent to `ip link set dev NAME type vxlan ...` commands.
+///
+/// Only the options that the kernel allows to change are available: the
+/// others, for instance the VNI, the port, the source port range or the
+/// maximum number of FDB entries, can only be set when creating the link.
+pub struct VxlanSetRequest {
+    request: LinkSetRequest,
+    info_data: Vec<InfoVxlan>,
+}
+
+impl VxlanSetRequest {
+    /// Execute the request.
+    pub async fn execute(self) -> Result<(), Error> {
+        let VxlanSetRequest { request, info_data } = self;
+        request
+            .link_info(InfoKind::Vxlan, Some(InfoData::Vxlan(info_data)))
+            .execute()
+            .await
+    }
+
+    /// Enable or disable the learning of the source addresses of the
+    /// received packets (equivalent to `ip link set dev NAME type vxlan
+    /// [no]learning`)
+    pub fn learning(mut self, enable: bool) -> Self {
+        self.info_data.push(InfoVxlan::Learning(enable as u8));
+        self
+    }
+
+    /// Set the lifetime of the learnt FDB entries, in seconds (equivalent to
+    /// `ip link set dev NAME type vxlan ageing SECONDS`)
+    pub fn ageing(mut self, seconds: u32) -> Self {
+        self.info_data.push(InfoVxlan::Ageing(seconds));
+        self
+    }
+
+    /// Set the TTL of the outgoing packets, 0 meaning inherit (equivalent
+    /// to `ip link set dev NAME type vxlan ttl TTL`)
+    pub fn ttl(mut self, ttl: u8) -> Self {
+        self.info_data.push(InfoVxlan::Ttl(ttl));
+        self
+    }
+
+    /// Set the TOS of the outgoing packets, 1 meaning inherit (equivalent
+    /// to `ip link set dev NAME type vxlan tos TOS`)
+    pub fn tos(mut self, tos: u8) -> Self {
+        self.info_data.push(InfoVxlan::Tos(tos));
+        self
+    }
+}
+
+const VLAN_PRIO_MAX: u32 = 7;
+const IFLA_VLAN_QOS_MAPPING: u16 = 1;
+// VLAN_VID_MASK, the VLAN 0 removing the tag of a VF
+const VF_VLAN_ID_MAX: u16 = 4095;
+
+/// `XDP_FLAGS_UPDATE_IF_NOEXIST`, only attach an XDP program if none is
+/// attached yet
+pub const XDP_FLAGS_UPDATE_IF_NOEXIST: u32 = 1 << 0;
+/// `XDP_FLAGS_SKB_MODE`, attach an XDP program in generic mode
+pub const XDP_FLAGS_SKB_MODE: u32 = 1 << 1;
+/// `XDP_FLAGS_DRV_MODE`, attach an XDP program in driver (native) mode
+pub const XDP_FLAGS_DRV_MODE: u32 = 1 << 2;
+/// `XDP_FLAGS_HW_MODE`, offload an XDP program to the device
+pub const XDP_FLAGS_HW_MODE: u32 = 1 << 3;
+/// `XDP_FLAGS_REPLACE`, only replace the expected XDP program, see
+/// [`LinkSetRequest::xdp_replace`]
+pub const XDP_FLAGS_REPLACE: u32 = 1 << 4;
+
+const IFLA_XDP_FD: u16 = 1;
+const IFLA_XDP_FLAGS: u16 = 3;
+const IFLA_XDP_EXPECTED_FD: u16 = 8;
+
+/// The attributes nested in `IFLA_XDP`, which netlink-packet-route only
+/// handles as raw bytes
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Xdp {
+    Fd(RawFd),
+    ExpectedFd(RawFd),
+    Flags(u32),
+}
+
+impl nla::Nla for Xdp {
+    fn value_len(&self) -> usize {
+        4
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        match *self {
+            Xdp::Fd(fd) | Xdp::ExpectedFd(fd) => {
+                NativeEndian::write_i32(buffer, fd)
+            }
+            Xdp::Flags(flags) => NativeEndian::write_u32(buffer, flags),
+        }
+    }
+
+    fn kind(&self) -> u16 {
+        match self {
+            Xdp::Fd(_) => IFLA_XDP_FD,
+            Xdp::ExpectedFd(_) => IFLA_XDP_EXPECTED_FD,
+            Xdp::Flags(_) => IFLA_XDP_FLAGS,
+        }
+    }
+}
 
-use crate::{try_nl, Error, Handle};
+// Attributes missing from `Nla`
+const IFLA_GRO_MAX_SIZE: u16 = 58;
+const IFLA_GSO_IPV4_MAX_SIZE: u16 = 63;
+const IFLA_GRO_IPV4_MAX_SIZE: u16 = 64;
+
+const IFLA_PROTO_DOWN_REASON_MASK: u16 = 1;
+const IFLA_PROTO_DOWN_REASON_VALUE: u16 = 2;
+
+// The attributes nested in IFLA_PROTO_DOWN_REASON
+enum ProtoDownReason {
+    Mask(u32),
+    Value(u32),
+}
+
+impl nla::Nla for ProtoDownReason {
+    fn value_len(&self) -> usize {
+        4
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        match self {
+            ProtoDownReason::Mask(value) | ProtoDownReason::Value(value) => {
+                NativeEndian::write_u32(buffer, *value)
+            }
+        }
+    }
+
+    fn kind(&self) -> u16 {
+        match self {
+            ProtoDownReason::Mask(_) => IFLA_PROTO_DOWN_REASON_MASK,
+            ProtoDownReason::Value(_) => IFLA_PROTO_DOWN_REASON_VALUE,
+        }
+    }
+}
+
+// IFLA_MACVLAN_MACADDR_MODE values
+const MACVLAN_MACADDR_ADD: u32 = 0;
+const MACVLAN_MACADDR_DEL: u32 = 1;
+const MACVLAN_MACADDR_FLUSH: u32 = 2;
+const MACVLAN_MACADDR_SET: u32 = 3;
+
+/// A request to change the options of an existing macvlan link. This is
+/// equivalent to `ip link set dev NAME type macvlan ...` commands.
+///
+/// In `source` mode (`MACVLAN_MODE_SOURCE`), the link only receives the
+/// frames whose source address is in its list of allowed addresses. The
+/// kernel applies a single change of this list per request: when several of
+/// the methods changing it are called, only the last one is kept.
+pub struct MacVlanSetRequest {
+    request: LinkSetRequest,
+    info_data: Vec<InfoMacVlan>,
+    source_macs: Vec<InfoMacVlan>,
+}
+
+impl MacVlanSetRequest {
+    /// Execute the request.
+    pub async fn execute(self) -> Result<(), Error> {
+        let MacVlanSetRequest {
+            request,
+            mut info_data,
+            source_macs,
+        } = self;
+        info_data.extend(source_macs);
+        request
+            .link_info(InfoKind::MacVlan, Some(InfoData::MacVlan(info_data)))
+            .execute()
+            .await
+    }
+
+    /// Change the mode of the link, a `MACVLAN_MODE_*` value (equivalent to
+    /// `ip link set dev NAME type macvlan mode MODE`)
+    pub fn mode(mut self, mode: u32) -> Self {
+        self.info_data.push(InfoMacVlan::Mode(mode));
+        self
+    }
+
+    /// Allow an address in source mode (equivalent to `ip link set dev NAME
+    /// type macvlan macaddr add ADDRESS`)
+    pub fn add_source_mac(mut self, address: MacAddress) -> Self {
+        self.source_macs = vec![
+            InfoMacVlan::MacAddrMode(MACVLAN_MACADDR_ADD),
+            InfoMacVlan::MacAddr(address.octets()),
+        ];
+        self
+    }
+
+    /// Remove an address from the allowed ones (equivalent to `ip link set
+    /// dev NAME type macvlan macaddr del ADDRESS`)
+    pub fn del_source_mac(mut self, address: MacAddress) -> Self {
+        self.source_macs = vec![
+            InfoMacVlan::MacAddrMode(MACVLAN_MACADDR_DEL),
+            InfoMacVlan::MacAddr(address.octets()),
+        ];
+        self
+    }
+
+    /// Remove all the allowed addresses (equivalent to `ip link set dev NAME
+    /// type macvlan macaddr flush`)
+    pub fn flush_source_macs(mut self) -> Self {
+        self.source_macs =
+            vec![InfoMacVlan::MacAddrMode(MACVLAN_MACADDR_FLUSH)];
+        self
+    }
+
+    /// Replace the allowed addresses (equivalent to `ip link set dev NAME
+    /// type macvlan macaddr set ADDRESS...`)
+    pub fn set_source_macs(mut self, addresses: &[MacAddress]) -> Self {
+        self.source_macs = vec![
+            InfoMacVlan::MacAddrMode(MACVLAN_MACADDR_SET),
+            InfoMacVlan::MacAddrData(
+                addresses
+                    .iter()
+                    .map(|address| InfoMacVlan::MacAddr(address.octets()))
+                    .collect(),
+            ),
+        ];
+        self
+    }
+}
+
+/// A request to change the settings of a virtual function (VF) of an SR-IOV
+/// capable link. This is equivalent to `ip link set dev NAME vf VF ...`
+/// commands.
+pub struct VfSetRequest {
+    request: LinkSetRequest,
+    vf: u32,
+    settings: Vec<VfSetting>,
+}
+
+impl VfSetRequest {
+    /// Execute the request. An [`Error::InvalidRequest`] is returned if the
+    /// VLAN id is above 4095 or the VLAN priority above 7.
+    pub async fn execute(self) -> Result<(), Error> {
+        let VfSetRequest {
+            mut request,
+            vf,
+            settings,
+        } = self;
+        for setting in &settings {
+            if let VfSetting::Vlan { id, qos }
+            | VfSetting::VlanWithProtocol { id, qos, .. } = *setting
+            {
+                if id > VF_VLAN_ID_MAX || qos as u32 > VLAN_PRIO_MAX {
+                    return Err(Error::InvalidRequest(format!(
+                        "invalid VF VLAN {} with priority {}",
+                        id, qos
+                    )));
+                }
+            }
+        }
+        request
+            .message
+            .nlas
+            .push(Nla::VfInfoList(vf_info_list(vf, &settings)));
+        request.execute().await
+    }
+
+    /// Set the hardware address of the VF (equivalent to `ip link set dev
+    /// NAME vf VF mac ADDRESS`)
+    pub fn mac(mut self, address: MacAddress) -> Self {
+        self.settings.push(VfSetting::Mac(address));
+        self
+    }
+
+    /// Tag the traffic of the VF with the given VLAN id and priority, the VLAN
+    /// 0 disabling the tagging (equivalent to `ip link set dev NAME vf VF vlan
+    /// VLAN_ID qos QOS`)
+    pub fn vlan(mut self, id: u16, qos: u8) -> Self {
+        self.settings.push(VfSetting::Vlan { id, qos });
+        self
+    }
+
+    /// Like [`Self::vlan`] with the given VLAN protocol, e.g. `0x88a8` for
+    /// 802.1ad (equivalent to `ip link set dev NAME vf VF vlan VLAN_ID qos
+    /// QOS proto { 802.1Q | 802.1ad }`)
+    pub fn vlan_with_protocol(
+        mut self,
+        id: u16,
+        qos: u8,
+        protocol: u16,
+    ) -> Self {
+        self.settings
+            .push(VfSetting::VlanWithProtocol { id, qos, protocol });
+        self
+    }
+
+    /// Set the minimum and maximum transmit rates of the VF, in Mbit/s, 0
+    /// meaning no limit (equivalent to `ip link set dev NAME vf VF
+    /// min_tx_rate MIN max_tx_rate MAX`). Both are set, since the kernel
+    /// resets the one which is missing.
+    pub fn tx_rate(mut self, min: u32, max: u32) -> Self {
+        self.settings.push(VfSetting::Rate { min, max });
+        self
+    }
+
+    /// Enable or disable the check of the source address of the frames sent
+    /// by the VF (equivalent to `ip link set dev NAME vf VF spoofchk { on |
+    /// off }`)
+    pub fn spoofchk(mut self, enable: bool) -> Self {
+        self.settings.push(VfSetting::SpoofCheck(enable));
+        self
+    }
+
+    /// Trust the VF, letting it change its address or enable promiscuous
+    /// mode (equivalent to `ip link set dev NAME vf VF trust { on | off }`)
+    pub fn trust(mut self, enable: bool) -> Self {
+        self.settings.push(VfSetting::Trust(enable));
+        self
+    }
+
+    /// Set the link state of the VF (equivalent to `ip link set dev NAME vf
+    /// VF state { auto | enable | disable }`)
+    pub fn link_state(mut self, state: VfLinkState) -> Self {
+        self.settings.push(VfSetting::LinkState(state));
+        self
+    }
+}
+
+/// A request to change the parameters of an existing CAN device. This is
+/// equivalent to `ip link set dev NAME type can ...` commands.
+///
+/// The kernel only accepts the bit timing and the control modes while the
+/// device is down.
+pub struct CanSetRequest {
+    request: LinkSetRequest,
+    bitrate: Option<u32>,
+    sample_point: Option<u32>,
+    ctrlmode_mask: u32,
+    ctrlmode_flags: u32,
+    restart_ms: Option<u32>,
+}
+
+impl CanSetRequest {
+    /// Execute the request. An [`Error::InvalidRequest`] is returned if a
+    /// sample point is set without a bitrate, or is not below 100%.
+    pub async fn execute(self) -> Result<(), Error> {
+        let CanSetRequest {
+            request,
+            bitrate,
+            sample_point,
+            ctrlmode_mask,
+            ctrlmode_flags,
+            restart_ms,
+        } = self;
+        let mut info_data = Vec::new();
+        match (bitrate, sample_point) {
+            (None, Some(_)) => {
+                return Err(Error::InvalidRequest(
+                    "a CAN sample point requires a bitrate".into(),
+                ))
+            }
+            (_, Some(sample_point)) if sample_point >= 1000 => {
+                return Err(Error::InvalidRequest(format!(
+                    "CAN sample point {} is out of range",
+                    sample_point
+                )))
+            }
+            (Some(bitrate), sample_point) => {
+                info_data.push(InfoCan::BitTiming {
+                    bitrate,
+                    sample_point: sample_point.unwrap_or(0),
+                })
+            }
+            (None, None) => (),
+        }
+        if ctrlmode_mask != 0 {
+            info_data.push(InfoCan::CtrlMode {
+                mask: ctrlmode_mask,
+                flags: ctrlmode_flags,
+            });
+        }
+        info_data.extend(restart_ms.map(InfoCan::RestartMs));
+        request
+            .link_info(
+                InfoKind::Other("can".into()),
+                Some(InfoData::Other(encode(&info_data))),
+            )
+            .execute()
+            .await
+    }
+
+    /// Set the bitrate, in bit/s, the kernel computing the timing parameters
+    /// (equivalent to `ip link set dev NAME type can bitrate BITRATE`)
+    pub fn bitrate(mut self, bitrate: u32) -> Self {
+        self.bitrate = Some(bitrate);
+        self
+    }
+
+    /// Set the sample point along with the bitrate, in tenths of a percent:
+    /// 875 stands for 87.5% (equivalent to `ip link set dev NAME type can
+    /// bitrate BITRATE sample-point 0.875`). The kernel picks a default one
+    /// otherwise.
+    pub fn sample_point(mut self, sample_point: u32) -> Self {
+        self.sample_point = Some(sample_point);
+        self
+    }
+
+    /// Enable or disable a control mode. Modes that are not set are left
+    /// unchanged. This is equivalent to `ip link set dev NAME type can MODE
+    /// { on | off }` for the modes listed in [`CanCtrlMode`].
+    pub fn ctrlmode(mut self, mode: CanCtrlMode, enable: bool) -> Self {
+        let bit = mode.bit();
+        self.ctrlmode_mask |= bit;
+        if enable {
+            self.ctrlmode_flags |= bit;
+        } else {
+            self.ctrlmode_flags &= !bit;
+        }
+        self
+    }
+
+    /// Set the delay before restarting the device after a bus-off
+    /// condition, in milliseconds, 0 disabling the automatic restart
+    /// (equivalent to `ip link set dev NAME type can restart-ms DELAY`)
+    pub fn restart_ms(mut self, delay: u32) -> Self {
+        self.restart_ms = Some(delay);
+        self
+    }
+}
+
+/// A request to change the parameters of an existing GRE (`gre` or
+/// `gretap`), IPIP or SIT tunnel in place. This is equivalent to `ip tunnel
+/// change`.
+///
+/// The kernel resets the parameters missing from such a request, so the
+/// current ones are read first and sent along with the changes. The remote
+/// endpoint of a layer 3 tunnel can be changed, but not set or cleared: the
+/// point to point state of the link can't change.
+pub struct TunnelSetRequest {
+    request: LinkSetRequest,
+    local: Option<Ipv4Addr>,
+    remote: Option<Ipv4Addr>,
+    ttl: Option<u8>,
+    tos: Option<u8>,
+    key: Option<u32>,
+}
+
+impl TunnelSetRequest {
+    /// Execute the request.
+    pub async fn execute(self) -> Result<(), Error> {
+        let TunnelSetRequest {
+            request,
+            local,
+            remote,
+            ttl,
+            tos,
+            key,
+        } = self;
+        let index = request.message.header.index;
+        let link = request
+            .handle
+            .clone()
+            .link()
+            .get()
+            .match_index(index)
+            .execute()
+            .try_next()
+            .await?
+            .ok_or(Error::RequestFailed)?;
+        let infos = link
+            .nlas
+            .into_iter()
+            .find_map(|nla| match nla {
+                Nla::Info(infos) => Some(infos),
+                _ => None,
+            })
+            .unwrap_or_default();
+        let mut kind = None;
+        let mut data = None;
+        for info in infos {
+            match info {
+                Info::Kind(k) => kind = Some(k),
+                Info::Data(
+                    InfoData::GreTun(bytes)
+                    | InfoData::GreTap(bytes)
+                    | InfoData::IpTun(bytes)
+                    | InfoData::SitTun(bytes),
+                ) => data = Some(bytes),
+                _ => (),
+            }
+        }
+        let data = data.unwrap_or_default();
+
+        let data = match kind {
+            Some(InfoKind::GreTun | InfoKind::GreTap) => {
+                let mut changes = Vec::new();
+                changes.extend(local.map(InfoGre::Local));
+                changes.extend(remote.map(InfoGre::Remote));
+                changes.extend(ttl.map(InfoGre::Ttl));
+                changes.extend(tos.map(InfoGre::Tos));
+                if let Some(key) = key {
+                    changes.extend(tunnel::gre_key(&data, key));
+                }
+                tunnel::merge(&data, &changes)
+            }
+            Some(InfoKind::IpTun | InfoKind::SitTun) => {
+                if key.is_some() {
+                    return Err(Error::InvalidRequest(
+                        "keys are only supported by GRE tunnels".into(),
+                    ));
+                }
+                let mut changes = Vec::new();
+                changes.extend(local.map(InfoIpTun::Local));
+                changes.extend(remote.map(InfoIpTun::Remote));
+                changes.extend(ttl.map(InfoIpTun::Ttl));
+                changes.extend(tos.map(InfoIpTun::Tos));
+                tunnel::merge(&data, &changes)
+            }
+            _ => {
+                return Err(Error::InvalidRequest(format!(
+                    "link {} is not a GRE, IPIP or SIT tunnel",
+                    index
+                )))
+            }
+        };
+        let (kind, data) = match kind {
+            Some(InfoKind::GreTun) => {
+                (InfoKind::GreTun, InfoData::GreTun(data))
+            }
+            Some(InfoKind::GreTap) => {
+                (InfoKind::GreTap, InfoData::GreTap(data))
+            }
+            Some(InfoKind::IpTun) => (InfoKind::IpTun, InfoData::IpTun(data)),
+            _ => (InfoKind::SitTun, InfoData::SitTun(data)),
+        };
+        request.link_info(kind, Some(data)).execute().await
+    }
+
+    /// Set the address of the local endpoint (equivalent to `ip tunnel
+    /// change NAME local ADDR`)
+    pub fn local(mut self, addr: Ipv4Addr) -> Self {
+        self.local = Some(addr);
+        self
+    }
+
+    /// Set the address of the remote endpoint (equivalent to `ip tunnel
+    /// change NAME remote ADDR`)
+    pub fn remote(mut self, addr: Ipv4Addr) -> Self {
+        self.remote = Some(addr);
+        self
+    }
+
+    /// Set the TTL of the outgoing packets, 0 meaning inherit (equivalent to
+    /// `ip tunnel change NAME ttl TTL`)
+    pub fn ttl(mut self, ttl: u8) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    /// Set the TOS of the outgoing packets, 1 meaning inherit (equivalent to
+    /// `ip tunnel change NAME tos TOS`)
+    pub fn tos(mut self, tos: u8) -> Self {
+        self.tos = Some(tos);
+        self
+    }
+
+    /// Set the key of both directions of a GRE tunnel (equivalent to `ip
+    /// tunnel change NAME key KEY`)
+    pub fn key(mut self, key: u32) -> Self {
+        self.key = Some(key);
+        self
+    }
+}
 
 pub struct LinkSetRequest {
     handle: Handle,
     message: LinkMessage,
+    // Name of the master, resolved when the request is executed
+    master_name: Option<String>,
 }
 
 impl LinkSetRequest {
     pub(crate) fn new(handle: Handle, index: u32) -> Self {
         let mut message = LinkMessage::default();
         message.header.index = index;
-        LinkSetRequest { handle, message }
+        LinkSetRequest {
+            handle,
+            message,
+            master_name: None,
+        }
+    }
+
+    pub(crate) fn new_by_name(handle: Handle, name: String) -> Self {
+        let mut request = Self::new(handle, 0);
+        request.message.nlas.push(Nla::IfName(name));
+        request
     }
 
     /// Execute the request
     pub async fn execute(self) -> Result<(), Error> {
         let LinkSetRequest {
             mut handle,
-            message,
+            mut message,
+            master_name,
         } = self;
-        let mut req = NetlinkMessage::from(RtnlMessage::SetLink(message));
-        req.header.flags =
-            NLM_F_REQUEST | NLM_F_ACK | NLM_F_EXCL | NLM_F_CREATE;
+        // Without an index, the kernel looks the link up by the (last)
+        // IFLA_IFNAME attribute, which can then not rename it.
+        let names = message
+            .nlas
+            .iter()
+            .filter(|nla| matches!(nla, Nla::IfName(_)));
+        if message.header.index == 0 && names.count() > 1 {
+            return Err(Error::InvalidRequest(
+                "a link looked up by name cannot be renamed".into(),
+            ));
+        }
+        if let Some(name) = master_name {
+            let index =
+                resolve(handle.clone(), name.clone()).await?.ok_or_else(
+                    || Error::InvalidRequest(format!("no link named {}", name)),
+                )?;
+            message.nlas.push(Nla::Master(index));
+        }
+        // The kernel ignores IFLA_LINKINFO in RTM_SETLINK: changing the
+        // kind specific attributes of an existing link requires a
+        // RTM_NEWLINK request without NLM_F_CREATE and NLM_F_EXCL.
+        let has_link_info =
+            message.nlas.iter().any(|nla| matches!(nla, Nla::Info(_)));
+        let mut req = if has_link_info {
+            NetlinkMessage::from(RtnlMessage::NewLink(message))
+        } else {
+            NetlinkMessage::from(RtnlMessage::SetLink(message))
+        };
+        req.header.flags = NLM_F_REQUEST | NLM_F_ACK;
+        if !has_link_info {
+            req.header.flags |= NLM_F_EXCL | NLM_F_CREATE;
+        }
 
-        let mut response = handle.request(req)?;
+        let mut response = handle.try_request(req)?;
         while let Some(message) = response.next().await {
-            try_nl!(message);
+            try_nl!(message?);
         }
         Ok(())
     }
@@ -53,16 +1034,32 @@ impl LinkSetRequest {
     /// To Remove a link from a bridge, set its master to zero.
     /// This is equvalent to `ip link set LINK nomaster`
     pub fn master(mut self, master_index: u32) -> Self {
+        self.master_name = None;
         self.message.nlas.push(Nla::Master(master_index));
         self
     }
 
+    /// Same as [`Self::master`], with the name of the master instead of its
+    /// index. The name is resolved with [`crate::LinkHandle::resolve`] when
+    /// the request is executed, which fails with [`Error::InvalidRequest`]
+    /// if there is no such link.
+    pub fn master_by_name(mut self, master_name: &str) -> Self {
+        self.master_name = Some(master_name.to_string());
+        self
+    }
+
+    /// Enslave the link to the VRF device with the given index, so that its
+    /// routes move to the table of the VRF. This is equivalent to `ip link
+    /// set LINK vrf NAME`, and is undone with [`Self::nomaster`].
+    pub fn controller_vrf(self, vrf_index: u32) -> Self {
+        self.master(vrf_index)
+    }
+
     /// Detach the link from its _master_. This is equivalent to `ip link set
     /// LINK nomaster`. To succeed, the link that is being detached must be
     /// UP.
-    pub fn nomaster(mut self) -> Self {
-        self.message.nlas.push(Nla::Master(0u32));
-        self
+    pub fn nomaster(self) -> Self {
+        self.master(0)
     }
 
     /// Set the link with the given index up (equivalent to `ip link set dev DEV
@@ -119,6 +1116,105 @@ impl LinkSetRequest {
         self
     }
 
+    /// Set the device group of the link with the given index, 0 being the
+    /// default one (equivalent to `ip link set DEV group GROUP`). The links
+    /// of a group are listed with [`crate::LinkGetRequest::match_group`].
+    pub fn group(mut self, group: u32) -> Self {
+        self.message.nlas.push(Nla::Group(group));
+        self
+    }
+
+    /// Set the length of the transmit queue of the link with the given index
+    /// (equivalent to `ip link set DEV txqueuelen LENGTH`)
+    pub fn txqueuelen(mut self, length: u32) -> Self {
+        self.message.nlas.push(Nla::TxQueueLen(length));
+        self
+    }
+
+    /// Set the largest GSO packet the stack builds for the link, in bytes,
+    /// at most its `tso_max_size` (equivalent to `ip link set DEV
+    /// gso_max_size SIZE`). Sizes above 64 KiB enable BIG TCP for IPv6.
+    pub fn gso_max_size(mut self, size: u32) -> Self {
+        self.message.nlas.push(Nla::GsoMaxSize(size));
+        self
+    }
+
+    /// Set the largest number of segments of a GSO packet, at most its
+    /// `tso_max_segs` (equivalent to `ip link set DEV gso_max_segs SEGMENTS`)
+    pub fn gso_max_segs(mut self, segments: u32) -> Self {
+        self.message.nlas.push(Nla::GsoMaxSegs(segments));
+        self
+    }
+
+    /// Set the largest packet GRO builds on the link, in bytes (equivalent
+    /// to `ip link set DEV gro_max_size SIZE`)
+    pub fn gro_max_size(self, size: u32) -> Self {
+        self.u32_nla(IFLA_GRO_MAX_SIZE, size)
+    }
+
+    /// Set the largest IPv4 GSO packet, in bytes, sizes above 64 KiB enabling
+    /// BIG TCP for IPv4 (equivalent to `ip link set DEV gso_ipv4_max_size
+    /// SIZE`). This requires Linux 6.3 or later.
+    pub fn gso_ipv4_max_size(self, size: u32) -> Self {
+        self.u32_nla(IFLA_GSO_IPV4_MAX_SIZE, size)
+    }
+
+    /// Set the largest IPv4 packet GRO builds on the link, in bytes
+    /// (equivalent to `ip link set DEV gro_ipv4_max_size SIZE`). This
+    /// requires Linux 6.3 or later.
+    pub fn gro_ipv4_max_size(self, size: u32) -> Self {
+        self.u32_nla(IFLA_GRO_IPV4_MAX_SIZE, size)
+    }
+
+    fn u32_nla(mut self, kind: u16, value: u32) -> Self {
+        let mut buf = vec![0; 4];
+        NativeEndian::write_u32(&mut buf, value);
+        self.message
+            .nlas
+            .push(Nla::Other(nla::DefaultNla::new(kind, buf)));
+        self
+    }
+
+    /// Attach the XDP program of the file descriptor `fd` to the link, in
+    /// the mode selected by `flags`, a combination of the `XDP_FLAGS_*`
+    /// constants (equivalent to `ip link set dev DEV xdp{generic,drv,offload}
+    /// fd FD`). Without a mode flag, the driver mode is used if supported,
+    /// the generic one otherwise.
+    pub fn xdp_fd(mut self, fd: RawFd, flags: u32) -> Self {
+        let mut xdp = vec![Xdp::Fd(fd)];
+        if flags != 0 {
+            xdp.push(Xdp::Flags(flags));
+        }
+        self.message.nlas.push(Nla::Xdp(encode(&xdp)));
+        self
+    }
+
+    /// Atomically replace the XDP program of the file descriptor
+    /// `expected_fd` with the one of `fd`, failing if another program is
+    /// attached in the mode selected by `flags`.
+    pub fn xdp_replace(
+        mut self,
+        fd: RawFd,
+        expected_fd: RawFd,
+        flags: u32,
+    ) -> Self {
+        self.message.nlas.push(Nla::Xdp(encode(&[
+            Xdp::Fd(fd),
+            Xdp::ExpectedFd(expected_fd),
+            Xdp::Flags(flags | XDP_FLAGS_REPLACE),
+        ])));
+        self
+    }
+
+    /// Detach the XDP program of the link (equivalent to `ip link set dev
+    /// DEV xdp off`). Like [`Self::xdp_fd`] without a mode flag, this
+    /// applies to the driver mode if supported: a program attached in
+    /// another mode is detached with `xdp_fd(-1, XDP_FLAGS_SKB_MODE)` or
+    /// `xdp_fd(-1, XDP_FLAGS_HW_MODE)`.
+    pub fn xdp_detach(self) -> Self {
+        self.xdp_fd(-1, 0)
+    }
+
     /// Set the hardware address of the link with the given index (equivalent to
     /// `ip link set DEV address ADDRESS`)
     pub fn address(mut self, address: Vec<u8>) -> Self {
@@ -126,6 +1222,150 @@ impl LinkSetRequest {
         self
     }
 
+    /// Set the Ethernet address of the link with the given index (equivalent
+    /// to `ip link set DEV address ADDRESS`)
+    pub fn mac_address(self, address: MacAddress) -> Self {
+        self.address(address.into())
+    }
+
+    /// Set the carrier state of the link, to emulate a link flap (equivalent
+    /// to `ip link set dev DEV carrier { on | off }`). Only the drivers of
+    /// virtual links, for instance dummy, support it.
+    pub fn carrier(mut self, enable: bool) -> Self {
+        self.message.nlas.push(Nla::Carrier(enable as u8));
+        self
+    }
+
+    /// Set or clear the protodown state of the link, which keeps its
+    /// carrier down whatever its lower device (equivalent to `ip link set
+    /// dev DEV protodown { on | off }`). Only some drivers, for instance
+    /// vxlan and macvlan, support it, and the kernel refuses to clear it
+    /// while a reason is set.
+    pub fn protodown(mut self, enable: bool) -> Self {
+        self.message.nlas.push(Nla::ProtoDown(enable as u8));
+        self
+    }
+
+    /// Set the protodown reason bits selected by `mask` to their values in
+    /// `value`, the other bits being left unchanged (equivalent to `ip link
+    /// set dev DEV protodown_reason REASON { on | off }` for each bit of
+    /// `mask`). Only the last call is taken into account.
+    pub fn protodown_reason(mut self, mask: u32, value: u32) -> Self {
+        self.message
+            .nlas
+            .retain(|nla| !matches!(nla, Nla::ProtoDownReason(_)));
+        let reason = encode(&[
+            ProtoDownReason::Mask(mask),
+            ProtoDownReason::Value(value),
+        ]);
+        self.message.nlas.push(Nla::ProtoDownReason(reason));
+        self
+    }
+
+    /// Change the kind specific attributes (`IFLA_INFO_DATA`) of the link. The
+    /// `kind` must match the kind of the existing link.
+    pub fn link_info(mut self, kind: InfoKind, data: Option<InfoData>) -> Self {
+        let mut link_info_nlas = vec![Info::Kind(kind)];
+        if let Some(data) = data {
+            link_info_nlas.push(Info::Data(data));
+        }
+        self.message.nlas.push(Nla::Info(link_info_nlas));
+        self
+    }
+
+    /// Change the options of a bridge. This is equivalent to `ip link set dev
+    /// NAME type bridge`, it returns a BridgeSetRequest to specify the
+    /// options to change.
+    pub fn bridge(self) -> BridgeSetRequest {
+        BridgeSetRequest {
+            request: self,
+            info_data: vec![],
+            boolopt_value: 0,
+            boolopt_mask: 0,
+        }
+    }
+
+    /// Change the options of a bond. This is equivalent to `ip link set dev
+    /// NAME type bond`, it returns a BondSetRequest to specify the options to
+    /// change.
+    pub fn bond(self) -> BondSetRequest {
+        BondSetRequest {
+            request: self,
+            info_data: vec![],
+        }
+    }
+
+    /// Change the parameters of a GRE, IPIP or SIT tunnel. This is equivalent
+    /// to `ip tunnel change`, it returns a TunnelSetRequest to specify the
+    /// parameters to change.
+    pub fn tunnel(self) -> TunnelSetRequest {
+        TunnelSetRequest {
+            request: self,
+            local: None,
+            remote: None,
+            ttl: None,
+            tos: None,
+            key: None,
+        }
+    }
+
+    /// Change the options of a VLAN link. This is equivalent to `ip link set
+    /// dev NAME type vlan`, it returns a VlanSetRequest to specify the options
+    /// to change.
+    pub fn vlan(self) -> VlanSetRequest {
+        VlanSetRequest {
+            request: self,
+            ingress_qos: vec![],
+            egress_qos: vec![],
+        }
+    }
+
+    /// Change the options of a VXLAN link. This is equivalent to `ip link
+    /// set dev NAME type vxlan`, it returns a VxlanSetRequest to specify the
+    /// options to change.
+    pub fn vxlan(self) -> VxlanSetRequest {
+        VxlanSetRequest {
+            request: self,
+            info_data: vec![],
+        }
+    }
+
+    /// Change the options of a macvlan link. This is equivalent to `ip link
+    /// set dev NAME type macvlan`, it returns a MacVlanSetRequest to specify
+    /// the options to change.
+    pub fn macvlan(self) -> MacVlanSetRequest {
+        MacVlanSetRequest {
+            request: self,
+            info_data: vec![],
+            source_macs: vec![],
+        }
+    }
+
+    /// Change the settings of the virtual function `vf` of an SR-IOV capable
+    /// link. This is equivalent to `ip link set dev NAME vf VF`, it returns a
+    /// VfSetRequest to specify the settings to change.
+    pub fn vf(self, vf: u32) -> VfSetRequest {
+        VfSetRequest {
+            request: self,
+            vf,
+            settings: vec![],
+        }
+    }
+
+    /// Change the parameters of a CAN device. This is equivalent to `ip link
+    /// set dev NAME type can`, it returns a CanSetRequest to specify the
+    /// parameters to change.
+    pub fn can(self) -> CanSetRequest {
+        CanSetRequest {
+            request: self,
+            bitrate: None,
+            sample_point: None,
+            ctrlmode_mask: 0,
+            ctrlmode_flags: 0,
+            restart_ms: None,
+        }
+    }
+
     /// Move this network device into the network namespace of the process with
     /// the given `pid`.
     pub fn setns_by_pid(mut self, pid: u32) -> Self {
@@ -140,3 +1380,552 @@ impl LinkSetRequest {
         self
     }
 }
+
+#[cfg(test)]
+mod test {
+    use futures::stream::TryStreamExt;
+    use netlink_packet_core::NetlinkPayload;
+    use netlink_packet_route::MACVLAN_MODE_SOURCE;
+    use tokio::runtime::Runtime;
+
+    use super::*;
+    use crate::{
+        link::test::{dry_run_link, info_data},
+        MockNetwork,
+    };
+
+    #[test]
+    fn test_bridge_options() {
+        let message = dry_run_link(|handle| {
+            handle
+                .link()
+                .set(3)
+                .bridge()
+                .stp_state(1)
+                .priority(4096)
+                .forward_delay(1500)
+                .hello_time(200)
+                .max_age(2000)
+                .ageing_time(30000)
+                .vlan_filtering(true)
+                .vlan_protocol(0x88a8)
+                .vlan_default_pvid(10)
+                .mcast_snooping(false)
+                .execute()
+        })
+        .unwrap();
+        assert_eq!(message.header.index, 3);
+        assert_eq!(
+            info_data(&message),
+            &InfoData::Bridge(vec![
+                InfoBridge::StpState(1),
+                InfoBridge::Priority(4096),
+                InfoBridge::ForwardDelay(1500),
+                InfoBridge::HelloTime(200),
+                InfoBridge::MaxAge(2000),
+                InfoBridge::AgeingTime(30000),
+                InfoBridge::VlanFiltering(1),
+                InfoBridge::VlanProtocol(0x88a8),
+                InfoBridge::VlanDefaultPvid(10),
+                InfoBridge::MulticastSnooping(0),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_bridge_boolopt() {
+        let message = dry_run_link(|handle| {
+            handle
+                .link()
+                .set(3)
+                .bridge()
+                .boolopt(BridgeBoolOpt::NoLinkLocalLearn, true)
+                .boolopt(BridgeBoolOpt::MstEnabled, true)
+                .boolopt(BridgeBoolOpt::MstEnabled, false)
+                .execute()
+        })
+        .unwrap();
+        let value = match info_data(&message) {
+            InfoData::Bridge(nlas) => match nlas.as_slice() {
+                [InfoBridge::MultiBoolOpt(value)] => *value,
+                nlas => panic!("unexpected bridge options {:?}", nlas),
+            },
+            data => panic!("unexpected info data {:?}", data),
+        };
+        let mut payload = [0; 8];
+        NativeEndian::write_u64(&mut payload, value);
+        // optval: only no_linklocal_learn is set
+        assert_eq!(NativeEndian::read_u32(&payload[..4]), 1 << 0);
+        // optmask: both options are changed
+        assert_eq!(NativeEndian::read_u32(&payload[4..]), 1 << 0 | 1 << 2);
+    }
+
+    #[test]
+    fn test_bridge_vlan_stats() {
+        let message = dry_run_link(|handle| {
+            handle
+                .link()
+                .set(3)
+                .bridge()
+                .vlan_stats_enabled(true)
+                .vlan_stats_per_port(false)
+                .execute()
+        })
+        .unwrap();
+        assert_eq!(
+            info_data(&message),
+            &InfoData::Bridge(vec![
+                InfoBridge::VlanStatsEnabled(1),
+                InfoBridge::VlanStatsPerHost(0),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_vlan_qos_maps() {
+        let res = dry_run_link(|handle| {
+            handle
+                .link()
+                .set(4242)
+                .vlan()
+                .ingress_qos_map(8, 1)
+                .execute()
+        });
+        assert!(matches!(res, Err(Error::InvalidRequest(_))));
+
+        let message = dry_run_link(|handle| {
+            handle
+                .link()
+                .set(4242)
+                .vlan()
+                .ingress_qos_map(5, 3)
+                .del_egress_qos_map(2)
+                .execute()
+        })
+        .unwrap();
+        assert_eq!(
+            info_data(&message),
+            &InfoData::Vlan(vec![
+                InfoVlan::IngressQos(encode(&[VlanQosMapping {
+                    from: 5,
+                    to: 3
+                }])),
+                InfoVlan::EgressQos(encode(&[VlanQosMapping {
+                    from: 2,
+                    to: 0
+                }])),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_xdp_attach_replace_and_detach() {
+        let message = dry_run_link(|handle| {
+            handle
+                .link()
+                .set(4242)
+                .xdp_fd(7, XDP_FLAGS_SKB_MODE)
+                .execute()
+        })
+        .unwrap();
+        assert_eq!(
+            message.nlas,
+            vec![Nla::Xdp(encode(&[
+                Xdp::Fd(7),
+                Xdp::Flags(XDP_FLAGS_SKB_MODE)
+            ]))]
+        );
+
+        let message = dry_run_link(|handle| {
+            handle
+                .link()
+                .set(4242)
+                .xdp_replace(8, 7, XDP_FLAGS_SKB_MODE)
+                .execute()
+        })
+        .unwrap();
+        assert_eq!(
+            message.nlas,
+            vec![Nla::Xdp(encode(&[
+                Xdp::Fd(8),
+                Xdp::ExpectedFd(7),
+                Xdp::Flags(XDP_FLAGS_SKB_MODE | XDP_FLAGS_REPLACE),
+            ]))]
+        );
+
+        let message = dry_run_link(|handle| {
+            handle.link().set(4242).xdp_detach().execute()
+        })
+        .unwrap();
+        // IFLA_XDP_FD -1
+        assert_eq!(
+            message.nlas,
+            vec![Nla::Xdp(vec![8, 0, 1, 0, 0xff, 0xff, 0xff, 0xff])]
+        );
+    }
+
+    #[test]
+    fn test_vxlan() {
+        let (handle, dry_run) = MockNetwork::new().handle().dry_run();
+        futures::executor::block_on(
+            handle
+                .link()
+                .set(4242)
+                .vxlan()
+                .learning(false)
+                .ageing(100)
+                .ttl(9)
+                .execute(),
+        )
+        .unwrap();
+
+        // The kind specific attributes are only applied by RTM_NEWLINK,
+        // without NLM_F_CREATE
+        let messages = dry_run.messages();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].header.flags, NLM_F_REQUEST | NLM_F_ACK);
+        let message = match &messages[0].payload {
+            NetlinkPayload::InnerMessage(RtnlMessage::NewLink(message)) => {
+                message
+            }
+            payload => panic!("unexpected payload {:?}", payload),
+        };
+        assert_eq!(
+            info_data(message),
+            &InfoData::Vxlan(vec![
+                InfoVxlan::Learning(0),
+                InfoVxlan::Ageing(100),
+                InfoVxlan::Ttl(9),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_too_many_bond_targets() {
+        let targets: Vec<_> =
+            (1..=16).map(|i| Ipv4Addr::new(192, 0, 2, i)).collect();
+        let res = dry_run_link(|handle| {
+            handle
+                .link()
+                .set(4242)
+                .bond()
+                .arp_ip_target(targets.clone())
+                .ns_ip6_target(vec![Ipv6Addr::LOCALHOST; 17])
+                .execute()
+        });
+        assert!(matches!(res, Err(Error::InvalidRequest(_))));
+
+        let message = dry_run_link(|handle| {
+            handle
+                .link()
+                .set(4242)
+                .bond()
+                .arp_ip_target(targets.clone())
+                .execute()
+        })
+        .unwrap();
+        assert_eq!(
+            info_data(&message),
+            &InfoData::Bond(vec![InfoBond::ArpIpTarget(targets)])
+        );
+    }
+
+    #[test]
+    fn test_controller_vrf() {
+        let message = dry_run_link(|handle| {
+            handle.link().set(4242).controller_vrf(4243).execute()
+        })
+        .unwrap();
+        assert_eq!(message.header.index, 4242);
+        assert_eq!(message.nlas, vec![Nla::Master(4243)]);
+    }
+
+    #[test]
+    fn test_can_parameters() {
+        let res = dry_run_link(|handle| {
+            handle.link().set(4242).can().sample_point(875).execute()
+        });
+        assert!(matches!(res, Err(Error::InvalidRequest(_))));
+
+        let message = dry_run_link(|handle| {
+            handle
+                .link()
+                .set(4242)
+                .can()
+                .bitrate(500_000)
+                .sample_point(875)
+                .ctrlmode(CanCtrlMode::OneShot, true)
+                .ctrlmode(CanCtrlMode::Loopback, false)
+                .restart_ms(100)
+                .execute()
+        })
+        .unwrap();
+        assert!(message.nlas.contains(&Nla::Info(vec![
+            Info::Kind(InfoKind::Other("can".into())),
+            Info::Data(InfoData::Other(encode(&[
+                InfoCan::BitTiming {
+                    bitrate: 500_000,
+                    sample_point: 875,
+                },
+                InfoCan::CtrlMode {
+                    mask: CanCtrlMode::OneShot.bit()
+                        | CanCtrlMode::Loopback.bit(),
+                    flags: CanCtrlMode::OneShot.bit(),
+                },
+                InfoCan::RestartMs(100),
+            ]))),
+        ])));
+    }
+
+    #[test]
+    fn test_carrier() {
+        let message = dry_run_link(|handle| {
+            handle.link().set(4242).carrier(false).execute()
+        })
+        .unwrap();
+        assert_eq!(message.header.index, 4242);
+        assert_eq!(message.nlas, vec![Nla::Carrier(0)]);
+    }
+
+    #[test]
+    fn test_vf_settings() {
+        let res = dry_run_link(|handle| {
+            handle.link().set(4242).vf(3).vlan(4096, 0).execute()
+        });
+        assert!(matches!(res, Err(Error::InvalidRequest(_))));
+
+        let message = dry_run_link(|handle| {
+            handle
+                .link()
+                .set(4242)
+                .vf(3)
+                .mac(MacAddress([0x02, 0, 0, 0, 0, 1]))
+                .tx_rate(100, 1000)
+                .trust(true)
+                .link_state(VfLinkState::Disable)
+                .execute()
+        })
+        .unwrap();
+        assert_eq!(
+            message.nlas,
+            vec![Nla::VfInfoList(vf_info_list(
+                3,
+                &[
+                    VfSetting::Mac(MacAddress([0x02, 0, 0, 0, 0, 1])),
+                    VfSetting::Rate {
+                        min: 100,
+                        max: 1000
+                    },
+                    VfSetting::Trust(true),
+                    VfSetting::LinkState(VfLinkState::Disable),
+                ]
+            ))]
+        );
+    }
+
+    #[test]
+    fn test_macvlan_source_macs() {
+        let mac1 = MacAddress([0x02, 0, 0, 0, 0, 1]);
+        let mac2 = MacAddress([0x02, 0, 0, 0, 0, 2]);
+        let message = dry_run_link(|handle| {
+            handle
+                .link()
+                .set(4242)
+                .macvlan()
+                .add_source_mac(mac1)
+                .execute()
+        })
+        .unwrap();
+        assert_eq!(
+            info_data(&message),
+            &InfoData::MacVlan(vec![
+                InfoMacVlan::MacAddrMode(MACVLAN_MACADDR_ADD),
+                InfoMacVlan::MacAddr(mac1.octets()),
+            ])
+        );
+
+        let message = dry_run_link(|handle| {
+            handle
+                .link()
+                .set(4242)
+                .macvlan()
+                .del_source_mac(mac2)
+                .execute()
+        })
+        .unwrap();
+        assert_eq!(
+            info_data(&message),
+            &InfoData::MacVlan(vec![
+                InfoMacVlan::MacAddrMode(MACVLAN_MACADDR_DEL),
+                InfoMacVlan::MacAddr(mac2.octets()),
+            ])
+        );
+
+        let message = dry_run_link(|handle| {
+            handle
+                .link()
+                .set(4242)
+                .macvlan()
+                .flush_source_macs()
+                .execute()
+        })
+        .unwrap();
+        assert_eq!(
+            info_data(&message),
+            &InfoData::MacVlan(vec![InfoMacVlan::MacAddrMode(
+                MACVLAN_MACADDR_FLUSH
+            )])
+        );
+
+        let message = dry_run_link(|handle| {
+            handle
+                .link()
+                .set(4242)
+                .macvlan()
+                .mode(MACVLAN_MODE_SOURCE)
+                .set_source_macs(&[mac1, mac2])
+                .execute()
+        })
+        .unwrap();
+        assert_eq!(
+            info_data(&message),
+            &InfoData::MacVlan(vec![
+                InfoMacVlan::Mode(MACVLAN_MODE_SOURCE),
+                InfoMacVlan::MacAddrMode(MACVLAN_MACADDR_SET),
+                InfoMacVlan::MacAddrData(vec![
+                    InfoMacVlan::MacAddr(mac1.octets()),
+                    InfoMacVlan::MacAddr(mac2.octets()),
+                ]),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_protodown_with_reason() {
+        let message = dry_run_link(|handle| {
+            handle
+                .link()
+                .set(4242)
+                .protodown(true)
+                .protodown_reason(0b11, 0b10)
+                .protodown_reason(0b101, 0b001)
+                .execute()
+        })
+        .unwrap();
+        // IFLA_PROTO_DOWN_REASON_MASK and IFLA_PROTO_DOWN_REASON_VALUE
+        let mut reason = vec![8, 0, 1, 0];
+        reason.extend_from_slice(&0b101u32.to_ne_bytes());
+        reason.extend_from_slice(&[8, 0, 2, 0]);
+        reason.extend_from_slice(&0b001u32.to_ne_bytes());
+        assert_eq!(
+            message.nlas,
+            vec![Nla::ProtoDown(1), Nla::ProtoDownReason(reason)]
+        );
+    }
+
+    #[test]
+    fn test_txqueuelen() {
+        let message = dry_run_link(|handle| {
+            handle.link().set(4242).txqueuelen(500).execute()
+        })
+        .unwrap();
+        assert_eq!(message.header.index, 4242);
+        assert_eq!(message.nlas, vec![Nla::TxQueueLen(500)]);
+    }
+
+    #[test]
+    fn test_gso_gro_max_sizes() {
+        let message = dry_run_link(|handle| {
+            handle
+                .link()
+                .set(4242)
+                .gso_max_size(32768)
+                .gso_max_segs(1000)
+                .gro_max_size(16384)
+                .gso_ipv4_max_size(65536)
+                .gro_ipv4_max_size(131072)
+                .execute()
+        })
+        .unwrap();
+        assert_eq!(
+            message.nlas[..2],
+            [Nla::GsoMaxSize(32768), Nla::GsoMaxSegs(1000)]
+        );
+        let mut raw = Vec::new();
+        // IFLA_GRO_MAX_SIZE, IFLA_GSO_IPV4_MAX_SIZE and
+        // IFLA_GRO_IPV4_MAX_SIZE
+        for (kind, value) in [(58u16, 16384u32), (63, 65536), (64, 131072)] {
+            raw.extend_from_slice(&8u16.to_ne_bytes());
+            raw.extend_from_slice(&kind.to_ne_bytes());
+            raw.extend_from_slice(&value.to_ne_bytes());
+        }
+        assert_eq!(encode(&message.nlas[2..]), raw);
+    }
+
+    #[test]
+    fn test_group() {
+        let message =
+            dry_run_link(|handle| handle.link().set(4242).group(1).execute())
+                .unwrap();
+        assert_eq!(message.header.index, 4242);
+        assert_eq!(message.nlas, vec![Nla::Group(1)]);
+    }
+
+    #[test]
+    fn test_set_by_name() {
+        let res = dry_run_link(|handle| {
+            handle
+                .link()
+                .set_by_name("br0".into())
+                .name("br1".into())
+                .execute()
+        });
+        assert!(matches!(res, Err(Error::InvalidRequest(_))));
+
+        let message = dry_run_link(|handle| {
+            handle
+                .link()
+                .set_by_name("br0".into())
+                .mtu(1400)
+                .up()
+                .execute()
+        })
+        .unwrap();
+        assert_eq!(message.header.index, 0);
+        assert_eq!(message.header.flags, IFF_UP);
+        assert_eq!(
+            message.nlas,
+            vec![Nla::IfName("br0".into()), Nla::Mtu(1400)]
+        );
+    }
+
+    #[test]
+    fn test_master_by_name() {
+        let rt = Runtime::new().unwrap();
+        rt.block_on(async {
+            let link = MockNetwork::new().handle().link();
+            link.add().bridge("br0".into()).execute().await.unwrap();
+            link.add().dummy("dummy0".into()).execute().await.unwrap();
+            let bridge = link.resolve("br0").await.unwrap().unwrap();
+            let port = link.resolve("dummy0").await.unwrap().unwrap();
+
+            let res = link.set(port).master_by_name("br1").execute().await;
+            assert!(matches!(res, Err(Error::InvalidRequest(_))));
+
+            link.set(port)
+                .master_by_name("br0")
+                .execute()
+                .await
+                .unwrap();
+            let message = link
+                .get()
+                .match_index(port)
+                .execute()
+                .try_next()
+                .await
+                .unwrap()
+                .unwrap();
+            assert!(message.nlas.contains(&Nla::Master(bridge)));
+        });
+    }
+}
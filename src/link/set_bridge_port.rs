@@ -0,0 +1,486 @@
+// SPDX-License-Identifier: MIT
+
+use futures::stream::StreamExt;
+use netlink_packet_core::{NetlinkMessage, NLM_F_ACK, NLM_F_REQUEST};
+use netlink_packet_route::{
+    link::nlas::{AfSpecBridge, Nla},
+    LinkMessage, RtnlMessage, AF_BRIDGE, IFLA_PROTINFO,
+};
+use netlink_packet_utils::{
+    byteorder::{ByteOrder, NativeEndian},
+    nla::{self, DefaultNla, NLA_F_NESTED},
+    Emitable,
+};
+
+use crate::{try_nl, Error, Handle};
+
+const IFLA_BRPORT_STATE: u16 = 1;
+const IFLA_BRPORT_PRIORITY: u16 = 2;
+const IFLA_BRPORT_COST: u16 = 3;
+const IFLA_BRPORT_MODE: u16 = 4;
+const IFLA_BRPORT_GUARD: u16 = 5;
+const IFLA_BRPORT_PROTECT: u16 = 6;
+const IFLA_BRPORT_FAST_LEAVE: u16 = 7;
+const IFLA_BRPORT_LEARNING: u16 = 8;
+const IFLA_BRPORT_UNICAST_FLOOD: u16 = 9;
+const IFLA_BRPORT_PROXYARP: u16 = 10;
+const IFLA_BRPORT_PROXYARP_WIFI: u16 = 12;
+const IFLA_BRPORT_MULTICAST_ROUTER: u16 = 25;
+const IFLA_BRPORT_MCAST_FLOOD: u16 = 27;
+const IFLA_BRPORT_VLAN_TUNNEL: u16 = 29;
+const IFLA_BRPORT_BCAST_FLOOD: u16 = 30;
+const IFLA_BRPORT_NEIGH_SUPPRESS: u16 = 32;
+const IFLA_BRPORT_ISOLATED: u16 = 33;
+const IFLA_BRPORT_BACKUP_PORT: u16 = 34;
+
+const IFLA_BRIDGE_VLAN_TUNNEL_INFO: u16 = 3;
+const IFLA_BRIDGE_VLAN_TUNNEL_ID: u16 = 1;
+const IFLA_BRIDGE_VLAN_TUNNEL_VID: u16 = 2;
+
+/// STP state of a bridge port, as set by `bridge link set dev DEV state
+/// STATE`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum BridgePortState {
+    Disabled,
+    Listening,
+    Learning,
+    Forwarding,
+    Blocking,
+}
+
+impl From<BridgePortState> for u8 {
+    fn from(state: BridgePortState) -> u8 {
+        match state {
+            BridgePortState::Disabled => 0,
+            BridgePortState::Listening => 1,
+            BridgePortState::Learning => 2,
+            BridgePortState::Forwarding => 3,
+            BridgePortState::Blocking => 4,
+        }
+    }
+}
+
+/// Multicast router mode of a bridge port, as set by `bridge link set dev
+/// DEV mcast_router MODE`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum BridgePortMulticastRouter {
+    /// Never consider the port as a multicast router port.
+    Disabled,
+    /// Learn the multicast router ports from received queries (default).
+    TempQuery,
+    /// Always consider the port as a multicast router port.
+    Permanent,
+    /// Consider the port as a multicast router port, until the router
+    /// timeout expires.
+    Temp,
+}
+
+impl From<BridgePortMulticastRouter> for u8 {
+    fn from(mode: BridgePortMulticastRouter) -> u8 {
+        match mode {
+            BridgePortMulticastRouter::Disabled => 0,
+            BridgePortMulticastRouter::TempQuery => 1,
+            BridgePortMulticastRouter::Permanent => 2,
+            BridgePortMulticastRouter::Temp => 3,
+        }
+    }
+}
+
+/// Bridge port attributes (`IFLA_BRPORT_*`). They are sent to the kernel
+/// nested in a `IFLA_PROTINFO` attribute, the same way `bridge link set`
+/// does.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum InfoBridgePort {
+    State(u8),
+    Priority(u16),
+    Cost(u32),
+    HairpinMode(bool),
+    Guard(bool),
+    Protect(bool),
+    FastLeave(bool),
+    Learning(bool),
+    UnicastFlood(bool),
+    ProxyArp(bool),
+    ProxyArpWifi(bool),
+    MulticastFlood(bool),
+    VlanTunnel(bool),
+    BroadcastFlood(bool),
+    NeighSuppress(bool),
+    Isolated(bool),
+    MulticastRouter(u8),
+    BackupPort(u32),
+    Other(DefaultNla),
+}
+
+impl nla::Nla for InfoBridgePort {
+    fn value_len(&self) -> usize {
+        use self::InfoBridgePort::*;
+        match self {
+            State(_) | MulticastRouter(_) => 1,
+            Priority(_) => 2,
+            Cost(_) | BackupPort(_) => 4,
+            HairpinMode(_) | Guard(_) | Protect(_) | FastLeave(_)
+            | Learning(_) | UnicastFlood(_) | ProxyArp(_) | ProxyArpWifi(_)
+            | MulticastFlood(_) | VlanTunnel(_) | BroadcastFlood(_)
+            | NeighSuppress(_) | Isolated(_) => 1,
+            Other(nla) => nla.value_len(),
+        }
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        use self::InfoBridgePort::*;
+        match self {
+            State(value) | MulticastRouter(value) => buffer[0] = *value,
+            Priority(value) => NativeEndian::write_u16(buffer, *value),
+            Cost(value) | BackupPort(value) => {
+                NativeEndian::write_u32(buffer, *value)
+            }
+            HairpinMode(value)
+            | Guard(value)
+            | Protect(value)
+            | FastLeave(value)
+            | Learning(value)
+            | UnicastFlood(value)
+            | ProxyArp(value)
+            | ProxyArpWifi(value)
+            | MulticastFlood(value)
+            | VlanTunnel(value)
+            | BroadcastFlood(value)
+            | NeighSuppress(value)
+            | Isolated(value) => buffer[0] = *value as u8,
+            Other(nla) => nla.emit_value(buffer),
+        }
+    }
+
+    fn kind(&self) -> u16 {
+        use self::InfoBridgePort::*;
+        match self {
+            State(_) => IFLA_BRPORT_STATE,
+            Priority(_) => IFLA_BRPORT_PRIORITY,
+            Cost(_) => IFLA_BRPORT_COST,
+            HairpinMode(_) => IFLA_BRPORT_MODE,
+            Guard(_) => IFLA_BRPORT_GUARD,
+            Protect(_) => IFLA_BRPORT_PROTECT,
+            FastLeave(_) => IFLA_BRPORT_FAST_LEAVE,
+            Learning(_) => IFLA_BRPORT_LEARNING,
+            UnicastFlood(_) => IFLA_BRPORT_UNICAST_FLOOD,
+            ProxyArp(_) => IFLA_BRPORT_PROXYARP,
+            ProxyArpWifi(_) => IFLA_BRPORT_PROXYARP_WIFI,
+            MulticastFlood(_) => IFLA_BRPORT_MCAST_FLOOD,
+            VlanTunnel(_) => IFLA_BRPORT_VLAN_TUNNEL,
+            BroadcastFlood(_) => IFLA_BRPORT_BCAST_FLOOD,
+            NeighSuppress(_) => IFLA_BRPORT_NEIGH_SUPPRESS,
+            Isolated(_) => IFLA_BRPORT_ISOLATED,
+            MulticastRouter(_) => IFLA_BRPORT_MULTICAST_ROUTER,
+            BackupPort(_) => IFLA_BRPORT_BACKUP_PORT,
+            Other(nla) => nla.kind(),
+        }
+    }
+}
+
+/// A request to change the settings of a bridge port. This is equivalent to
+/// the `bridge link set dev DEV` commands.
+pub struct BridgePortSetRequest {
+    handle: Handle,
+    index: u32,
+    port_nlas: Vec<InfoBridgePort>,
+    af_spec: Vec<AfSpecBridge>,
+}
+
+impl BridgePortSetRequest {
+    pub(crate) fn new(handle: Handle, index: u32) -> Self {
+        BridgePortSetRequest {
+            handle,
+            index,
+            port_nlas: Vec::new(),
+            af_spec: Vec::new(),
+        }
+    }
+
+    /// Execute the request
+    pub async fn execute(self) -> Result<(), Error> {
+        let BridgePortSetRequest {
+            mut handle,
+            index,
+            port_nlas,
+            af_spec,
+        } = self;
+
+        let mut req = NetlinkMessage::from(RtnlMessage::SetLink(
+            build_message(index, &port_nlas, af_spec),
+        ));
+        req.header.flags = NLM_F_REQUEST | NLM_F_ACK;
+
+        let mut response = handle.try_request(req)?;
+        while let Some(message) = response.next().await {
+            try_nl!(message?);
+        }
+        Ok(())
+    }
+
+    /// Return a mutable reference to the bridge port attributes
+    pub fn port_nlas_mut(&mut self) -> &mut Vec<InfoBridgePort> {
+        &mut self.port_nlas
+    }
+
+    /// Enable or disable hairpin mode, which allows traffic to be sent back
+    /// out of the port it was received on. This is equivalent to `bridge
+    /// link set dev DEV hairpin on/off`.
+    pub fn hairpin(mut self, enable: bool) -> Self {
+        self.port_nlas.push(InfoBridgePort::HairpinMode(enable));
+        self
+    }
+
+    /// Enable or disable BPDU guard: when enabled, the port is disabled if a
+    /// STP BPDU is received on it. This is equivalent to `bridge link set dev
+    /// DEV guard on/off`.
+    pub fn guard(mut self, enable: bool) -> Self {
+        self.port_nlas.push(InfoBridgePort::Guard(enable));
+        self
+    }
+
+    /// Enable or disable root block: when enabled, the port is not allowed to
+    /// become the STP root port. This is equivalent to `bridge link set dev
+    /// DEV root_block on/off`.
+    pub fn root_block(mut self, enable: bool) -> Self {
+        self.port_nlas.push(InfoBridgePort::Protect(enable));
+        self
+    }
+
+    /// Set the STP path cost of the port. This is equivalent to `bridge link
+    /// set dev DEV cost COST`.
+    pub fn cost(mut self, cost: u32) -> Self {
+        self.port_nlas.push(InfoBridgePort::Cost(cost));
+        self
+    }
+
+    /// Set the STP priority of the port. This is equivalent to `bridge link
+    /// set dev DEV priority PRIO`.
+    pub fn priority(mut self, priority: u16) -> Self {
+        self.port_nlas.push(InfoBridgePort::Priority(priority));
+        self
+    }
+
+    /// Set the STP state of the port. This is meant for user space STP
+    /// implementations (such as `mstpd`), the kernel rejects it when kernel
+    /// STP is running on the bridge. This is equivalent to `bridge link set
+    /// dev DEV state STATE`.
+    pub fn state(mut self, state: BridgePortState) -> Self {
+        self.port_nlas.push(InfoBridgePort::State(state.into()));
+        self
+    }
+
+    /// Enable or disable learning of source MAC addresses on the port. This
+    /// is equivalent to `bridge link set dev DEV learning on/off`.
+    pub fn learning(mut self, enable: bool) -> Self {
+        self.port_nlas.push(InfoBridgePort::Learning(enable));
+        self
+    }
+
+    /// Enable or disable flooding of unknown unicast traffic to the port.
+    /// This is equivalent to `bridge link set dev DEV flood on/off`.
+    pub fn unicast_flood(mut self, enable: bool) -> Self {
+        self.port_nlas.push(InfoBridgePort::UnicastFlood(enable));
+        self
+    }
+
+    /// Enable or disable flooding of unknown multicast traffic to the port.
+    /// This is equivalent to `bridge link set dev DEV mcast_flood on/off`.
+    pub fn multicast_flood(mut self, enable: bool) -> Self {
+        self.port_nlas.push(InfoBridgePort::MulticastFlood(enable));
+        self
+    }
+
+    /// Enable or disable flooding of broadcast traffic to the port. This is
+    /// equivalent to `bridge link set dev DEV bcast_flood on/off`.
+    pub fn broadcast_flood(mut self, enable: bool) -> Self {
+        self.port_nlas.push(InfoBridgePort::BroadcastFlood(enable));
+        self
+    }
+
+    /// Enable or disable VLAN to tunnel mapping on the port. This is
+    /// typically enabled on the VXLAN port of a bridge, along with
+    /// [`vlan_tunnel_info()`](#method.vlan_tunnel_info). This is equivalent
+    /// to `bridge link set dev DEV vlan_tunnel on/off`.
+    pub fn vlan_tunnel(mut self, enable: bool) -> Self {
+        self.port_nlas.push(InfoBridgePort::VlanTunnel(enable));
+        self
+    }
+
+    /// Map the VLAN `vid` to the tunnel id `tunnel_id` (the VNI for VXLAN).
+    /// This is equivalent to `bridge vlan add dev DEV vid VID tunnel_info id
+    /// TUNNEL_ID`. The VLAN must already be configured on the port.
+    pub fn vlan_tunnel_info(mut self, vid: u16, tunnel_id: u32) -> Self {
+        self.af_spec.push(vlan_tunnel_info(vid, tunnel_id));
+        self
+    }
+
+    /// Enable or disable ARP and ND suppression on the port: the bridge
+    /// answers the requests itself from its neighbour entries instead of
+    /// flooding them, as done on the VXLAN port in EVPN setups. This is
+    /// equivalent to `bridge link set dev DEV neigh_suppress on/off`.
+    pub fn neigh_suppress(mut self, enable: bool) -> Self {
+        self.port_nlas.push(InfoBridgePort::NeighSuppress(enable));
+        self
+    }
+
+    /// Set the multicast router mode of the port, which decides whether
+    /// multicast traffic is always forwarded to it. This is equivalent to
+    /// `bridge link set dev DEV mcast_router MODE`.
+    pub fn multicast_router(mut self, mode: BridgePortMulticastRouter) -> Self {
+        self.port_nlas
+            .push(InfoBridgePort::MulticastRouter(mode.into()));
+        self
+    }
+
+    /// Enable or disable multicast fast leave: when enabled, the port is
+    /// removed from a multicast group as soon as an IGMP/MLD leave message
+    /// is received, without querying for other listeners. This is
+    /// equivalent to `bridge link set dev DEV fastleave on/off`.
+    pub fn fast_leave(mut self, enable: bool) -> Self {
+        self.port_nlas.push(InfoBridgePort::FastLeave(enable));
+        self
+    }
+
+    /// Isolate the port: isolated ports can only communicate with
+    /// non-isolated ports. This is equivalent to `bridge link set dev DEV
+    /// isolated on/off`.
+    pub fn isolated(mut self, enable: bool) -> Self {
+        self.port_nlas.push(InfoBridgePort::Isolated(enable));
+        self
+    }
+
+    /// Enable or disable proxy ARP on the port: the bridge answers the ARP
+    /// requests received on the port for the neighbours it knows, instead of
+    /// forwarding them. This is equivalent to `bridge link set dev DEV
+    /// proxy_arp on/off`.
+    pub fn proxy_arp(mut self, enable: bool) -> Self {
+        self.port_nlas.push(InfoBridgePort::ProxyArp(enable));
+        self
+    }
+
+    /// Enable or disable proxy ARP for wireless clients (IEEE 802.11 Proxy
+    /// ARP, used by hotspots): the bridge answers the ARP requests sent to
+    /// the port on behalf of its clients. This is equivalent to `bridge link
+    /// set dev DEV proxy_arp_wifi on/off`.
+    pub fn proxy_arp_wifi(mut self, enable: bool) -> Self {
+        self.port_nlas.push(InfoBridgePort::ProxyArpWifi(enable));
+        self
+    }
+
+    /// Send the traffic of the port to the port with the given index of the
+    /// same bridge when its carrier goes down, for instance to the peer link
+    /// of an MLAG pair, or the VXLAN port in EVPN multihoming. `0` removes
+    /// the backup port. This is equivalent to `bridge link set dev DEV
+    /// backup_port BACKUP_DEV`.
+    pub fn backup_port(mut self, index: u32) -> Self {
+        self.port_nlas.push(InfoBridgePort::BackupPort(index));
+        self
+    }
+}
+
+pub(crate) fn vlan_tunnel_info(vid: u16, tunnel_id: u32) -> AfSpecBridge {
+    let mut id = vec![0; 4];
+    NativeEndian::write_u32(&mut id, tunnel_id);
+    let mut vlan = vec![0; 2];
+    NativeEndian::write_u16(&mut vlan, vid);
+    let nlas = [
+        DefaultNla::new(IFLA_BRIDGE_VLAN_TUNNEL_ID, id),
+        DefaultNla::new(IFLA_BRIDGE_VLAN_TUNNEL_VID, vlan),
+    ];
+
+    let mut value = vec![0; nlas.as_slice().buffer_len()];
+    nlas.as_slice().emit(&mut value);
+    AfSpecBridge::Other(DefaultNla::new(IFLA_BRIDGE_VLAN_TUNNEL_INFO, value))
+}
+
+fn build_message(
+    index: u32,
+    port_nlas: &[InfoBridgePort],
+    af_spec: Vec<AfSpecBridge>,
+) -> LinkMessage {
+    let mut message = LinkMessage::default();
+    message.header.interface_family = AF_BRIDGE as u8;
+    message.header.index = index;
+
+    if !port_nlas.is_empty() {
+        let mut protinfo = vec![0; port_nlas.buffer_len()];
+        port_nlas.emit(&mut protinfo);
+        message.nlas.push(Nla::Other(DefaultNla::new(
+            IFLA_PROTINFO | NLA_F_NESTED,
+            protinfo,
+        )));
+    }
+    if !af_spec.is_empty() {
+        message.nlas.push(Nla::AfSpecBridge(af_spec));
+    }
+    message
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_bridge_port_protinfo() {
+        let message = build_message(
+            3,
+            &[
+                InfoBridgePort::Learning(false),
+                InfoBridgePort::Isolated(true),
+            ],
+            vec![],
+        );
+        assert_eq!(message.header.interface_family, AF_BRIDGE as u8);
+        assert_eq!(message.header.index, 3);
+        assert_eq!(
+            message.nlas,
+            vec![Nla::Other(DefaultNla::new(
+                IFLA_PROTINFO | NLA_F_NESTED,
+                [
+                    // IFLA_BRPORT_LEARNING
+                    [5, 0, 8, 0, 0, 0, 0, 0],
+                    // IFLA_BRPORT_ISOLATED
+                    [5, 0, 33, 0, 1, 0, 0, 0],
+                ]
+                .concat(),
+            ))]
+        );
+    }
+
+    #[test]
+    fn test_bridge_port_backup_port() {
+        let message =
+            build_message(3, &[InfoBridgePort::BackupPort(7)], vec![]);
+        assert_eq!(
+            message.nlas,
+            vec![Nla::Other(DefaultNla::new(
+                IFLA_PROTINFO | NLA_F_NESTED,
+                // IFLA_BRPORT_BACKUP_PORT
+                vec![8, 0, 34, 0, 7, 0, 0, 0],
+            ))]
+        );
+    }
+
+    #[test]
+    fn test_bridge_port_vlan_tunnel_info() {
+        let message = build_message(
+            3,
+            &[InfoBridgePort::VlanTunnel(true)],
+            vec![vlan_tunnel_info(10, 1010)],
+        );
+        assert_eq!(message.nlas.len(), 2);
+        assert_eq!(
+            message.nlas[1],
+            Nla::AfSpecBridge(vec![AfSpecBridge::Other(DefaultNla::new(
+                IFLA_BRIDGE_VLAN_TUNNEL_INFO,
+                vec![
+                    // IFLA_BRIDGE_VLAN_TUNNEL_ID
+                    8, 0, 1, 0, 0xf2, 0x03, 0, 0,
+                    // IFLA_BRIDGE_VLAN_TUNNEL_VID
+                    6, 0, 2, 0, 10, 0, 0, 0,
+                ],
+            ))])
+        );
+    }
+}
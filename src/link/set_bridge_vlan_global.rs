@@ -0,0 +1,231 @@
+// SPDX-License-Identifier: MIT
+
+use netlink_packet_core::{NLMSG_ERROR, NLM_F_ACK, NLM_F_REQUEST};
+use netlink_packet_route::AF_BRIDGE;
+use netlink_packet_utils::{
+    byteorder::{ByteOrder, NativeEndian},
+    nla::{DefaultNla, Nla, NLA_F_NESTED},
+};
+use netlink_sys::AsyncSocket;
+
+use super::nla::encode;
+use crate::{
+    raw::{self, SEQUENCE_NUMBER},
+    Error, Handle,
+};
+
+// Not defined by netlink-packet-route
+const RTM_NEWVLAN: u16 = 112;
+const BRIDGE_VLANDB_GLOBAL_OPTIONS: u16 = 3;
+const BRIDGE_VLANDB_GOPTS_ID: u16 = 1;
+const BRIDGE_VLANDB_GOPTS_RANGE: u16 = 2;
+const BRIDGE_VLANDB_GOPTS_MCAST_SNOOPING: u16 = 3;
+const BRIDGE_VLANDB_GOPTS_MSTI: u16 = 18;
+
+// The attributes nested in BRIDGE_VLANDB_GLOBAL_OPTIONS
+enum GlobalOption {
+    Id(u16),
+    Range(u16),
+    McastSnooping(u8),
+    Msti(u16),
+}
+
+impl Nla for GlobalOption {
+    fn value_len(&self) -> usize {
+        match self {
+            GlobalOption::McastSnooping(_) => 1,
+            _ => 2,
+        }
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        match self {
+            GlobalOption::Id(value)
+            | GlobalOption::Range(value)
+            | GlobalOption::Msti(value) => {
+                NativeEndian::write_u16(buffer, *value)
+            }
+            GlobalOption::McastSnooping(value) => buffer[0] = *value,
+        }
+    }
+
+    fn kind(&self) -> u16 {
+        match self {
+            GlobalOption::Id(_) => BRIDGE_VLANDB_GOPTS_ID,
+            GlobalOption::Range(_) => BRIDGE_VLANDB_GOPTS_RANGE,
+            GlobalOption::McastSnooping(_) => {
+                BRIDGE_VLANDB_GOPTS_MCAST_SNOOPING
+            }
+            GlobalOption::Msti(_) => BRIDGE_VLANDB_GOPTS_MSTI,
+        }
+    }
+}
+
+/// A request to change the global options of VLANs of a bridge, which apply
+/// to the bridge as a whole rather than to one of its ports. This is
+/// equivalent to `bridge vlan global set dev DEV vid VID ...` commands.
+///
+/// netlink-packet-route can't represent the `RTM_NEWVLAN` messages, so the
+/// request is sent on a dedicated netlink socket, in the network namespace
+/// of the connection of the handle, and is subject to its timeout and
+/// in-flight limit. It fails with [`Error::InvalidRequest`] on a
+/// [`Handle::dry_run`], [`Handle::mock`] or replay handle.
+pub struct BridgeVlanGlobalSetRequest {
+    handle: Handle,
+    index: u32,
+    vid: u16,
+    vid_end: Option<u16>,
+    options: Vec<GlobalOption>,
+}
+
+impl BridgeVlanGlobalSetRequest {
+    pub(crate) fn new(handle: Handle, index: u32, vid: u16) -> Self {
+        BridgeVlanGlobalSetRequest {
+            handle,
+            index,
+            vid,
+            vid_end: None,
+            options: Vec::new(),
+        }
+    }
+
+    /// Execute the request. An [`Error::InvalidRequest`] is returned if the
+    /// VLAN ids are not between 1 and 4094, or if the range is empty.
+    #[cfg(feature = "tokio_socket")]
+    pub async fn execute(self) -> Result<(), Error> {
+        self.execute_with_socket::<netlink_sys::TokioSocket>().await
+    }
+
+    /// Same as [`BridgeVlanGlobalSetRequest::execute`], using the socket
+    /// type `S` (for instance `SmolSocket`).
+    pub async fn execute_with_socket<S>(self) -> Result<(), Error>
+    where
+        S: AsyncSocket,
+    {
+        let request = self.to_bytes()?;
+        raw::request::<S, _, _>(&self.handle, &request, |message| match message
+            .message_type()
+        {
+            NLMSG_ERROR => Ok(Some(())),
+            _ => Ok(None),
+        })
+        .await
+    }
+
+    /// Apply the options to the VLANs `vid` to `vid_end` (equivalent to
+    /// `bridge vlan global set dev DEV vid VID-VID_END ...`)
+    pub fn range(mut self, vid_end: u16) -> Self {
+        self.vid_end = Some(vid_end);
+        self
+    }
+
+    /// Enable or disable multicast snooping on the VLANs (equivalent to
+    /// `bridge vlan global set dev DEV vid VID mcast_snooping { 0 | 1 }`).
+    /// It only has an effect if the `mcast_vlan_snooping` option of the
+    /// bridge is enabled, see [`crate::BridgeBoolOpt::McastVlanSnooping`].
+    pub fn mcast_snooping(mut self, enable: bool) -> Self {
+        self.options.push(GlobalOption::McastSnooping(enable as u8));
+        self
+    }
+
+    /// Map the VLANs to the multiple spanning tree instance `msti`
+    /// (equivalent to `bridge vlan global set dev DEV vid VID msti MSTI`).
+    /// The `mst_enabled` option of the bridge must be enabled, see
+    /// [`crate::BridgeBoolOpt::MstEnabled`]. This requires Linux 5.18 or
+    /// later.
+    pub fn msti(mut self, msti: u16) -> Self {
+        self.options.push(GlobalOption::Msti(msti));
+        self
+    }
+
+    fn to_bytes(&self) -> Result<Vec<u8>, Error> {
+        let vid_end = self.vid_end.unwrap_or(self.vid);
+        if !(1..=4094).contains(&self.vid) || !(1..=4094).contains(&vid_end) {
+            return Err(Error::InvalidRequest(format!(
+                "invalid VLAN range {}-{}",
+                self.vid, vid_end
+            )));
+        }
+        if vid_end < self.vid {
+            return Err(Error::InvalidRequest(format!(
+                "empty VLAN range {}-{}",
+                self.vid, vid_end
+            )));
+        }
+        let mut options = vec![GlobalOption::Id(self.vid)];
+        if let Some(vid_end) = self.vid_end {
+            options.push(GlobalOption::Range(vid_end));
+        }
+        let options = encode(&options)
+            .into_iter()
+            .chain(encode(&self.options))
+            .collect();
+        let global = DefaultNla::new(
+            BRIDGE_VLANDB_GLOBAL_OPTIONS | NLA_F_NESTED,
+            options,
+        );
+        let global = encode(&[global]);
+
+        // nlmsghdr, br_vlan_msg and BRIDGE_VLANDB_GLOBAL_OPTIONS
+        let mut buf = vec![0; 16 + 8];
+        buf.extend(global);
+        let len = buf.len() as u32;
+        NativeEndian::write_u32(&mut buf[0..4], len);
+        NativeEndian::write_u16(&mut buf[4..6], RTM_NEWVLAN);
+        NativeEndian::write_u16(&mut buf[6..8], NLM_F_REQUEST | NLM_F_ACK);
+        NativeEndian::write_u32(&mut buf[8..12], SEQUENCE_NUMBER);
+        buf[16] = AF_BRIDGE as u8;
+        NativeEndian::write_u32(&mut buf[20..24], self.index);
+        Ok(buf)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::MockNetwork;
+
+    #[test]
+    fn test_encode_global_options() {
+        let handle = MockNetwork::new().handle();
+        let request = BridgeVlanGlobalSetRequest::new(handle.clone(), 42, 10)
+            .range(20)
+            .mcast_snooping(false)
+            .msti(3);
+        let mut expected = vec![0; 16];
+        expected.extend_from_slice(&[
+            7, 0, 0, 0, 42, 0, 0, 0, // br_vlan_msg
+            36, 0, 3, 0x80, // BRIDGE_VLANDB_GLOBAL_OPTIONS
+            6, 0, 1, 0, 10, 0, 0, 0, // BRIDGE_VLANDB_GOPTS_ID
+            6, 0, 2, 0, 20, 0, 0, 0, // BRIDGE_VLANDB_GOPTS_RANGE
+            5, 0, 3, 0, 0, 0, 0, 0, // BRIDGE_VLANDB_GOPTS_MCAST_SNOOPING
+            6, 0, 18, 0, 3, 0, 0, 0, // BRIDGE_VLANDB_GOPTS_MSTI
+        ]);
+        NativeEndian::write_u32(&mut expected[0..4], 60);
+        NativeEndian::write_u16(&mut expected[4..6], RTM_NEWVLAN);
+        NativeEndian::write_u16(&mut expected[6..8], NLM_F_REQUEST | NLM_F_ACK);
+        NativeEndian::write_u32(&mut expected[8..12], SEQUENCE_NUMBER);
+        assert_eq!(request.to_bytes().unwrap(), expected);
+
+        let request = BridgeVlanGlobalSetRequest::new(handle.clone(), 42, 20)
+            .range(10)
+            .mcast_snooping(true);
+        assert!(matches!(request.to_bytes(), Err(Error::InvalidRequest(_))));
+        let request = BridgeVlanGlobalSetRequest::new(handle, 42, 4095);
+        assert!(matches!(request.to_bytes(), Err(Error::InvalidRequest(_))));
+    }
+
+    #[test]
+    fn test_dry_run_is_rejected() {
+        let (handle, dry_run) = MockNetwork::new().handle().dry_run();
+        let res = futures::executor::block_on(
+            handle
+                .link()
+                .set_bridge_vlan_global(42, 10)
+                .mcast_snooping(true)
+                .execute(),
+        );
+        assert!(matches!(res, Err(Error::InvalidRequest(_))));
+        assert!(dry_run.messages().is_empty());
+    }
+}
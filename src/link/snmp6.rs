@@ -0,0 +1,261 @@
+// SPDX-License-Identifier: MIT
+
+use netlink_packet_route::{
+    link::nlas::{AfSpecInet, Inet6, Nla},
+    LinkMessage,
+};
+use netlink_packet_utils::byteorder::{ByteOrder, NativeEndian};
+
+/// IPv6 counters of a link (`/proc/net/dev_snmp6/<interface_name>`), from
+/// the `IFLA_INET6_STATS` attribute. The counters unknown to the running
+/// kernel are 0.
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct Ipv6Stats {
+    pub in_receives: u64,
+    pub in_octets: u64,
+    pub in_delivers: u64,
+    pub out_forw_datagrams: u64,
+    pub out_requests: u64,
+    pub out_octets: u64,
+    pub in_hdr_errors: u64,
+    pub in_too_big_errors: u64,
+    pub in_no_routes: u64,
+    pub in_addr_errors: u64,
+    pub in_unknown_protos: u64,
+    pub in_truncated_pkts: u64,
+    pub in_discards: u64,
+    pub out_discards: u64,
+    pub out_no_routes: u64,
+    pub reasm_timeout: u64,
+    pub reasm_reqds: u64,
+    pub reasm_oks: u64,
+    pub reasm_fails: u64,
+    pub frag_oks: u64,
+    pub frag_fails: u64,
+    pub frag_creates: u64,
+    pub in_mcast_pkts: u64,
+    pub out_mcast_pkts: u64,
+    pub in_bcast_pkts: u64,
+    pub out_bcast_pkts: u64,
+    pub in_mcast_octets: u64,
+    pub out_mcast_octets: u64,
+    pub in_bcast_octets: u64,
+    pub out_bcast_octets: u64,
+    pub in_csum_errors: u64,
+    pub in_no_ect_pkts: u64,
+    pub in_ect1_pkts: u64,
+    pub in_ect0_pkts: u64,
+    pub in_ce_pkts: u64,
+    pub reasm_overlaps: u64,
+    pub out_transmits: u64,
+}
+
+impl Ipv6Stats {
+    /// Extract the IPv6 counters of a link. `None` if IPv6 is disabled on
+    /// the link.
+    pub fn from_link(message: &LinkMessage) -> Option<Self> {
+        inet6_attribute(message, |nla| match nla {
+            Inet6::Stats(buf) => Some(Self::parse(buf)),
+            _ => None,
+        })
+    }
+
+    /// Parse the value of an `IFLA_INET6_STATS` attribute
+    pub(crate) fn parse(buf: &[u8]) -> Self {
+        let reordered = counters(buf)(0) as usize >= IPSTATS_REORDERED.len()
+            && kernel_version() >= Some((6, 9));
+        Self::parse_with_layout(buf, reordered)
+    }
+
+    fn parse_with_layout(buf: &[u8], reordered: bool) -> Self {
+        let counter = counters(buf);
+        let index = |i: usize| if reordered { IPSTATS_REORDERED[i] } else { i };
+        let counter = |i: usize| counter(index(i));
+        Ipv6Stats {
+            in_receives: counter(1),
+            in_octets: counter(2),
+            in_delivers: counter(3),
+            out_forw_datagrams: counter(4),
+            out_requests: counter(5),
+            out_octets: counter(6),
+            in_hdr_errors: counter(7),
+            in_too_big_errors: counter(8),
+            in_no_routes: counter(9),
+            in_addr_errors: counter(10),
+            in_unknown_protos: counter(11),
+            in_truncated_pkts: counter(12),
+            in_discards: counter(13),
+            out_discards: counter(14),
+            out_no_routes: counter(15),
+            reasm_timeout: counter(16),
+            reasm_reqds: counter(17),
+            reasm_oks: counter(18),
+            reasm_fails: counter(19),
+            frag_oks: counter(20),
+            frag_fails: counter(21),
+            frag_creates: counter(22),
+            in_mcast_pkts: counter(23),
+            out_mcast_pkts: counter(24),
+            in_bcast_pkts: counter(25),
+            out_bcast_pkts: counter(26),
+            in_mcast_octets: counter(27),
+            out_mcast_octets: counter(28),
+            in_bcast_octets: counter(29),
+            out_bcast_octets: counter(30),
+            in_csum_errors: counter(31),
+            in_no_ect_pkts: counter(32),
+            in_ect1_pkts: counter(33),
+            in_ect0_pkts: counter(34),
+            in_ce_pkts: counter(35),
+            reasm_overlaps: counter(36),
+            out_transmits: counter(37),
+        }
+    }
+}
+
+// The `IPSTATS_MIB_*` counters were appended to the enum until Linux 6.3
+// (which added `OutTransmits`), so the fields above are in that order. Linux
+// 6.9 moved the most used counters to the front: this maps the index of a
+// counter in the historical order to its index in the new one.
+const IPSTATS_REORDERED: [usize; 38] = [
+    0, 1, 2, 3, 11, 8, 10, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24,
+    25, 26, 27, 28, 29, 30, 31, 32, 33, 34, 35, 36, 4, 5, 6, 7, 37, 9,
+];
+
+fn kernel_version() -> Option<(u32, u32)> {
+    let uname = nix::sys::utsname::uname().ok()?;
+    let release = uname.release().to_str()?;
+    let mut numbers = release
+        .split(|c: char| !c.is_ascii_digit())
+        .map(|n| n.parse().ok());
+    Some((numbers.next()??, numbers.next()??))
+}
+
+/// ICMPv6 counters of a link, from the `IFLA_INET6_ICMP6STATS` attribute.
+/// The counters unknown to the running kernel are 0.
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct Icmpv6Stats {
+    pub in_msgs: u64,
+    pub in_errors: u64,
+    pub out_msgs: u64,
+    pub out_errors: u64,
+    pub in_csum_errors: u64,
+    pub out_rate_limit_host: u64,
+}
+
+impl Icmpv6Stats {
+    /// Extract the ICMPv6 counters of a link. `None` if IPv6 is disabled on
+    /// the link.
+    pub fn from_link(message: &LinkMessage) -> Option<Self> {
+        inet6_attribute(message, |nla| match nla {
+            Inet6::IcmpStats(buf) => Some(Self::parse(buf)),
+            _ => None,
+        })
+    }
+
+    /// Parse the value of an `IFLA_INET6_ICMP6STATS` attribute
+    pub(crate) fn parse(buf: &[u8]) -> Self {
+        let counter = counters(buf);
+        Icmpv6Stats {
+            in_msgs: counter(1),
+            in_errors: counter(2),
+            out_msgs: counter(3),
+            out_errors: counter(4),
+            in_csum_errors: counter(5),
+            out_rate_limit_host: counter(6),
+        }
+    }
+}
+
+fn inet6_attribute<T>(
+    message: &LinkMessage,
+    f: impl Fn(&Inet6) -> Option<T>,
+) -> Option<T> {
+    message
+        .nlas
+        .iter()
+        .filter_map(|nla| match nla {
+            Nla::AfSpecInet(specs) => Some(specs),
+            _ => None,
+        })
+        .flatten()
+        .filter_map(|spec| match spec {
+            AfSpecInet::Inet6(nlas) => Some(nlas),
+            _ => None,
+        })
+        .flatten()
+        .find_map(f)
+}
+
+// Both attributes are arrays of u64 (`IPSTATS_MIB_*` and `ICMP6_MIB_*`),
+// the first item being the number of items, itself included.
+fn counters(buf: &[u8]) -> impl Fn(usize) -> u64 + '_ {
+    let len = if buf.len() >= 8 {
+        (NativeEndian::read_u64(buf) as usize).min(buf.len() / 8)
+    } else {
+        0
+    };
+    move |i| {
+        if i < len {
+            NativeEndian::read_u64(&buf[i * 8..])
+        } else {
+            0
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn encode(counters: &[u64]) -> Vec<u8> {
+        let mut buf = vec![0; counters.len() * 8];
+        for (i, counter) in counters.iter().enumerate() {
+            NativeEndian::write_u64(&mut buf[i * 8..], *counter);
+        }
+        buf
+    }
+
+    #[test]
+    fn test_snmp6_stats() {
+        let mut message = LinkMessage::default();
+        assert_eq!(Ipv6Stats::from_link(&message), None);
+
+        message
+            .nlas
+            .push(Nla::AfSpecInet(vec![AfSpecInet::Inet6(vec![
+                Inet6::Stats(encode(&[4, 10, 1000, 9])),
+                // older kernel, without the rate limit counter
+                Inet6::IcmpStats(encode(&[6, 3, 1, 2, 0, 0, 42])),
+            ])]));
+        assert_eq!(
+            Ipv6Stats::from_link(&message),
+            Some(Ipv6Stats {
+                in_receives: 10,
+                in_octets: 1000,
+                in_delivers: 9,
+                ..Default::default()
+            })
+        );
+        assert_eq!(
+            Icmpv6Stats::from_link(&message),
+            Some(Icmpv6Stats {
+                in_msgs: 3,
+                in_errors: 1,
+                out_msgs: 2,
+                ..Default::default()
+            })
+        );
+        assert_eq!(Ipv6Stats::parse(&[1, 2]), Ipv6Stats::default());
+
+        let mut counters: Vec<u64> = (0..38).collect();
+        counters[0] = 38;
+        let stats = Ipv6Stats::parse_with_layout(&encode(&counters), false);
+        assert_eq!((stats.out_requests, stats.out_transmits), (5, 37));
+        let stats = Ipv6Stats::parse_with_layout(&encode(&counters), true);
+        assert_eq!((stats.out_requests, stats.out_transmits), (8, 9));
+        assert_eq!((stats.in_no_ect_pkts, stats.reasm_overlaps), (4, 37));
+    }
+}
@@ -0,0 +1,114 @@
+// SPDX-License-Identifier: MIT
+
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use futures::{Stream, TryStreamExt};
+
+use crate::{handle::sleep, Error, Handle, Link, LinkStats};
+
+/// The counters of a link at some point, see
+/// [`crate::LinkHandle::stats_stream`]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct LinkStatsSample {
+    pub index: u32,
+    pub name: String,
+    pub stats: LinkStats,
+    /// The increase of the counters since the previous sample of the link,
+    /// `None` in its first sample
+    pub delta: Option<LinkStats>,
+}
+
+struct Sampler {
+    handle: Handle,
+    interval: Duration,
+    indices: Vec<u32>,
+    previous: HashMap<u32, LinkStats>,
+    next_tick: Instant,
+}
+
+impl Sampler {
+    async fn sample(&mut self) -> Result<Vec<LinkStatsSample>, Error> {
+        let now = Instant::now();
+        if self.next_tick > now {
+            sleep(self.next_tick - now).await;
+        }
+        // Don't try to catch up when a dump took longer than the interval
+        self.next_tick = (self.next_tick + self.interval).max(Instant::now());
+
+        let links: Vec<_> =
+            self.handle.link().get().execute().try_collect().await?;
+        let mut previous = HashMap::new();
+        let mut samples = Vec::new();
+        for link in links.into_iter().map(Link::from) {
+            if !self.indices.is_empty() && !self.indices.contains(&link.index) {
+                continue;
+            }
+            let stats = match link.stats {
+                Some(stats) => stats,
+                None => continue,
+            };
+            samples.push(LinkStatsSample {
+                index: link.index,
+                name: link.name,
+                stats,
+                delta: self.previous.get(&link.index).map(|p| stats.delta(p)),
+            });
+            previous.insert(link.index, stats);
+        }
+        // Only keep the links which still exist, so that a recreated link
+        // with the same index starts over
+        self.previous = previous;
+        Ok(samples)
+    }
+}
+
+pub(crate) fn stats_stream(
+    handle: Handle,
+    interval: Duration,
+    indices: &[u32],
+) -> impl Stream<Item = Result<Vec<LinkStatsSample>, Error>> {
+    let sampler = Sampler {
+        handle,
+        interval,
+        indices: indices.to_vec(),
+        previous: HashMap::new(),
+        next_tick: Instant::now(),
+    };
+    futures::stream::unfold(sampler, |mut sampler| async move {
+        let samples = sampler.sample().await;
+        Some((samples, sampler))
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_link_stats_delta() {
+        let previous = LinkStats {
+            rx_packets: 10,
+            rx_bytes: 1000,
+            tx_packets: 5,
+            ..Default::default()
+        };
+        let stats = LinkStats {
+            rx_packets: 15,
+            rx_bytes: 1600,
+            tx_packets: 2,
+            ..Default::default()
+        };
+        assert_eq!(
+            stats.delta(&previous),
+            LinkStats {
+                rx_packets: 5,
+                rx_bytes: 600,
+                ..Default::default()
+            }
+        );
+    }
+}
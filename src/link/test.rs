@@ -1,16 +1,64 @@
 // SPDX-License-Identifier: MIT
 
-use futures::stream::TryStreamExt;
+use std::time::Duration;
+
+use futures::{stream::TryStreamExt, Future};
+use netlink_packet_core::NetlinkPayload;
 use netlink_packet_route::link::{
-    nlas::{Info, InfoKind, Nla},
+    nlas::{Info, InfoData, InfoKind, Nla, Prop},
     LinkMessage,
 };
+use netlink_packet_route::{RtnlMessage, IFF_UP};
 use tokio::runtime::Runtime;
 
-use crate::{new_connection, Error, LinkHandle};
+use crate::{
+    new_connection, BridgeBoolOpt, Error, Handle, Link, LinkHandle, MockNetwork,
+};
 
 const IFACE_NAME: &str = "wg142"; // rand?
 
+/// Run the request built by `request` on a handle in dry-run mode, which
+/// needs neither a runtime nor a socket, and return the link message it
+/// sends. Requests rejected before being sent return their error.
+pub(crate) fn dry_run_link<F, R>(request: F) -> Result<LinkMessage, Error>
+where
+    F: FnOnce(Handle) -> R,
+    R: Future<Output = Result<(), Error>>,
+{
+    let (handle, dry_run) = MockNetwork::new().handle().dry_run();
+    let result = futures::executor::block_on(request(handle));
+    let messages = dry_run.messages();
+    if let Err(err) = result {
+        assert!(messages.is_empty());
+        return Err(err);
+    }
+    assert_eq!(messages.len(), 1);
+    match &messages[0].payload {
+        NetlinkPayload::InnerMessage(
+            RtnlMessage::NewLink(message)
+            | RtnlMessage::SetLink(message)
+            | RtnlMessage::NewLinkProp(message)
+            | RtnlMessage::DelLinkProp(message),
+        ) => Ok(message.clone()),
+        payload => panic!("unexpected payload {:?}", payload),
+    }
+}
+
+/// The `IFLA_INFO_DATA` attribute of `message`
+pub(crate) fn info_data(message: &LinkMessage) -> &InfoData {
+    message
+        .nlas
+        .iter()
+        .find_map(|nla| match nla {
+            Nla::Info(infos) => infos.iter().find_map(|info| match info {
+                Info::Data(data) => Some(data),
+                _ => None,
+            }),
+            _ => None,
+        })
+        .expect("no IFLA_INFO_DATA attribute")
+}
+
 #[test]
 fn create_get_delete_wg() {
     let rt = Runtime::new().unwrap();
@@ -27,6 +75,155 @@ fn create_get_delete_wg() {
     rt.block_on(_del_wg(&mut handle, msg.header.index)).unwrap();
 }
 
+#[test]
+fn get_after_dropped_dump() {
+    let rt = Runtime::new().unwrap();
+    rt.block_on(async {
+        let (conn, handle, _) = new_connection().unwrap();
+        tokio::spawn(conn);
+        let mut links = handle.link().get().execute();
+        assert!(links.try_next().await.unwrap().is_some());
+        drop(links);
+
+        // The rest of the dump must not be mistaken for the reply of this
+        // request
+        let mut links = handle.link().get().match_index(1).execute();
+        let lo = links.try_next().await.unwrap().unwrap();
+        assert_eq!(lo.header.index, 1);
+        assert!(links.try_next().await.unwrap().is_none());
+    });
+}
+
+#[test]
+fn get_loopback_view() {
+    let rt = Runtime::new().unwrap();
+    rt.block_on(async {
+        let (conn, handle, _) = new_connection().unwrap();
+        tokio::spawn(conn);
+        let mut links = handle.link().get().match_index(1).execute();
+        let lo = Link::from(links.try_next().await.unwrap().unwrap());
+        assert_eq!(lo.index, 1);
+        assert_eq!(lo.name, "lo");
+        assert!(lo.mtu.is_some());
+        assert!(lo.kind.is_none());
+        assert!(lo.stats.is_some());
+    });
+}
+
+#[test]
+#[cfg_attr(not(feature = "test_as_root"), ignore)]
+fn rename_up_link() {
+    let rt = Runtime::new().unwrap();
+    rt.block_on(async {
+        let (conn, handle, _) = new_connection().unwrap();
+        tokio::spawn(conn);
+        let link = handle.link();
+        link.add().bridge("rename0".into()).execute().await.unwrap();
+        let msg = link
+            .get()
+            .match_name("rename0".into())
+            .execute()
+            .try_next()
+            .await
+            .unwrap()
+            .unwrap();
+        let index = msg.header.index;
+        link.set(index).up().execute().await.unwrap();
+        link.property_add(index)
+            .alt_ifname(&["rename1", "rename-alt"])
+            .execute()
+            .await
+            .unwrap();
+
+        link.rename(index, "rename1".into())
+            .down_if_busy(true)
+            .execute()
+            .await
+            .unwrap();
+
+        let msg = link
+            .get()
+            .match_index(index)
+            .execute()
+            .try_next()
+            .await
+            .unwrap()
+            .unwrap();
+        link.del(index).execute().await.unwrap();
+        let view = Link::from(&msg);
+        assert_eq!(view.name, "rename1");
+        assert_ne!(view.flags & IFF_UP, 0);
+        assert!(msg.nlas.iter().any(|nla| matches!(
+            nla,
+            Nla::PropList(props) if props.contains(&Prop::AltIfName("rename-alt".into()))
+        )));
+    });
+}
+
+#[test]
+#[cfg_attr(not(feature = "test_as_root"), ignore)]
+fn wait_removed_link() {
+    let rt = Runtime::new().unwrap();
+    rt.block_on(async {
+        let (conn, handle, _) = new_connection().unwrap();
+        tokio::spawn(conn);
+        let link = handle.link();
+        link.add().bridge("waitrm0".into()).execute().await.unwrap();
+        let index = link.resolve("waitrm0").await.unwrap().unwrap();
+        let timeout = Duration::from_millis(100);
+        assert!(matches!(
+            link.wait_removed(index, timeout).await,
+            Err(Error::Timeout)
+        ));
+
+        link.del(index).execute().await.unwrap();
+        link.wait_removed(index, Duration::from_secs(5))
+            .await
+            .unwrap();
+        link.add().bridge("waitrm0".into()).execute().await.unwrap();
+        let index = link.resolve("waitrm0").await.unwrap().unwrap();
+        link.del(index).execute().await.unwrap();
+    });
+}
+
+#[test]
+#[cfg_attr(not(feature = "test_as_root"), ignore)]
+fn set_bridge_vlan_global_options() {
+    let rt = Runtime::new().unwrap();
+    rt.block_on(async {
+        let (conn, handle, _) = new_connection().unwrap();
+        tokio::spawn(conn);
+        let link = handle.link();
+        link.add()
+            .bridge("vlanglobal0".into())
+            .vlan_filtering(true)
+            .execute()
+            .await
+            .unwrap();
+        let index = link.resolve("vlanglobal0").await.unwrap().unwrap();
+        let res = async {
+            link.set(index)
+                .bridge()
+                .boolopt(BridgeBoolOpt::McastVlanSnooping, true)
+                .execute()
+                .await?;
+            link.set_bridge_vlan_global(index, 1)
+                .mcast_snooping(false)
+                .execute()
+                .await?;
+            // The VLAN 2 does not exist
+            link.set_bridge_vlan_global(index, 2)
+                .mcast_snooping(false)
+                .execute()
+                .await
+        }
+        .await;
+        link.del(index).execute().await.unwrap();
+
+        assert!(matches!(res, Err(Error::NetlinkError(_))));
+    });
+}
+
 fn has_nla(msg: &LinkMessage, nla: &Nla) -> bool {
     msg.nlas.iter().any(|x| x == nla)
 }
@@ -0,0 +1,719 @@
+// SPDX-License-Identifier: MIT
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use netlink_packet_utils::{
+    byteorder::{BigEndian, ByteOrder, NativeEndian},
+    nla::{DefaultNla, Nla, NlasIterator},
+};
+
+use super::nla::encode;
+
+const IFLA_GENEVE_ID: u16 = 1;
+const IFLA_GENEVE_REMOTE: u16 = 2;
+const IFLA_GENEVE_TTL: u16 = 3;
+const IFLA_GENEVE_TOS: u16 = 4;
+const IFLA_GENEVE_PORT: u16 = 5;
+const IFLA_GENEVE_COLLECT_METADATA: u16 = 6;
+const IFLA_GENEVE_REMOTE6: u16 = 7;
+const IFLA_GENEVE_UDP_CSUM: u16 = 8;
+const IFLA_GENEVE_UDP_ZERO_CSUM6_TX: u16 = 9;
+const IFLA_GENEVE_UDP_ZERO_CSUM6_RX: u16 = 10;
+const IFLA_GENEVE_LABEL: u16 = 11;
+const IFLA_GENEVE_TTL_INHERIT: u16 = 12;
+const IFLA_GENEVE_DF: u16 = 13;
+
+const IFLA_GRE_LINK: u16 = 1;
+const IFLA_GRE_IFLAGS: u16 = 2;
+const IFLA_GRE_OFLAGS: u16 = 3;
+const IFLA_GRE_IKEY: u16 = 4;
+const IFLA_GRE_OKEY: u16 = 5;
+const IFLA_GRE_LOCAL: u16 = 6;
+const IFLA_GRE_REMOTE: u16 = 7;
+const IFLA_GRE_TTL: u16 = 8;
+const IFLA_GRE_TOS: u16 = 9;
+const IFLA_GRE_PMTUDISC: u16 = 10;
+const IFLA_GRE_ENCAP_LIMIT: u16 = 11;
+const IFLA_GRE_FLOWINFO: u16 = 12;
+const IFLA_GRE_FLAGS: u16 = 13;
+const IFLA_GRE_ENCAP_TYPE: u16 = 14;
+const IFLA_GRE_ENCAP_FLAGS: u16 = 15;
+const IFLA_GRE_ENCAP_SPORT: u16 = 16;
+const IFLA_GRE_ENCAP_DPORT: u16 = 17;
+const IFLA_GRE_COLLECT_METADATA: u16 = 18;
+
+const IFLA_IPTUN_LINK: u16 = 1;
+const IFLA_IPTUN_LOCAL: u16 = 2;
+const IFLA_IPTUN_REMOTE: u16 = 3;
+const IFLA_IPTUN_TTL: u16 = 4;
+const IFLA_IPTUN_TOS: u16 = 5;
+const IFLA_IPTUN_ENCAP_LIMIT: u16 = 6;
+const IFLA_IPTUN_FLOWINFO: u16 = 7;
+const IFLA_IPTUN_FLAGS: u16 = 8;
+const IFLA_IPTUN_PROTO: u16 = 9;
+const IFLA_IPTUN_PMTUDISC: u16 = 10;
+const IFLA_IPTUN_6RD_PREFIX: u16 = 11;
+const IFLA_IPTUN_6RD_RELAY_PREFIX: u16 = 12;
+const IFLA_IPTUN_6RD_PREFIXLEN: u16 = 13;
+const IFLA_IPTUN_6RD_RELAY_PREFIXLEN: u16 = 14;
+
+const IFLA_VTI_LINK: u16 = 1;
+const IFLA_VTI_IKEY: u16 = 2;
+const IFLA_VTI_OKEY: u16 = 3;
+const IFLA_VTI_LOCAL: u16 = 4;
+const IFLA_VTI_REMOTE: u16 = 5;
+const IFLA_VTI_FWMARK: u16 = 6;
+
+const IFLA_BAREUDP_PORT: u16 = 1;
+const IFLA_BAREUDP_ETHERTYPE: u16 = 2;
+const IFLA_BAREUDP_SRCPORT_MIN: u16 = 3;
+const IFLA_BAREUDP_MULTIPROTO_MODE: u16 = 4;
+
+const IFLA_GTP_FD0: u16 = 1;
+const IFLA_GTP_FD1: u16 = 2;
+const IFLA_GTP_PDP_HASHSIZE: u16 = 3;
+const IFLA_GTP_ROLE: u16 = 4;
+
+/// `GRE_CSUM`, GRE flag adding a checksum to the packets
+pub const GRE_CSUM: u16 = 0x8000;
+/// `GRE_KEY`, set in the GRE flags when a key is used
+pub const GRE_KEY: u16 = 0x2000;
+/// `GRE_SEQ`, GRE flag numbering the packets
+pub const GRE_SEQ: u16 = 0x1000;
+
+/// `IP6_TNL_F_IGN_ENCAP_LIMIT`, IPv6 tunnel flag omitting the tunnel
+/// encapsulation limit option
+pub(crate) const IP6_TNL_F_IGN_ENCAP_LIMIT: u32 = 0x1;
+
+pub(crate) const TUNNEL_ENCAP_FLAG_CSUM: u16 = 1 << 0;
+pub(crate) const TUNNEL_ENCAP_FLAG_REMCSUM: u16 = 1 << 2;
+
+/// The UDP encapsulation of the packets of an IP tunnel, the UDP port on
+/// the receiving side being set up with `ip fou add`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum TunnelEncap {
+    None,
+    /// Foo over UDP: the packets are put in UDP datagrams as is
+    Fou,
+    /// Generic UDP encapsulation, adding a header with the protocol of the
+    /// encapsulated packets
+    Gue,
+}
+
+impl From<TunnelEncap> for u16 {
+    fn from(encap: TunnelEncap) -> u16 {
+        match encap {
+            TunnelEncap::None => 0,
+            TunnelEncap::Fou => 1,
+            TunnelEncap::Gue => 2,
+        }
+    }
+}
+
+/// Whether a geneve link sets the "Don't Fragment" bit of the outgoing IPv4
+/// packets.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum GeneveDf {
+    Unset,
+    Set,
+    /// Copy the bit of the encapsulated IPv4 packet
+    Inherit,
+}
+
+impl From<GeneveDf> for u8 {
+    fn from(df: GeneveDf) -> u8 {
+        match df {
+            GeneveDf::Unset => 0,
+            GeneveDf::Set => 1,
+            GeneveDf::Inherit => 2,
+        }
+    }
+}
+
+/// The packets carried by an `ip6tnl` tunnel
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Ip6TnlMode {
+    /// Both IPv4 and IPv6 packets
+    Any,
+    /// IPv4 packets, `ipip6`
+    IpIp6,
+    /// IPv6 packets, `ip6ip6`
+    Ip6Ip6,
+}
+
+impl From<Ip6TnlMode> for u8 {
+    fn from(mode: Ip6TnlMode) -> u8 {
+        match mode {
+            Ip6TnlMode::Any => 0,
+            Ip6TnlMode::IpIp6 => 4,
+            Ip6TnlMode::Ip6Ip6 => 41,
+        }
+    }
+}
+
+/// The side of the GTP tunnels of a `gtp` link
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum GtpRole {
+    /// The gateway side, towards the packet data network
+    Ggsn,
+    /// The serving side, towards the radio access network
+    Sgsn,
+}
+
+impl From<GtpRole> for u32 {
+    fn from(role: GtpRole) -> u32 {
+        match role {
+            GtpRole::Ggsn => 0,
+            GtpRole::Sgsn => 1,
+        }
+    }
+}
+
+/// Geneve attributes (`IFLA_GENEVE_*`), which `netlink-packet-route` does not
+/// implement.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum InfoGeneve {
+    Id(u32),
+    Remote(Ipv4Addr),
+    Remote6(Ipv6Addr),
+    Ttl(u8),
+    Tos(u8),
+    Port(u16),
+    CollectMetadata,
+    UdpCsum(bool),
+    UdpZeroCsum6Tx(bool),
+    UdpZeroCsum6Rx(bool),
+    Label(u32),
+    TtlInherit(bool),
+    Df(u8),
+    Other(DefaultNla),
+}
+
+impl Nla for InfoGeneve {
+    fn value_len(&self) -> usize {
+        use self::InfoGeneve::*;
+        match self {
+            CollectMetadata => 0,
+            Ttl(_) | Tos(_) | UdpCsum(_) | UdpZeroCsum6Tx(_)
+            | UdpZeroCsum6Rx(_) | TtlInherit(_) | Df(_) => 1,
+            Port(_) => 2,
+            Id(_) | Remote(_) | Label(_) => 4,
+            Remote6(_) => 16,
+            Other(nla) => nla.value_len(),
+        }
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        use self::InfoGeneve::*;
+        match self {
+            CollectMetadata => (),
+            Ttl(value) | Tos(value) | Df(value) => buffer[0] = *value,
+            UdpCsum(value)
+            | UdpZeroCsum6Tx(value)
+            | UdpZeroCsum6Rx(value)
+            | TtlInherit(value) => buffer[0] = *value as u8,
+            Port(value) => BigEndian::write_u16(buffer, *value),
+            Id(value) => NativeEndian::write_u32(buffer, *value),
+            Label(value) => BigEndian::write_u32(buffer, *value),
+            Remote(address) => buffer.copy_from_slice(&address.octets()),
+            Remote6(address) => buffer.copy_from_slice(&address.octets()),
+            Other(nla) => nla.emit_value(buffer),
+        }
+    }
+
+    fn kind(&self) -> u16 {
+        use self::InfoGeneve::*;
+        match self {
+            Id(_) => IFLA_GENEVE_ID,
+            Remote(_) => IFLA_GENEVE_REMOTE,
+            Remote6(_) => IFLA_GENEVE_REMOTE6,
+            Ttl(_) => IFLA_GENEVE_TTL,
+            Tos(_) => IFLA_GENEVE_TOS,
+            Port(_) => IFLA_GENEVE_PORT,
+            CollectMetadata => IFLA_GENEVE_COLLECT_METADATA,
+            UdpCsum(_) => IFLA_GENEVE_UDP_CSUM,
+            UdpZeroCsum6Tx(_) => IFLA_GENEVE_UDP_ZERO_CSUM6_TX,
+            UdpZeroCsum6Rx(_) => IFLA_GENEVE_UDP_ZERO_CSUM6_RX,
+            Label(_) => IFLA_GENEVE_LABEL,
+            TtlInherit(_) => IFLA_GENEVE_TTL_INHERIT,
+            Df(_) => IFLA_GENEVE_DF,
+            Other(nla) => nla.kind(),
+        }
+    }
+}
+
+/// GRE attributes (`IFLA_GRE_*`), of the `gre`, `gretap`, `ip6gre` and
+/// `ip6gretap` links. The `netlink-packet-route` version used by this crate
+/// only handles them as raw bytes.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum InfoGre {
+    Link(u32),
+    IFlags(u16),
+    OFlags(u16),
+    IKey(u32),
+    OKey(u32),
+    Local(Ipv4Addr),
+    Remote(Ipv4Addr),
+    Local6(Ipv6Addr),
+    Remote6(Ipv6Addr),
+    Ttl(u8),
+    Tos(u8),
+    PMtuDisc(bool),
+    EncapLimit(u8),
+    FlowInfo(u32),
+    Flags(u32),
+    EncapType(u16),
+    EncapFlags(u16),
+    EncapSport(u16),
+    EncapDport(u16),
+    CollectMetadata,
+    Other(DefaultNla),
+}
+
+impl Nla for InfoGre {
+    fn value_len(&self) -> usize {
+        use self::InfoGre::*;
+        match self {
+            CollectMetadata => 0,
+            Ttl(_) | Tos(_) | PMtuDisc(_) | EncapLimit(_) => 1,
+            IFlags(_) | OFlags(_) | EncapType(_) | EncapFlags(_)
+            | EncapSport(_) | EncapDport(_) => 2,
+            Link(_) | IKey(_) | OKey(_) | Local(_) | Remote(_)
+            | FlowInfo(_) | Flags(_) => 4,
+            Local6(_) | Remote6(_) => 16,
+            Other(nla) => nla.value_len(),
+        }
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        use self::InfoGre::*;
+        match self {
+            CollectMetadata => (),
+            Ttl(value) | Tos(value) | EncapLimit(value) => buffer[0] = *value,
+            PMtuDisc(value) => buffer[0] = *value as u8,
+            IFlags(value) | OFlags(value) | EncapSport(value)
+            | EncapDport(value) => BigEndian::write_u16(buffer, *value),
+            EncapType(value) | EncapFlags(value) => {
+                NativeEndian::write_u16(buffer, *value)
+            }
+            Link(value) | Flags(value) => {
+                NativeEndian::write_u32(buffer, *value)
+            }
+            IKey(value) | OKey(value) | FlowInfo(value) => {
+                BigEndian::write_u32(buffer, *value)
+            }
+            Local(address) | Remote(address) => {
+                buffer.copy_from_slice(&address.octets())
+            }
+            Local6(address) | Remote6(address) => {
+                buffer.copy_from_slice(&address.octets())
+            }
+            Other(nla) => nla.emit_value(buffer),
+        }
+    }
+
+    fn kind(&self) -> u16 {
+        use self::InfoGre::*;
+        match self {
+            Link(_) => IFLA_GRE_LINK,
+            IFlags(_) => IFLA_GRE_IFLAGS,
+            OFlags(_) => IFLA_GRE_OFLAGS,
+            IKey(_) => IFLA_GRE_IKEY,
+            OKey(_) => IFLA_GRE_OKEY,
+            Local(_) | Local6(_) => IFLA_GRE_LOCAL,
+            Remote(_) | Remote6(_) => IFLA_GRE_REMOTE,
+            Ttl(_) => IFLA_GRE_TTL,
+            Tos(_) => IFLA_GRE_TOS,
+            PMtuDisc(_) => IFLA_GRE_PMTUDISC,
+            EncapLimit(_) => IFLA_GRE_ENCAP_LIMIT,
+            FlowInfo(_) => IFLA_GRE_FLOWINFO,
+            Flags(_) => IFLA_GRE_FLAGS,
+            EncapType(_) => IFLA_GRE_ENCAP_TYPE,
+            EncapFlags(_) => IFLA_GRE_ENCAP_FLAGS,
+            EncapSport(_) => IFLA_GRE_ENCAP_SPORT,
+            EncapDport(_) => IFLA_GRE_ENCAP_DPORT,
+            CollectMetadata => IFLA_GRE_COLLECT_METADATA,
+            Other(nla) => nla.kind(),
+        }
+    }
+}
+
+/// IP in IP tunnel attributes (`IFLA_IPTUN_*`), of the `ipip`, `sit` and
+/// `ip6tnl` links. The `netlink-packet-route` version used by this crate only
+/// handles them as raw bytes.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum InfoIpTun {
+    Link(u32),
+    Local(Ipv4Addr),
+    Remote(Ipv4Addr),
+    Local6(Ipv6Addr),
+    Remote6(Ipv6Addr),
+    Ttl(u8),
+    Tos(u8),
+    EncapLimit(u8),
+    FlowInfo(u32),
+    Flags(u32),
+    Proto(u8),
+    PMtuDisc(bool),
+    Ip6rdPrefix(Ipv6Addr),
+    Ip6rdRelayPrefix(Ipv4Addr),
+    Ip6rdPrefixLen(u16),
+    Ip6rdRelayPrefixLen(u16),
+    Other(DefaultNla),
+}
+
+impl Nla for InfoIpTun {
+    fn value_len(&self) -> usize {
+        use self::InfoIpTun::*;
+        match self {
+            Ttl(_) | Tos(_) | EncapLimit(_) | Proto(_) | PMtuDisc(_) => 1,
+            Ip6rdPrefixLen(_) | Ip6rdRelayPrefixLen(_) => 2,
+            Link(_) | Local(_) | Remote(_) | FlowInfo(_) | Flags(_)
+            | Ip6rdRelayPrefix(_) => 4,
+            Local6(_) | Remote6(_) | Ip6rdPrefix(_) => 16,
+            Other(nla) => nla.value_len(),
+        }
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        use self::InfoIpTun::*;
+        match self {
+            Ttl(value) | Tos(value) | EncapLimit(value) | Proto(value) => {
+                buffer[0] = *value
+            }
+            PMtuDisc(value) => buffer[0] = *value as u8,
+            Ip6rdPrefixLen(value) | Ip6rdRelayPrefixLen(value) => {
+                NativeEndian::write_u16(buffer, *value)
+            }
+            Link(value) | Flags(value) => {
+                NativeEndian::write_u32(buffer, *value)
+            }
+            FlowInfo(value) => BigEndian::write_u32(buffer, *value),
+            Local(address) | Remote(address) | Ip6rdRelayPrefix(address) => {
+                buffer.copy_from_slice(&address.octets())
+            }
+            Local6(address) | Remote6(address) | Ip6rdPrefix(address) => {
+                buffer.copy_from_slice(&address.octets())
+            }
+            Other(nla) => nla.emit_value(buffer),
+        }
+    }
+
+    fn kind(&self) -> u16 {
+        use self::InfoIpTun::*;
+        match self {
+            Link(_) => IFLA_IPTUN_LINK,
+            Local(_) | Local6(_) => IFLA_IPTUN_LOCAL,
+            Remote(_) | Remote6(_) => IFLA_IPTUN_REMOTE,
+            Ttl(_) => IFLA_IPTUN_TTL,
+            Tos(_) => IFLA_IPTUN_TOS,
+            EncapLimit(_) => IFLA_IPTUN_ENCAP_LIMIT,
+            FlowInfo(_) => IFLA_IPTUN_FLOWINFO,
+            Flags(_) => IFLA_IPTUN_FLAGS,
+            Proto(_) => IFLA_IPTUN_PROTO,
+            PMtuDisc(_) => IFLA_IPTUN_PMTUDISC,
+            Ip6rdPrefix(_) => IFLA_IPTUN_6RD_PREFIX,
+            Ip6rdRelayPrefix(_) => IFLA_IPTUN_6RD_RELAY_PREFIX,
+            Ip6rdPrefixLen(_) => IFLA_IPTUN_6RD_PREFIXLEN,
+            Ip6rdRelayPrefixLen(_) => IFLA_IPTUN_6RD_RELAY_PREFIXLEN,
+            Other(nla) => nla.kind(),
+        }
+    }
+}
+
+/// VTI attributes (`IFLA_VTI_*`), of the `vti` and `vti6` links. The
+/// `netlink-packet-route` version used by this crate only handles them as
+/// raw bytes.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum InfoVti {
+    Link(u32),
+    IKey(u32),
+    OKey(u32),
+    Local(IpAddr),
+    Remote(IpAddr),
+    FwMark(u32),
+    Other(DefaultNla),
+}
+
+impl Nla for InfoVti {
+    fn value_len(&self) -> usize {
+        use self::InfoVti::*;
+        match self {
+            Link(_) | IKey(_) | OKey(_) | FwMark(_) => 4,
+            Local(IpAddr::V4(_)) | Remote(IpAddr::V4(_)) => 4,
+            Local(IpAddr::V6(_)) | Remote(IpAddr::V6(_)) => 16,
+            Other(nla) => nla.value_len(),
+        }
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        use self::InfoVti::*;
+        match self {
+            Link(value) | FwMark(value) => {
+                NativeEndian::write_u32(buffer, *value)
+            }
+            IKey(value) | OKey(value) => BigEndian::write_u32(buffer, *value),
+            Local(IpAddr::V4(address)) | Remote(IpAddr::V4(address)) => {
+                buffer.copy_from_slice(&address.octets())
+            }
+            Local(IpAddr::V6(address)) | Remote(IpAddr::V6(address)) => {
+                buffer.copy_from_slice(&address.octets())
+            }
+            Other(nla) => nla.emit_value(buffer),
+        }
+    }
+
+    fn kind(&self) -> u16 {
+        use self::InfoVti::*;
+        match self {
+            Link(_) => IFLA_VTI_LINK,
+            IKey(_) => IFLA_VTI_IKEY,
+            OKey(_) => IFLA_VTI_OKEY,
+            Local(_) => IFLA_VTI_LOCAL,
+            Remote(_) => IFLA_VTI_REMOTE,
+            FwMark(_) => IFLA_VTI_FWMARK,
+            Other(nla) => nla.kind(),
+        }
+    }
+}
+
+/// Bareudp attributes (`IFLA_BAREUDP_*`), which `netlink-packet-route` does
+/// not implement.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum InfoBareUdp {
+    Port(u16),
+    Ethertype(u16),
+    SrcPortMin(u16),
+    MultiprotoMode,
+    Other(DefaultNla),
+}
+
+impl Nla for InfoBareUdp {
+    fn value_len(&self) -> usize {
+        use self::InfoBareUdp::*;
+        match self {
+            MultiprotoMode => 0,
+            Port(_) | Ethertype(_) | SrcPortMin(_) => 2,
+            Other(nla) => nla.value_len(),
+        }
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        use self::InfoBareUdp::*;
+        match self {
+            MultiprotoMode => (),
+            Port(value) | Ethertype(value) => {
+                BigEndian::write_u16(buffer, *value)
+            }
+            SrcPortMin(value) => NativeEndian::write_u16(buffer, *value),
+            Other(nla) => nla.emit_value(buffer),
+        }
+    }
+
+    fn kind(&self) -> u16 {
+        use self::InfoBareUdp::*;
+        match self {
+            Port(_) => IFLA_BAREUDP_PORT,
+            Ethertype(_) => IFLA_BAREUDP_ETHERTYPE,
+            SrcPortMin(_) => IFLA_BAREUDP_SRCPORT_MIN,
+            MultiprotoMode => IFLA_BAREUDP_MULTIPROTO_MODE,
+            Other(nla) => nla.kind(),
+        }
+    }
+}
+
+/// GTP attributes (`IFLA_GTP_*`), which `netlink-packet-route` only handles
+/// as raw bytes.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum InfoGtp {
+    Fd0(u32),
+    Fd1(u32),
+    PdpHashsize(u32),
+    Role(u32),
+    Other(DefaultNla),
+}
+
+impl Nla for InfoGtp {
+    fn value_len(&self) -> usize {
+        use self::InfoGtp::*;
+        match self {
+            Fd0(_) | Fd1(_) | PdpHashsize(_) | Role(_) => 4,
+            Other(nla) => nla.value_len(),
+        }
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        use self::InfoGtp::*;
+        match self {
+            Fd0(value) | Fd1(value) | PdpHashsize(value) | Role(value) => {
+                NativeEndian::write_u32(buffer, *value)
+            }
+            Other(nla) => nla.emit_value(buffer),
+        }
+    }
+
+    fn kind(&self) -> u16 {
+        use self::InfoGtp::*;
+        match self {
+            Fd0(_) => IFLA_GTP_FD0,
+            Fd1(_) => IFLA_GTP_FD1,
+            PdpHashsize(_) => IFLA_GTP_PDP_HASHSIZE,
+            Role(_) => IFLA_GTP_ROLE,
+            Other(nla) => nla.kind(),
+        }
+    }
+}
+
+/// The attributes setting the key of a GRE tunnel in both directions, the
+/// other flags being taken from its current raw `IFLA_INFO_DATA`
+pub(crate) fn gre_key(data: &[u8], key: u32) -> [InfoGre; 4] {
+    let flags = |kind: u16| {
+        NlasIterator::new(data)
+            .flatten()
+            .find(|nla| nla.kind() == kind && nla.value().len() == 2)
+            .map(|nla| BigEndian::read_u16(nla.value()))
+            .unwrap_or(0)
+    };
+    [
+        InfoGre::IKey(key),
+        InfoGre::OKey(key),
+        InfoGre::IFlags(flags(IFLA_GRE_IFLAGS) | GRE_KEY),
+        InfoGre::OFlags(flags(IFLA_GRE_OFLAGS) | GRE_KEY),
+    ]
+}
+
+/// Replace, in raw `IFLA_INFO_DATA`, the attributes of the same types as
+/// `changes`, and add the others
+pub(crate) fn merge<T: Nla>(data: &[u8], changes: &[T]) -> Vec<u8> {
+    let mut nlas: Vec<_> = NlasIterator::new(data)
+        .flatten()
+        .filter(|nla| !changes.iter().any(|c| c.kind() == nla.kind()))
+        .map(|nla| DefaultNla::new(nla.kind(), nla.value().to_vec()))
+        .collect();
+    nlas.extend(changes.iter().map(|nla| {
+        let mut value = vec![0; nla.value_len()];
+        nla.emit_value(&mut value);
+        DefaultNla::new(nla.kind(), value)
+    }));
+    encode(&nlas)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_encode_tunnel_attributes() {
+        assert_eq!(
+            encode(&[
+                InfoGeneve::Id(10),
+                InfoGeneve::Port(6081),
+                InfoGeneve::CollectMetadata,
+                InfoGeneve::Df(GeneveDf::Inherit.into()),
+            ]),
+            vec![
+                8, 0, 1, 0, 10, 0, 0, 0, // IFLA_GENEVE_ID
+                6, 0, 5, 0, 0x17, 0xc1, 0, 0, // IFLA_GENEVE_PORT
+                4, 0, 6, 0, // IFLA_GENEVE_COLLECT_METADATA
+                5, 0, 13, 0, 2, 0, 0, 0, // IFLA_GENEVE_DF
+            ]
+        );
+        assert_eq!(
+            encode(&[
+                InfoGre::Remote(Ipv4Addr::new(192, 0, 2, 1)),
+                InfoGre::OKey(1),
+                InfoGre::OFlags(GRE_KEY),
+            ]),
+            vec![
+                8, 0, 7, 0, 192, 0, 2, 1, // IFLA_GRE_REMOTE
+                8, 0, 5, 0, 0, 0, 0, 1, // IFLA_GRE_OKEY
+                6, 0, 3, 0, 0x20, 0, 0, 0, // IFLA_GRE_OFLAGS
+            ]
+        );
+        assert_eq!(
+            encode(&[InfoBareUdp::Ethertype(0x8847)]),
+            vec![6, 0, 2, 0, 0x88, 0x47, 0, 0]
+        );
+        assert_eq!(
+            encode(&[
+                InfoGre::IKey(1),
+                InfoGre::IFlags(GRE_KEY),
+                InfoGre::EncapDport(5555),
+                InfoGre::EncapLimit(2),
+            ]),
+            vec![
+                8, 0, 4, 0, 0, 0, 0, 1, // IFLA_GRE_IKEY
+                6, 0, 2, 0, 0x20, 0, 0, 0, // IFLA_GRE_IFLAGS
+                6, 0, 17, 0, 0x15, 0xb3, 0, 0, // IFLA_GRE_ENCAP_DPORT
+                5, 0, 11, 0, 2, 0, 0, 0, // IFLA_GRE_ENCAP_LIMIT
+            ]
+        );
+        let mut expected = vec![
+            8, 0, 12, 0, 192, 0, 0, 0, // IFLA_IPTUN_6RD_RELAY_PREFIX
+            6, 0, 14, 0, // IFLA_IPTUN_6RD_RELAY_PREFIXLEN
+        ];
+        expected.extend_from_slice(&8u16.to_ne_bytes());
+        expected.extend_from_slice(&[0, 0]);
+        assert_eq!(
+            encode(&[
+                InfoIpTun::Ip6rdRelayPrefix(Ipv4Addr::new(192, 0, 0, 0)),
+                InfoIpTun::Ip6rdRelayPrefixLen(8),
+            ]),
+            expected
+        );
+        assert_eq!(
+            encode(&[
+                InfoIpTun::Proto(Ip6TnlMode::IpIp6.into()),
+                InfoIpTun::FlowInfo(0x12345),
+            ]),
+            vec![
+                5, 0, 9, 0, 4, 0, 0, 0, // IFLA_IPTUN_PROTO
+                8, 0, 7, 0, 0, 1, 0x23, 0x45, // IFLA_IPTUN_FLOWINFO
+            ]
+        );
+        assert_eq!(
+            encode(&[
+                InfoGeneve::Label(0x12345),
+                InfoGeneve::UdpZeroCsum6Tx(true),
+            ]),
+            vec![
+                8, 0, 11, 0, 0, 1, 0x23, 0x45, // IFLA_GENEVE_LABEL
+                5, 0, 9, 0, 1, 0, 0, 0, // IFLA_GENEVE_UDP_ZERO_CSUM6_TX
+            ]
+        );
+        assert_eq!(
+            encode(&[
+                InfoVti::Remote(Ipv4Addr::new(192, 0, 2, 1).into()),
+                InfoVti::OKey(2),
+            ]),
+            vec![
+                8, 0, 5, 0, 192, 0, 2, 1, // IFLA_VTI_REMOTE
+                8, 0, 3, 0, 0, 0, 0, 2, // IFLA_VTI_OKEY
+            ]
+        );
+    }
+
+    #[test]
+    fn test_merge_tunnel_attributes() {
+        let data = encode(&[
+            InfoIpTun::Local(Ipv4Addr::new(192, 0, 2, 1)),
+            InfoIpTun::Remote(Ipv4Addr::new(192, 0, 2, 2)),
+            InfoIpTun::Ttl(64),
+        ]);
+        assert_eq!(
+            merge(
+                &data,
+                &[
+                    InfoIpTun::Remote(Ipv4Addr::new(192, 0, 2, 3)),
+                    InfoIpTun::Tos(1)
+                ]
+            ),
+            encode(&[
+                InfoIpTun::Local(Ipv4Addr::new(192, 0, 2, 1)),
+                InfoIpTun::Ttl(64),
+                InfoIpTun::Remote(Ipv4Addr::new(192, 0, 2, 3)),
+                InfoIpTun::Tos(1),
+            ])
+        );
+    }
+}
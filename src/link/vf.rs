@@ -0,0 +1,218 @@
+// SPDX-License-Identifier: MIT
+
+use netlink_packet_utils::{
+    byteorder::{BigEndian, ByteOrder, NativeEndian},
+    nla::Nla,
+};
+
+use super::nla;
+use crate::MacAddress;
+
+const IFLA_VF_INFO: u16 = 1;
+
+const IFLA_VF_MAC: u16 = 1;
+const IFLA_VF_VLAN: u16 = 2;
+const IFLA_VF_SPOOFCHK: u16 = 4;
+const IFLA_VF_LINK_STATE: u16 = 5;
+const IFLA_VF_RATE: u16 = 6;
+const IFLA_VF_TRUST: u16 = 9;
+const IFLA_VF_VLAN_LIST: u16 = 12;
+
+const IFLA_VF_VLAN_INFO: u16 = 1;
+
+// The address of struct ifla_vf_mac has room for 32 bytes
+const VF_MAC_LEN: usize = 32;
+
+/// The link state of a VF, as seen by the driver in the guest
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum VfLinkState {
+    /// Follow the link state of the physical function
+    Auto,
+    /// Always up
+    Enable,
+    /// Always down
+    Disable,
+}
+
+impl From<VfLinkState> for u32 {
+    fn from(state: VfLinkState) -> u32 {
+        match state {
+            VfLinkState::Auto => 0,
+            VfLinkState::Enable => 1,
+            VfLinkState::Disable => 2,
+        }
+    }
+}
+
+/// A setting of a VF, each of them being sent as a `struct ifla_vf_*`
+/// starting with the index of the VF
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub(crate) enum VfSetting {
+    Mac(MacAddress),
+    Vlan { id: u16, qos: u8 },
+    // struct ifla_vf_vlan_info, nested in IFLA_VF_VLAN_LIST
+    VlanWithProtocol { id: u16, qos: u8, protocol: u16 },
+    Rate { min: u32, max: u32 },
+    SpoofCheck(bool),
+    LinkState(VfLinkState),
+    Trust(bool),
+}
+
+struct VfNla<'a> {
+    vf: u32,
+    setting: &'a VfSetting,
+}
+
+impl Nla for VfNla<'_> {
+    fn value_len(&self) -> usize {
+        use self::VfSetting::*;
+        match self.setting {
+            Mac(_) => 4 + VF_MAC_LEN,
+            Vlan { .. } | Rate { .. } => 12,
+            // Attribute header and 16 bytes struct (with 2 bytes of padding)
+            VlanWithProtocol { .. } => 4 + 16,
+            SpoofCheck(_) | LinkState(_) | Trust(_) => 8,
+        }
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        use self::VfSetting::*;
+        buffer.fill(0);
+        match self.setting {
+            Mac(address) => {
+                NativeEndian::write_u32(&mut buffer[..4], self.vf);
+                buffer[4..10].copy_from_slice(&address.octets());
+            }
+            Vlan { id, qos } => {
+                NativeEndian::write_u32(&mut buffer[..4], self.vf);
+                NativeEndian::write_u32(&mut buffer[4..8], *id as u32);
+                NativeEndian::write_u32(&mut buffer[8..12], *qos as u32);
+            }
+            VlanWithProtocol { id, qos, protocol } => {
+                NativeEndian::write_u16(&mut buffer[..2], 20);
+                NativeEndian::write_u16(&mut buffer[2..4], IFLA_VF_VLAN_INFO);
+                let info = &mut buffer[4..];
+                NativeEndian::write_u32(&mut info[..4], self.vf);
+                NativeEndian::write_u32(&mut info[4..8], *id as u32);
+                NativeEndian::write_u32(&mut info[8..12], *qos as u32);
+                BigEndian::write_u16(&mut info[12..14], *protocol);
+            }
+            Rate { min, max } => {
+                NativeEndian::write_u32(&mut buffer[..4], self.vf);
+                NativeEndian::write_u32(&mut buffer[4..8], *min);
+                NativeEndian::write_u32(&mut buffer[8..12], *max);
+            }
+            SpoofCheck(enable) | Trust(enable) => {
+                NativeEndian::write_u32(&mut buffer[..4], self.vf);
+                NativeEndian::write_u32(&mut buffer[4..8], *enable as u32);
+            }
+            LinkState(state) => {
+                NativeEndian::write_u32(&mut buffer[..4], self.vf);
+                NativeEndian::write_u32(&mut buffer[4..8], (*state).into());
+            }
+        }
+    }
+
+    fn kind(&self) -> u16 {
+        use self::VfSetting::*;
+        match self.setting {
+            Mac(_) => IFLA_VF_MAC,
+            Vlan { .. } => IFLA_VF_VLAN,
+            VlanWithProtocol { .. } => IFLA_VF_VLAN_LIST,
+            Rate { .. } => IFLA_VF_RATE,
+            SpoofCheck(_) => IFLA_VF_SPOOFCHK,
+            LinkState(_) => IFLA_VF_LINK_STATE,
+            Trust(_) => IFLA_VF_TRUST,
+        }
+    }
+}
+
+struct VfInfo<'a> {
+    vf: u32,
+    settings: &'a [VfSetting],
+}
+
+impl Nla for VfInfo<'_> {
+    fn value_len(&self) -> usize {
+        nla::encode(&self.nlas()).len()
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        buffer.copy_from_slice(&nla::encode(&self.nlas()))
+    }
+
+    fn kind(&self) -> u16 {
+        IFLA_VF_INFO
+    }
+}
+
+impl VfInfo<'_> {
+    fn nlas(&self) -> Vec<VfNla<'_>> {
+        self.settings
+            .iter()
+            .map(|setting| VfNla {
+                vf: self.vf,
+                setting,
+            })
+            .collect()
+    }
+}
+
+/// The value of the `IFLA_VFINFO_LIST` attribute applying `settings` to the
+/// VF `vf`
+pub(crate) fn vf_info_list(vf: u32, settings: &[VfSetting]) -> Vec<u8> {
+    nla::encode(&[VfInfo { vf, settings }])
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_vf_vlan_with_protocol() {
+        let settings = [VfSetting::VlanWithProtocol {
+            id: 100,
+            qos: 3,
+            protocol: 0x88a8,
+        }];
+        let buf = vf_info_list(2, &settings);
+        let mut expected = vec![
+            28, 0, 1, 0, // IFLA_VF_INFO
+            24, 0, 12, 0, // IFLA_VF_VLAN_LIST
+            20, 0, 1, 0, // IFLA_VF_VLAN_INFO
+        ];
+        expected.extend_from_slice(&2u32.to_ne_bytes());
+        expected.extend_from_slice(&100u32.to_ne_bytes());
+        expected.extend_from_slice(&3u32.to_ne_bytes());
+        expected.extend_from_slice(&[0x88, 0xa8, 0, 0]);
+        assert_eq!(buf, expected);
+    }
+
+    #[test]
+    fn test_vf_settings() {
+        let settings = [
+            VfSetting::Mac(MacAddress([0x02, 0, 0, 0, 0, 1])),
+            VfSetting::Rate {
+                min: 100,
+                max: 1000,
+            },
+            VfSetting::LinkState(VfLinkState::Disable),
+        ];
+        let buf = vf_info_list(3, &settings);
+        let mut expected = vec![
+            72, 0, 1, 0, // IFLA_VF_INFO
+            40, 0, 1, 0, // IFLA_VF_MAC
+        ];
+        expected.extend_from_slice(&3u32.to_ne_bytes());
+        expected.extend_from_slice(&[0x02, 0, 0, 0, 0, 1]);
+        expected.extend_from_slice(&[0; 26]);
+        expected.extend_from_slice(&[16, 0, 6, 0]); // IFLA_VF_RATE
+        for value in [3u32, 100, 1000] {
+            expected.extend_from_slice(&value.to_ne_bytes());
+        }
+        expected.extend_from_slice(&[12, 0, 5, 0]); // IFLA_VF_LINK_STATE
+        expected.extend_from_slice(&3u32.to_ne_bytes());
+        expected.extend_from_slice(&2u32.to_ne_bytes());
+        assert_eq!(buf, expected);
+    }
+}
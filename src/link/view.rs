@@ -0,0 +1,171 @@
+// SPDX-License-Identifier: MIT
+
+use netlink_packet_route::{
+    link::nlas::{Info, InfoKind, Nla, State},
+    LinkMessage,
+};
+use netlink_packet_utils::byteorder::{ByteOrder, NativeEndian};
+
+/// The most commonly used properties of a link, extracted from a
+/// [`LinkMessage`] (for instance one returned by
+/// [`crate::LinkGetRequest::execute`]).
+///
+/// ```no_run
+/// use futures::stream::TryStreamExt;
+/// use rtnetlink::{Handle, Link};
+///
+/// async fn print_links(handle: Handle) -> Result<(), rtnetlink::Error> {
+///     let mut links = handle.link().get().execute();
+///     while let Some(msg) = links.try_next().await? {
+///         let link = Link::from(msg);
+///         println!("{}: {} mtu {:?}", link.index, link.name, link.mtu);
+///     }
+///     Ok(())
+/// }
+/// ```
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Link {
+    pub index: u32,
+    pub name: String,
+    /// `IFF_*` flags of the link
+    pub flags: u32,
+    /// `ARPHRD_*` type of the link layer
+    pub link_layer_type: u16,
+    pub mtu: Option<u32>,
+    #[cfg_attr(feature = "serde", serde(serialize_with = "serialize_state"))]
+    pub oper_state: State,
+    /// Kind of the link (bridge, veth, vlan, ...). `None` for physical
+    /// interfaces.
+    #[cfg_attr(feature = "serde", serde(serialize_with = "serialize_kind"))]
+    pub kind: Option<InfoKind>,
+    /// Link layer address
+    pub address: Option<Vec<u8>>,
+    /// Index of the controller (bridge, bond, ...) of the link
+    pub master: Option<u32>,
+    pub stats: Option<LinkStats>,
+}
+
+/// Link counters, from the `IFLA_STATS64` attribute.
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct LinkStats {
+    pub rx_packets: u64,
+    pub tx_packets: u64,
+    pub rx_bytes: u64,
+    pub tx_bytes: u64,
+    pub rx_errors: u64,
+    pub tx_errors: u64,
+    pub rx_dropped: u64,
+    pub tx_dropped: u64,
+}
+
+impl LinkStats {
+    fn parse(buf: &[u8]) -> Option<Self> {
+        if buf.len() < 64 {
+            return None;
+        }
+        let counter = |i: usize| NativeEndian::read_u64(&buf[i * 8..]);
+        Some(LinkStats {
+            rx_packets: counter(0),
+            tx_packets: counter(1),
+            rx_bytes: counter(2),
+            tx_bytes: counter(3),
+            rx_errors: counter(4),
+            tx_errors: counter(5),
+            rx_dropped: counter(6),
+            tx_dropped: counter(7),
+        })
+    }
+
+    /// The increase of the counters since `previous`. Counters which went
+    /// back (because the link was recreated for instance) give 0.
+    pub fn delta(&self, previous: &LinkStats) -> LinkStats {
+        LinkStats {
+            rx_packets: self.rx_packets.saturating_sub(previous.rx_packets),
+            tx_packets: self.tx_packets.saturating_sub(previous.tx_packets),
+            rx_bytes: self.rx_bytes.saturating_sub(previous.rx_bytes),
+            tx_bytes: self.tx_bytes.saturating_sub(previous.tx_bytes),
+            rx_errors: self.rx_errors.saturating_sub(previous.rx_errors),
+            tx_errors: self.tx_errors.saturating_sub(previous.tx_errors),
+            rx_dropped: self.rx_dropped.saturating_sub(previous.rx_dropped),
+            tx_dropped: self.tx_dropped.saturating_sub(previous.tx_dropped),
+        }
+    }
+}
+
+impl From<&LinkMessage> for Link {
+    fn from(message: &LinkMessage) -> Self {
+        let mut link = Link {
+            index: message.header.index,
+            name: String::new(),
+            flags: message.header.flags,
+            link_layer_type: message.header.link_layer_type,
+            mtu: None,
+            oper_state: State::Unknown,
+            kind: None,
+            address: None,
+            master: None,
+            stats: None,
+        };
+        for nla in &message.nlas {
+            match nla {
+                Nla::IfName(name) => link.name = name.clone(),
+                Nla::Mtu(mtu) => link.mtu = Some(*mtu),
+                Nla::OperState(state) => link.oper_state = *state,
+                Nla::Address(address) => link.address = Some(address.clone()),
+                Nla::Master(index) => link.master = Some(*index),
+                Nla::Stats64(buf) => link.stats = LinkStats::parse(buf),
+                Nla::Info(infos) => {
+                    link.kind = infos.iter().find_map(|info| match info {
+                        Info::Kind(kind) => Some(kind.clone()),
+                        _ => None,
+                    })
+                }
+                _ => (),
+            }
+        }
+        link
+    }
+}
+
+impl From<LinkMessage> for Link {
+    fn from(message: LinkMessage) -> Self {
+        Link::from(&message)
+    }
+}
+
+// `State` and `InfoKind` are serialized the way `ip -json link` shows
+// them, e.g. `"UP"` and `"bridge"`.
+#[cfg(feature = "serde")]
+fn serialize_state<S>(state: &State, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    serializer.serialize_str(&format!("{:?}", state).to_uppercase())
+}
+
+#[cfg(feature = "serde")]
+fn serialize_kind<S>(
+    kind: &Option<InfoKind>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    match kind {
+        Some(kind) => serializer.serialize_some(&kind_name(kind)),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// The name of `kind`, as in `ip link add type <kind>`
+pub(crate) fn kind_name(kind: &InfoKind) -> String {
+    use netlink_packet_utils::nla::Nla as _;
+
+    let mut buf = vec![0; kind.value_len()];
+    kind.emit_value(&mut buf);
+    String::from_utf8_lossy(&buf)
+        .trim_end_matches('\0')
+        .to_string()
+}
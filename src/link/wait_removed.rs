@@ -0,0 +1,83 @@
+// SPDX-License-Identifier: MIT
+
+use std::time::Duration;
+
+use futures::TryStreamExt;
+use netlink_packet_core::{NetlinkMessage, NetlinkPayload};
+use netlink_packet_route::{RtnlMessage, RTM_DELLINK, RTNLGRP_LINK};
+use netlink_sys::{AsyncSocket, AsyncSocketExt};
+
+use crate::{
+    handle::{sleep, timeout_after},
+    raw::{handle_socket, split_messages},
+    Error, Handle,
+};
+
+// How often the link is looked up when the events can't be relied on
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+async fn exists(handle: &Handle, index: u32) -> Result<bool, Error> {
+    let link = handle
+        .link()
+        .get()
+        .match_index(index)
+        .execute()
+        .try_next()
+        .await;
+    match link {
+        Ok(link) => Ok(link.is_some()),
+        Err(err) if err.is_not_found() => Ok(false),
+        Err(err) => Err(err),
+    }
+}
+
+pub(crate) async fn wait_removed<S>(
+    handle: Handle,
+    index: u32,
+    timeout: Duration,
+) -> Result<(), Error>
+where
+    S: AsyncSocket,
+{
+    // Subscribe before looking the link up, so that its removal can't
+    // happen unnoticed in between. The socket is in the network namespace of
+    // the handle, like the link.
+    let mut socket = handle_socket::<S>(&handle)?;
+    socket.socket_mut().add_membership(RTNLGRP_LINK)?;
+    let removed = async {
+        if !exists(&handle, index).await? {
+            return Ok(());
+        }
+        // Stop listening if the event socket fails, for instance after an
+        // overrun
+        while let Ok((buf, _)) = socket.recv_from_full().await {
+            if removed_in(&buf, index)? {
+                return Ok(());
+            }
+        }
+        while exists(&handle, index).await? {
+            sleep(POLL_INTERVAL).await;
+        }
+        Ok(())
+    };
+    timeout_after(Some(timeout), removed).await
+}
+
+// Whether `buf` holds the removal event of the link `index`
+fn removed_in(buf: &[u8], index: u32) -> Result<bool, Error> {
+    for message in split_messages(buf)? {
+        if message.message_type() != RTM_DELLINK {
+            continue;
+        }
+        let message =
+            NetlinkMessage::<RtnlMessage>::deserialize(message.into_inner())?;
+        if let NetlinkPayload::InnerMessage(RtnlMessage::DelLink(link)) =
+            message.payload
+        {
+            if link.header.index == index {
+                return Ok(true);
+            }
+        }
+    }
+    Ok(false)
+}
@@ -0,0 +1,362 @@
+// SPDX-License-Identifier: MIT
+
+use netlink_packet_core::{NLMSG_DONE, NLM_F_DUMP, NLM_F_REQUEST};
+use netlink_packet_route::{RTM_GETSTATS, RTM_NEWSTATS};
+use netlink_packet_utils::{
+    byteorder::{ByteOrder, NativeEndian},
+    nla::{NlaBuffer, NlasIterator},
+    DecodeError,
+};
+use netlink_sys::AsyncSocket;
+
+use crate::{
+    raw::{self, SEQUENCE_NUMBER},
+    Error, Handle,
+};
+
+const IF_STATS_MSG_LEN: usize = 12;
+const IFLA_STATS_LINK_XSTATS: u16 = 2;
+const IFLA_STATS_LINK_XSTATS_SLAVE: u16 = 3;
+const LINK_XSTATS_TYPE_BRIDGE: u16 = 1;
+const LINK_XSTATS_TYPE_BOND: u16 = 2;
+const BRIDGE_XSTATS_VLAN: u16 = 1;
+const BRIDGE_XSTATS_STP: u16 = 4;
+const BOND_XSTATS_3AD: u16 = 1;
+
+/// Extended statistics of a link, specific to its kind, see
+/// [`crate::LinkHandle::xstats`]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct LinkXstats {
+    pub index: u32,
+    /// 802.3ad counters of a bond, summed over its ports
+    pub bond_3ad: Option<Bond3adStats>,
+    /// 802.3ad counters of a bond port
+    pub port_bond_3ad: Option<Bond3adStats>,
+    /// Counters of the VLANs of a bridge or of a bridge port
+    pub bridge_vlans: Vec<BridgeVlanStats>,
+    /// STP counters of a bridge port
+    pub port_stp: Option<BridgeStpStats>,
+}
+
+/// 802.3ad counters (`BOND_3AD_STAT_*`)
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct Bond3adStats {
+    pub lacpdu_rx: u64,
+    pub lacpdu_tx: u64,
+    pub lacpdu_unknown_rx: u64,
+    pub lacpdu_illegal_rx: u64,
+    pub marker_rx: u64,
+    pub marker_tx: u64,
+    pub marker_resp_rx: u64,
+    pub marker_resp_tx: u64,
+    pub marker_unknown_rx: u64,
+}
+
+/// Counters of a bridge VLAN (`struct bridge_vlan_xstats`). They are only
+/// maintained when enabled with
+/// [`crate::BridgeSetRequest::vlan_stats_enabled`].
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct BridgeVlanStats {
+    pub vid: u16,
+    /// `BRIDGE_VLAN_INFO_*` flags of the VLAN
+    pub flags: u16,
+    pub rx_bytes: u64,
+    pub rx_packets: u64,
+    pub tx_bytes: u64,
+    pub tx_packets: u64,
+}
+
+/// STP counters of a bridge port (`struct bridge_stp_xstats`)
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct BridgeStpStats {
+    pub transition_blk: u64,
+    pub transition_fwd: u64,
+    pub rx_bpdu: u64,
+    pub tx_bpdu: u64,
+    pub rx_tcn: u64,
+    pub tx_tcn: u64,
+}
+
+/// A `RTM_GETSTATS` request for the `IFLA_STATS_LINK_XSTATS` and
+/// `IFLA_STATS_LINK_XSTATS_SLAVE` statistics (equivalent to `ip stats show
+/// group xstats`).
+///
+/// netlink-packet-route can't represent the stats messages, so the request
+/// is sent on a dedicated netlink socket, in the network namespace of the
+/// connection of the handle, and is subject to its timeout and in-flight
+/// limit. It fails with [`Error::InvalidRequest`] on a [`Handle::dry_run`],
+/// [`Handle::mock`] or replay handle.
+pub struct LinkXstatsRequest {
+    handle: Handle,
+    index: Option<u32>,
+}
+
+impl LinkXstatsRequest {
+    pub(crate) fn new(handle: Handle) -> Self {
+        LinkXstatsRequest {
+            handle,
+            index: None,
+        }
+    }
+
+    /// Only retrieve the statistics of the link `index`, instead of those of
+    /// all the links
+    pub fn match_index(mut self, index: u32) -> Self {
+        self.index = Some(index);
+        self
+    }
+
+    /// Execute the request
+    #[cfg(feature = "tokio_socket")]
+    pub async fn execute(self) -> Result<Vec<LinkXstats>, Error> {
+        self.execute_with_socket::<netlink_sys::TokioSocket>().await
+    }
+
+    /// Same as [`LinkXstatsRequest::execute`], using the socket type `S`
+    /// (for instance `SmolSocket`).
+    pub async fn execute_with_socket<S>(self) -> Result<Vec<LinkXstats>, Error>
+    where
+        S: AsyncSocket,
+    {
+        let mut stats = Vec::new();
+        raw::request::<S, _, _>(&self.handle, &self.to_bytes(), |message| {
+            match message.message_type() {
+                RTM_NEWSTATS => {
+                    stats.push(LinkXstats::parse(message.payload())?);
+                    // The stats of a single link are not followed by
+                    // NLMSG_DONE
+                    Ok(self.index.map(|_| ()))
+                }
+                NLMSG_DONE => raw::dump_result(&message).map(Some),
+                _ => Ok(None),
+            }
+        })
+        .await?;
+        Ok(stats)
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        // nlmsghdr and if_stats_msg
+        let mut buf = vec![0; 16 + IF_STATS_MSG_LEN];
+        let len = buf.len() as u32;
+        let flags = match self.index {
+            Some(_) => NLM_F_REQUEST,
+            None => NLM_F_REQUEST | NLM_F_DUMP,
+        };
+        NativeEndian::write_u32(&mut buf[0..4], len);
+        NativeEndian::write_u16(&mut buf[4..6], RTM_GETSTATS);
+        NativeEndian::write_u16(&mut buf[6..8], flags);
+        NativeEndian::write_u32(&mut buf[8..12], SEQUENCE_NUMBER);
+        NativeEndian::write_u32(&mut buf[20..24], self.index.unwrap_or(0));
+        let filter_mask = (1 << (IFLA_STATS_LINK_XSTATS - 1))
+            | (1 << (IFLA_STATS_LINK_XSTATS_SLAVE - 1));
+        NativeEndian::write_u32(&mut buf[24..28], filter_mask);
+        buf
+    }
+}
+
+impl LinkXstats {
+    /// Parse the payload of a `RTM_NEWSTATS` message
+    pub(crate) fn parse(payload: &[u8]) -> Result<Self, DecodeError> {
+        if payload.len() < IF_STATS_MSG_LEN {
+            return Err(DecodeError::from("invalid stats message"));
+        }
+        let mut stats = LinkXstats {
+            index: NativeEndian::read_u32(&payload[4..8]),
+            ..Default::default()
+        };
+        for nla in NlasIterator::new(&payload[IF_STATS_MSG_LEN..]) {
+            let nla = nla?;
+            let port = match nla.kind() {
+                IFLA_STATS_LINK_XSTATS => false,
+                IFLA_STATS_LINK_XSTATS_SLAVE => true,
+                _ => continue,
+            };
+            for nla in NlasIterator::new(nla.value()) {
+                let nla = nla?;
+                match nla.kind() {
+                    LINK_XSTATS_TYPE_BRIDGE => {
+                        stats.parse_bridge(nla.value())?
+                    }
+                    LINK_XSTATS_TYPE_BOND => {
+                        let bond_3ad = parse_bond(nla.value())?;
+                        if port {
+                            stats.port_bond_3ad = bond_3ad;
+                        } else {
+                            stats.bond_3ad = bond_3ad;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Ok(stats)
+    }
+
+    fn parse_bridge(&mut self, buf: &[u8]) -> Result<(), DecodeError> {
+        for nla in NlasIterator::new(buf) {
+            let nla = nla?;
+            match nla.kind() {
+                BRIDGE_XSTATS_VLAN => {
+                    let counter = counters(&nla, 40)?;
+                    let value = nla.value();
+                    self.bridge_vlans.push(BridgeVlanStats {
+                        rx_bytes: counter(0),
+                        rx_packets: counter(1),
+                        tx_bytes: counter(2),
+                        tx_packets: counter(3),
+                        vid: NativeEndian::read_u16(&value[32..34]),
+                        flags: NativeEndian::read_u16(&value[34..36]),
+                    });
+                }
+                BRIDGE_XSTATS_STP => {
+                    let counter = counters(&nla, 48)?;
+                    self.port_stp = Some(BridgeStpStats {
+                        transition_blk: counter(0),
+                        transition_fwd: counter(1),
+                        rx_bpdu: counter(2),
+                        tx_bpdu: counter(3),
+                        rx_tcn: counter(4),
+                        tx_tcn: counter(5),
+                    });
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+fn parse_bond(buf: &[u8]) -> Result<Option<Bond3adStats>, DecodeError> {
+    for nla in NlasIterator::new(buf) {
+        let nla = nla?;
+        if nla.kind() != BOND_XSTATS_3AD {
+            continue;
+        }
+        let mut stats = Bond3adStats::default();
+        for nla in NlasIterator::new(nla.value()) {
+            let nla = nla?;
+            let counter = match nla.kind() {
+                0 => &mut stats.lacpdu_rx,
+                1 => &mut stats.lacpdu_tx,
+                2 => &mut stats.lacpdu_unknown_rx,
+                3 => &mut stats.lacpdu_illegal_rx,
+                4 => &mut stats.marker_rx,
+                5 => &mut stats.marker_tx,
+                6 => &mut stats.marker_resp_rx,
+                7 => &mut stats.marker_resp_tx,
+                8 => &mut stats.marker_unknown_rx,
+                _ => continue,
+            };
+            *counter = counters(&nla, 8)?(0);
+        }
+        return Ok(Some(stats));
+    }
+    Ok(None)
+}
+
+// Read the value of `nla` as an array of u64, checking it is at least `len`
+// bytes long
+fn counters<'a>(
+    nla: &'a NlaBuffer<&'a [u8]>,
+    len: usize,
+) -> Result<impl Fn(usize) -> u64 + 'a, DecodeError> {
+    let value = nla.value();
+    if value.len() < len {
+        return Err(DecodeError::from(format!(
+            "invalid stats attribute {}",
+            nla.kind()
+        )));
+    }
+    Ok(move |i: usize| NativeEndian::read_u64(&value[i * 8..]))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn nla(kind: u16, value: &[u8]) -> Vec<u8> {
+        let mut buf = vec![0; 4];
+        NativeEndian::write_u16(&mut buf[0..2], 4 + value.len() as u16);
+        NativeEndian::write_u16(&mut buf[2..4], kind);
+        buf.extend_from_slice(value);
+        buf.resize((buf.len() + 3) & !3, 0);
+        buf
+    }
+
+    fn u64s(values: &[u64]) -> Vec<u8> {
+        values.iter().flat_map(|v| v.to_ne_bytes()).collect()
+    }
+
+    #[test]
+    fn test_parse_xstats() {
+        let mut vlan = u64s(&[1000, 10, 2000, 20]);
+        vlan.extend_from_slice(&[0; 8]);
+        NativeEndian::write_u16(&mut vlan[32..34], 100);
+        NativeEndian::write_u16(&mut vlan[34..36], 2);
+        let bridge = [
+            nla(BRIDGE_XSTATS_VLAN, &vlan),
+            nla(BRIDGE_XSTATS_STP, &u64s(&[1, 2, 3, 4, 5, 6])),
+        ]
+        .concat();
+        let bond = nla(
+            BOND_XSTATS_3AD,
+            &[nla(0, &u64s(&[7])), nla(1, &u64s(&[8]))].concat(),
+        );
+
+        let mut payload = vec![0; IF_STATS_MSG_LEN];
+        NativeEndian::write_u32(&mut payload[4..8], 3);
+        payload.extend(nla(
+            IFLA_STATS_LINK_XSTATS_SLAVE,
+            &[
+                nla(LINK_XSTATS_TYPE_BRIDGE, &bridge),
+                nla(LINK_XSTATS_TYPE_BOND, &bond),
+            ]
+            .concat(),
+        ));
+        assert_eq!(
+            LinkXstats::parse(&payload).unwrap(),
+            LinkXstats {
+                index: 3,
+                bond_3ad: None,
+                port_bond_3ad: Some(Bond3adStats {
+                    lacpdu_rx: 7,
+                    lacpdu_tx: 8,
+                    ..Default::default()
+                }),
+                bridge_vlans: vec![BridgeVlanStats {
+                    vid: 100,
+                    flags: 2,
+                    rx_bytes: 1000,
+                    rx_packets: 10,
+                    tx_bytes: 2000,
+                    tx_packets: 20,
+                }],
+                port_stp: Some(BridgeStpStats {
+                    transition_blk: 1,
+                    transition_fwd: 2,
+                    rx_bpdu: 3,
+                    tx_bpdu: 4,
+                    rx_tcn: 5,
+                    tx_tcn: 6,
+                }),
+            }
+        );
+
+        let truncated = nla(
+            IFLA_STATS_LINK_XSTATS,
+            &nla(
+                LINK_XSTATS_TYPE_BRIDGE,
+                &nla(BRIDGE_XSTATS_STP, &u64s(&[1])),
+            ),
+        );
+        payload.truncate(IF_STATS_MSG_LEN);
+        payload.extend(truncated);
+        assert!(LinkXstats::parse(&payload).is_err());
+        assert!(LinkXstats::parse(&payload[..4]).is_err());
+    }
+}
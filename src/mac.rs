@@ -0,0 +1,86 @@
+// SPDX-License-Identifier: MIT
+
+use std::{fmt, str::FromStr};
+
+use crate::Error;
+
+/// An Ethernet (EUI-48) hardware address, which can be parsed from the
+/// usual `aa:bb:cc:dd:ee:ff` notation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct MacAddress(pub [u8; 6]);
+
+impl MacAddress {
+    pub fn octets(&self) -> [u8; 6] {
+        self.0
+    }
+
+    pub fn is_multicast(&self) -> bool {
+        self.0[0] & 0x01 != 0
+    }
+}
+
+impl From<[u8; 6]> for MacAddress {
+    fn from(octets: [u8; 6]) -> Self {
+        MacAddress(octets)
+    }
+}
+
+impl From<MacAddress> for Vec<u8> {
+    fn from(address: MacAddress) -> Self {
+        address.0.to_vec()
+    }
+}
+
+impl FromStr for MacAddress {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::InvalidMacAddress(s.to_string());
+        let mut octets = [0; 6];
+        let mut parts = s.split(':');
+        for octet in octets.iter_mut() {
+            let part = parts.next().ok_or_else(invalid)?;
+            if part.len() != 2 {
+                return Err(invalid());
+            }
+            *octet = u8::from_str_radix(part, 16).map_err(|_| invalid())?;
+        }
+        if parts.next().is_some() {
+            return Err(invalid());
+        }
+        Ok(MacAddress(octets))
+    }
+}
+
+impl fmt::Display for MacAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [a, b, c, d, e, g] = self.0;
+        write!(
+            f,
+            "{:02x}:{:02x}:{:02x}:{:02x}:{:02x}:{:02x}",
+            a, b, c, d, e, g
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_mac_address() {
+        let mac: MacAddress = "00:1A:2b:3c:4d:5e".parse().unwrap();
+        assert_eq!(mac, MacAddress([0x00, 0x1a, 0x2b, 0x3c, 0x4d, 0x5e]));
+        assert_eq!(mac.to_string(), "00:1a:2b:3c:4d:5e");
+
+        for invalid in [
+            "",
+            "00:1a:2b:3c:4d",
+            "00:1a:2b:3c:4d:5e:6f",
+            "0:1a:2b:3c:4d:5e",
+            "zz:1a:2b:3c:4d:5e",
+        ] {
+            assert!(invalid.parse::<MacAddress>().is_err(), "{}", invalid);
+        }
+    }
+}
@@ -10,7 +10,9 @@ macro_rules! try_rtnl {
         let (header, payload) = $msg.into_parts();
         match payload {
             NetlinkPayload::InnerMessage($message_type(msg)) => msg,
-            NetlinkPayload::Error(err) => return Err(Error::NetlinkError(err)),
+            NetlinkPayload::Error(err) => {
+                return Err(Error::netlink(header.flags, err))
+            }
             _ => {
                 return Err(Error::UnexpectedMessage(NetlinkMessage::new(
                     header, payload,
@@ -25,8 +27,9 @@ macro_rules! try_nl {
     ($msg: expr) => {{
         use netlink_packet_core::NetlinkPayload;
         use $crate::Error;
-        if let NetlinkPayload::Error(err) = $msg.payload {
-            return Err(Error::NetlinkError(err));
+        let msg = $msg;
+        if let NetlinkPayload::Error(err) = msg.payload {
+            return Err(Error::netlink(msg.header.flags, err));
         }
     }};
 }
@@ -0,0 +1,570 @@
+// SPDX-License-Identifier: MIT
+
+use std::{
+    mem::discriminant,
+    sync::{Arc, Mutex},
+};
+
+use netlink_packet_core::{
+    NetlinkMessage, NetlinkPayload, NLM_F_CREATE, NLM_F_DUMP, NLM_F_EXCL,
+    NLM_F_REPLACE,
+};
+use netlink_packet_route::{
+    address,
+    link::nlas::{Nla as LinkNla, State},
+    neighbour, route, AddressMessage, LinkMessage, NeighbourMessage,
+    RouteMessage, RtnlMessage, RuleMessage, ARPHRD_ETHER, ARPHRD_LOOPBACK,
+    IFF_LOOPBACK, IFF_UP,
+};
+use nix::libc;
+
+use crate::{
+    handle::error_message, Address, Handle, Link, Neighbour, Route, Rule,
+};
+
+/// An in-memory network (links, addresses, routes, rules and neighbours)
+/// that answers the requests of a handle returned by [`MockNetwork::handle`]
+/// (or [`crate::Handle::mock`]) the way the kernel would, so that the code
+/// using this crate can be tested without privileges. It starts with a
+/// single loopback link `lo`.
+///
+/// The mock only implements the semantics that matter to most programs:
+/// - links are created with `NLM_F_CREATE`, and their attributes are
+///   replaced by the ones of the requests. Deleting a link deletes its
+///   addresses, routes and neighbours.
+/// - adding an address, route or rule that already exists fails with
+///   `EEXIST`, unless `NLM_F_REPLACE` is set (for addresses and routes).
+/// - deleting an object that does not exist fails with the error of the
+///   kernel (`ENODEV`, `EADDRNOTAVAIL`, `ESRCH` or `ENOENT`).
+///
+/// Other requests (traffic control for instance) fail with `EOPNOTSUPP`.
+///
+/// ```no_run
+/// use rtnetlink::MockNetwork;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), rtnetlink::Error> {
+///     let network = MockNetwork::new();
+///     let handle = network.handle();
+///
+///     handle.link().add().bridge("br0".into()).execute().await?;
+///     assert_eq!(network.links().len(), 2);
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct MockNetwork(Arc<Mutex<MockState>>);
+
+#[derive(Debug)]
+struct MockState {
+    links: Vec<LinkMessage>,
+    addresses: Vec<AddressMessage>,
+    routes: Vec<RouteMessage>,
+    rules: Vec<RuleMessage>,
+    neighbours: Vec<NeighbourMessage>,
+    next_index: u32,
+}
+
+impl Default for MockNetwork {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockNetwork {
+    pub fn new() -> Self {
+        let mut lo = LinkMessage::default();
+        lo.header.index = 1;
+        lo.header.link_layer_type = ARPHRD_LOOPBACK;
+        lo.header.flags = IFF_UP | IFF_LOOPBACK;
+        lo.nlas.push(LinkNla::IfName("lo".into()));
+        lo.nlas.push(LinkNla::Mtu(65536));
+        lo.nlas.push(LinkNla::OperState(State::Unknown));
+        MockNetwork(Arc::new(Mutex::new(MockState {
+            links: vec![lo],
+            addresses: vec![],
+            routes: vec![],
+            rules: vec![],
+            neighbours: vec![],
+            next_index: 2,
+        })))
+    }
+
+    /// Return a handle sending its requests to this network. Unlike
+    /// [`crate::Handle::mock`], it does not need a netlink socket.
+    pub fn handle(&self) -> Handle {
+        Handle::new_mock(self.clone())
+    }
+
+    pub fn links(&self) -> Vec<Link> {
+        self.0
+            .lock()
+            .unwrap()
+            .links
+            .iter()
+            .map(Link::from)
+            .collect()
+    }
+
+    pub fn addresses(&self) -> Vec<Address> {
+        let state = self.0.lock().unwrap();
+        state.addresses.iter().map(Address::from).collect()
+    }
+
+    pub fn routes(&self) -> Vec<Route> {
+        self.0
+            .lock()
+            .unwrap()
+            .routes
+            .iter()
+            .map(Route::from)
+            .collect()
+    }
+
+    pub fn rules(&self) -> Vec<Rule> {
+        self.0
+            .lock()
+            .unwrap()
+            .rules
+            .iter()
+            .map(Rule::from)
+            .collect()
+    }
+
+    pub fn neighbours(&self) -> Vec<Neighbour> {
+        let state = self.0.lock().unwrap();
+        state.neighbours.iter().map(Neighbour::from).collect()
+    }
+
+    pub(crate) fn respond(
+        &self,
+        request: &NetlinkMessage<RtnlMessage>,
+    ) -> Vec<NetlinkMessage<RtnlMessage>> {
+        let flags = request.header.flags;
+        let mut state = self.0.lock().unwrap();
+        let result = match &request.payload {
+            NetlinkPayload::InnerMessage(message) => {
+                state.handle(message, flags)
+            }
+            _ => Err(libc::EOPNOTSUPP),
+        };
+        let mut responses = match result {
+            Ok(messages) => {
+                messages.into_iter().map(NetlinkMessage::from).collect()
+            }
+            Err(errno) => {
+                // Like the kernel, echo the request back in the error
+                let mut request = request.clone();
+                request.finalize();
+                let mut header = vec![0; request.buffer_len()];
+                request.serialize(&mut header);
+                vec![error_message(-errno, header)]
+            }
+        };
+        responses.iter_mut().for_each(NetlinkMessage::finalize);
+        responses
+    }
+}
+
+impl MockState {
+    fn handle(
+        &mut self,
+        message: &RtnlMessage,
+        flags: u16,
+    ) -> Result<Vec<RtnlMessage>, i32> {
+        use RtnlMessage::*;
+
+        let dump = flags & NLM_F_DUMP == NLM_F_DUMP;
+        match message {
+            NewLink(link) => self.set_link(link, flags)?,
+            // RTM_SETLINK never creates links, and ignores NLM_F_EXCL
+            SetLink(link) => self.set_link(link, 0)?,
+            DelLink(link) => self.del_link(link)?,
+            GetLink(link) if dump => {
+                return Ok(self.links.iter().cloned().map(NewLink).collect())
+            }
+            GetLink(link) => {
+                let index = self.find_link(link)?.ok_or(libc::ENODEV)?;
+                return Ok(vec![NewLink(self.links[index].clone())]);
+            }
+            NewAddress(address) => self.new_address(address, flags)?,
+            DelAddress(address) => {
+                let index = position(&self.addresses, address, same_address)
+                    .ok_or(libc::EADDRNOTAVAIL)?;
+                self.addresses.remove(index);
+            }
+            GetAddress(address) => {
+                let family = address.header.family;
+                return Ok(filter(
+                    &self.addresses,
+                    |a| a.header.family,
+                    family,
+                )
+                .map(NewAddress)
+                .collect());
+            }
+            NewRoute(route) => self.new_route(route, flags)?,
+            DelRoute(route) => {
+                let index = position(&self.routes, route, same_route)
+                    .ok_or(libc::ESRCH)?;
+                self.routes.remove(index);
+            }
+            GetRoute(route) => {
+                let family = route.header.address_family;
+                return Ok(filter(
+                    &self.routes,
+                    |r| r.header.address_family,
+                    family,
+                )
+                .map(NewRoute)
+                .collect());
+            }
+            NewRule(rule) => {
+                if self.rules.contains(rule) {
+                    return Err(libc::EEXIST);
+                }
+                self.rules.push(rule.clone());
+            }
+            DelRule(rule) => {
+                let index = position(&self.rules, rule, PartialEq::eq)
+                    .ok_or(libc::ENOENT)?;
+                self.rules.remove(index);
+            }
+            GetRule(rule) => {
+                let family = rule.header.family;
+                return Ok(filter(&self.rules, |r| r.header.family, family)
+                    .map(NewRule)
+                    .collect());
+            }
+            NewNeighbour(neighbour) => self.new_neighbour(neighbour, flags)?,
+            DelNeighbour(neighbour) => {
+                let index =
+                    position(&self.neighbours, neighbour, same_neighbour)
+                        .ok_or(libc::ENOENT)?;
+                self.neighbours.remove(index);
+            }
+            GetNeighbour(neighbour) => {
+                let family = neighbour.header.family;
+                return Ok(filter(
+                    &self.neighbours,
+                    |n| n.header.family,
+                    family,
+                )
+                .map(NewNeighbour)
+                .collect());
+            }
+            _ => return Err(libc::EOPNOTSUPP),
+        }
+        Ok(vec![])
+    }
+
+    fn link_exists(&self, index: u32) -> bool {
+        self.links.iter().any(|link| link.header.index == index)
+    }
+
+    /// Position of the link designated by the index or the name of
+    /// `message`, `ENODEV` if the index does not exist.
+    fn find_link(&self, message: &LinkMessage) -> Result<Option<usize>, i32> {
+        if message.header.index != 0 {
+            return self
+                .links
+                .iter()
+                .position(|link| link.header.index == message.header.index)
+                .map(Some)
+                .ok_or(libc::ENODEV);
+        }
+        Ok(link_name(message).and_then(|name| {
+            self.links
+                .iter()
+                .position(|link| link_name(link) == Some(name))
+        }))
+    }
+
+    fn set_link(
+        &mut self,
+        message: &LinkMessage,
+        flags: u16,
+    ) -> Result<(), i32> {
+        for nla in &message.nlas {
+            if let LinkNla::Master(index) | LinkNla::Link(index) = nla {
+                if *index != 0 && !self.link_exists(*index) {
+                    return Err(libc::ENODEV);
+                }
+            }
+        }
+        let position = match self.find_link(message)? {
+            Some(_) if flags & NLM_F_EXCL != 0 => return Err(libc::EEXIST),
+            Some(position) => position,
+            None if flags & NLM_F_CREATE == 0 => return Err(libc::ENODEV),
+            None if link_name(message).is_none() => return Err(libc::EINVAL),
+            None => {
+                let mut link = LinkMessage::default();
+                link.header.index = self.next_index;
+                link.header.link_layer_type = ARPHRD_ETHER;
+                link.nlas.push(LinkNla::Mtu(1500));
+                self.next_index += 1;
+                self.links.push(link);
+                self.links.len() - 1
+            }
+        };
+
+        let link = &mut self.links[position];
+        let header = &message.header;
+        if header.change_mask != 0 {
+            link.header.flags = (link.header.flags & !header.change_mask)
+                | (header.flags & header.change_mask);
+        } else if header.flags != 0 {
+            link.header.flags = header.flags;
+        }
+        for nla in &message.nlas {
+            link.nlas.retain(|n| discriminant(n) != discriminant(nla));
+            if *nla != LinkNla::Master(0) {
+                link.nlas.push(nla.clone());
+            }
+        }
+        if link.header.flags & IFF_LOOPBACK == 0 {
+            let state = if link.header.flags & IFF_UP != 0 {
+                State::Up
+            } else {
+                State::Down
+            };
+            link.nlas.retain(|n| !matches!(n, LinkNla::OperState(_)));
+            link.nlas.push(LinkNla::OperState(state));
+        }
+        Ok(())
+    }
+
+    fn del_link(&mut self, message: &LinkMessage) -> Result<(), i32> {
+        let position = self.find_link(message)?.ok_or(libc::ENODEV)?;
+        let index = self.links.remove(position).header.index;
+        for link in &mut self.links {
+            link.nlas.retain(|nla| *nla != LinkNla::Master(index));
+        }
+        self.addresses.retain(|a| a.header.index != index);
+        self.routes
+            .retain(|r| !r.nlas.contains(&route::Nla::Oif(index)));
+        self.neighbours.retain(|n| n.header.ifindex != index);
+        Ok(())
+    }
+
+    fn new_address(
+        &mut self,
+        message: &AddressMessage,
+        flags: u16,
+    ) -> Result<(), i32> {
+        if !self.link_exists(message.header.index) {
+            return Err(libc::ENODEV);
+        }
+        match position(&self.addresses, message, same_address) {
+            Some(index) if flags & NLM_F_REPLACE != 0 => {
+                self.addresses[index] = message.clone()
+            }
+            Some(_) => return Err(libc::EEXIST),
+            None => self.addresses.push(message.clone()),
+        }
+        Ok(())
+    }
+
+    fn new_route(
+        &mut self,
+        message: &RouteMessage,
+        flags: u16,
+    ) -> Result<(), i32> {
+        for nla in &message.nlas {
+            if let route::Nla::Oif(index) = nla {
+                if !self.link_exists(*index) {
+                    return Err(libc::ENODEV);
+                }
+            }
+        }
+        match position(&self.routes, message, same_route) {
+            Some(index) if flags & NLM_F_REPLACE != 0 => {
+                self.routes[index] = message.clone()
+            }
+            Some(_) => return Err(libc::EEXIST),
+            None => self.routes.push(message.clone()),
+        }
+        Ok(())
+    }
+
+    fn new_neighbour(
+        &mut self,
+        message: &NeighbourMessage,
+        flags: u16,
+    ) -> Result<(), i32> {
+        if !self.link_exists(message.header.ifindex) {
+            return Err(libc::ENODEV);
+        }
+        match position(&self.neighbours, message, same_neighbour) {
+            Some(_) if flags & NLM_F_EXCL != 0 => return Err(libc::EEXIST),
+            Some(index) => self.neighbours[index] = message.clone(),
+            None if flags & NLM_F_CREATE == 0 => return Err(libc::ENOENT),
+            None => self.neighbours.push(message.clone()),
+        }
+        Ok(())
+    }
+}
+
+fn position<T>(
+    objects: &[T],
+    message: &T,
+    same: impl Fn(&T, &T) -> bool,
+) -> Option<usize> {
+    objects.iter().position(|object| same(object, message))
+}
+
+/// The objects of the `family` address family, or all of them if `family`
+/// is `AF_UNSPEC`
+fn filter<'a, T: Clone>(
+    objects: &'a [T],
+    object_family: impl Fn(&T) -> u8 + 'a,
+    family: u8,
+) -> impl Iterator<Item = T> + 'a {
+    objects
+        .iter()
+        .filter(move |object| family == 0 || object_family(object) == family)
+        .cloned()
+}
+
+fn link_name(message: &LinkMessage) -> Option<&str> {
+    message.nlas.iter().find_map(|nla| match nla {
+        LinkNla::IfName(name) => Some(name.as_str()),
+        _ => None,
+    })
+}
+
+fn same_address(a: &AddressMessage, b: &AddressMessage) -> bool {
+    fn local(message: &AddressMessage) -> Option<&Vec<u8>> {
+        let mut address = None;
+        for nla in &message.nlas {
+            match nla {
+                address::Nla::Local(bytes) => return Some(bytes),
+                address::Nla::Address(bytes) => address = Some(bytes),
+                _ => {}
+            }
+        }
+        address
+    }
+    a.header.index == b.header.index
+        && a.header.family == b.header.family
+        && a.header.prefix_len == b.header.prefix_len
+        && local(a) == local(b)
+}
+
+fn same_route(a: &RouteMessage, b: &RouteMessage) -> bool {
+    fn key(message: &RouteMessage) -> (Option<&Vec<u8>>, u32, u32) {
+        let mut key = (None, message.header.table as u32, 0);
+        for nla in &message.nlas {
+            match nla {
+                route::Nla::Destination(bytes) => key.0 = Some(bytes),
+                route::Nla::Table(table) => key.1 = *table,
+                route::Nla::Priority(priority) => key.2 = *priority,
+                _ => {}
+            }
+        }
+        key
+    }
+    a.header.address_family == b.header.address_family
+        && a.header.destination_prefix_length
+            == b.header.destination_prefix_length
+        && key(a) == key(b)
+}
+
+fn same_neighbour(a: &NeighbourMessage, b: &NeighbourMessage) -> bool {
+    fn destination(message: &NeighbourMessage) -> Option<&Vec<u8>> {
+        message.nlas.iter().find_map(|nla| match nla {
+            neighbour::Nla::Destination(bytes) => Some(bytes),
+            _ => None,
+        })
+    }
+    a.header.ifindex == b.header.ifindex
+        && a.header.family == b.header.family
+        && destination(a) == destination(b)
+}
+
+#[cfg(test)]
+mod test {
+    use std::net::{IpAddr, Ipv4Addr};
+
+    use futures::stream::TryStreamExt;
+    use tokio::runtime::Runtime;
+
+    use super::*;
+
+    #[test]
+    fn test_mock_network() {
+        let rt = Runtime::new().unwrap();
+        rt.block_on(async {
+            let network = MockNetwork::new();
+            let handle = network.handle();
+
+            handle
+                .link()
+                .add()
+                .bridge("br0".into())
+                .execute()
+                .await
+                .unwrap();
+            let err = handle
+                .link()
+                .add()
+                .bridge("br0".into())
+                .execute()
+                .await
+                .unwrap_err();
+            assert!(err.is_already_exists());
+
+            let link = handle
+                .link()
+                .get()
+                .match_name("br0".into())
+                .execute()
+                .try_next()
+                .await
+                .unwrap()
+                .unwrap();
+            let index = link.header.index;
+            handle.link().set(index).up().execute().await.unwrap();
+            handle
+                .address()
+                .add(index, Ipv4Addr::new(192, 0, 2, 1).into(), 24)
+                .execute()
+                .await
+                .unwrap();
+            handle
+                .route()
+                .add()
+                .v4()
+                .destination_prefix(Ipv4Addr::new(198, 51, 100, 0), 24)
+                .gateway(Ipv4Addr::new(192, 0, 2, 254))
+                .output_interface(index)
+                .execute()
+                .await
+                .unwrap();
+
+            let br0 = &network.links()[1];
+            assert_eq!((br0.index, br0.name.as_str()), (index, "br0"));
+            assert_eq!(br0.oper_state, State::Up);
+            let addresses: Vec<_> = handle
+                .address()
+                .get()
+                .execute()
+                .try_collect()
+                .await
+                .unwrap();
+            assert_eq!(addresses.len(), 1);
+            assert_eq!(
+                Address::from(&addresses[0]).address,
+                Some(IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1)))
+            );
+            assert_eq!(network.routes().len(), 1);
+
+            handle.link().del(index).execute().await.unwrap();
+            assert_eq!(network.links().len(), 1);
+            assert!(network.addresses().is_empty());
+            assert!(network.routes().is_empty());
+            let err = handle.link().del(index).execute().await.unwrap_err();
+            assert!(err.is_not_found());
+        });
+    }
+}
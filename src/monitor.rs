@@ -0,0 +1,137 @@
+// SPDX-License-Identifier: MIT
+
+use std::io;
+#[cfg(feature = "tokio_socket")]
+use std::time::Duration;
+
+use futures::channel::mpsc::UnboundedReceiver;
+#[cfg(feature = "tokio_socket")]
+use futures::{stream, Stream, StreamExt};
+use netlink_packet_core::NetlinkMessage;
+use netlink_packet_route::RtnlMessage;
+use netlink_proto::Connection;
+#[cfg(feature = "tokio_socket")]
+use netlink_sys::TokioSocket;
+use netlink_sys::{AsyncSocket, SocketAddr};
+#[cfg(feature = "tokio_socket")]
+use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
+
+#[cfg(feature = "tokio_socket")]
+use crate::handle::sleep;
+use crate::new_connection_with_socket;
+
+#[cfg(feature = "tokio_socket")]
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// Create a new netlink socket subscribed to the multicast `groups` (a
+/// bitmask of the `RTMGRP_*` constants). Unlike binding the socket of a
+/// [`crate::Handle`] connection, the events do not share the socket with the
+/// requests: an overrun of the event socket can't disturb the requests. The
+/// returned connection must be spawned, and the events are read from the
+/// returned receiver.
+#[cfg(feature = "tokio_socket")]
+#[allow(clippy::type_complexity)]
+pub fn new_monitor(
+    groups: u32,
+) -> io::Result<(
+    Connection<RtnlMessage>,
+    UnboundedReceiver<(NetlinkMessage<RtnlMessage>, SocketAddr)>,
+)> {
+    new_monitor_with_socket(groups)
+}
+
+/// Same as [`new_monitor`], using the socket type `S`.
+#[allow(clippy::type_complexity)]
+pub fn new_monitor_with_socket<S>(
+    groups: u32,
+) -> io::Result<(
+    Connection<RtnlMessage, S>,
+    UnboundedReceiver<(NetlinkMessage<RtnlMessage>, SocketAddr)>,
+)>
+where
+    S: AsyncSocket,
+{
+    let (mut conn, handle, messages) = new_connection_with_socket::<S>()?;
+    // The socket is already bound, it must be bound to the same port id
+    let port_id = handle.port_id().unwrap_or(0);
+    conn.socket_mut()
+        .socket_mut()
+        .bind(&SocketAddr::new(port_id, groups))?;
+    Ok((conn, messages))
+}
+
+#[cfg(feature = "tokio_socket")]
+/// An item of the stream returned by [`new_supervised_monitor`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MonitorEvent {
+    /// A multicast message sent by the kernel
+    Message(NetlinkMessage<RtnlMessage>),
+    /// The netlink socket was closed (for instance after the kernel failed
+    /// to deliver events because the socket buffer was full) and has been
+    /// re-created. Events may have been lost in between: consumers should
+    /// dump the objects they track again.
+    Reconnected,
+}
+
+#[cfg(feature = "tokio_socket")]
+/// Listen to the rtnetlink multicast `groups` (a bitmask of the
+/// `RTMGRP_*` constants), re-creating the netlink socket whenever it gets
+/// closed. This is a more robust version of binding the socket of
+/// [`crate::new_connection`] to the groups and reading the messages it
+/// returns.
+///
+/// The socket is driven by a task spawned on the tokio runtime, which stops
+/// as soon as the returned stream is dropped.
+///
+/// # Panics
+///
+/// This function panics if it is not called from within a tokio runtime.
+pub fn new_supervised_monitor(groups: u32) -> impl Stream<Item = MonitorEvent> {
+    let (tx, mut rx) = unbounded_channel();
+    tokio::spawn(supervise(groups, tx));
+    stream::poll_fn(move |cx| rx.poll_recv(cx))
+}
+
+#[cfg(feature = "tokio_socket")]
+async fn supervise(groups: u32, tx: UnboundedSender<MonitorEvent>) {
+    let mut reconnect = false;
+    loop {
+        match new_monitor_with_socket::<TokioSocket>(groups) {
+            Ok((conn, mut messages)) => {
+                if reconnect && tx.send(MonitorEvent::Reconnected).is_err() {
+                    return;
+                }
+                let conn = tokio::spawn(conn);
+                loop {
+                    tokio::select! {
+                        message = messages.next() => match message {
+                            Some((message, _)) => {
+                                if tx
+                                    .send(MonitorEvent::Message(message))
+                                    .is_err()
+                                {
+                                    conn.abort();
+                                    return;
+                                }
+                            }
+                            None => break,
+                        },
+                        _ = tx.closed() => {
+                            conn.abort();
+                            return;
+                        }
+                    }
+                }
+                log::warn!("netlink monitor socket closed, reconnecting");
+            }
+            Err(e) => {
+                log::warn!("failed to create netlink monitor socket: {}", e)
+            }
+        }
+        reconnect = true;
+        tokio::select! {
+            _ = sleep(RECONNECT_DELAY) => (),
+            _ = tx.closed() => return,
+        }
+    }
+}
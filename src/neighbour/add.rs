@@ -12,7 +12,7 @@ use netlink_packet_route::{
     RtnlMessage,
 };
 
-use crate::{Error, Handle};
+use crate::{Error, Handle, MacAddress};
 use std::net::IpAddr;
 
 pub struct NeighbourAddRequest {
@@ -84,6 +84,12 @@ impl NeighbourAddRequest {
         self
     }
 
+    /// Set the Ethernet address of the neighbor (see `NDA_LLADDR` for
+    /// details).
+    pub fn mac_address(self, addr: MacAddress) -> Self {
+        self.link_local_address(&addr.octets())
+    }
+
     /// Set a neighbor cache link layer address (see `NDA_LLADDR` for details).
     pub fn link_local_address(mut self, addr: &[u8]) -> Self {
         let lla = self.message.nlas.iter_mut().find_map(|nla| match nla {
@@ -142,10 +148,11 @@ impl NeighbourAddRequest {
         let replace = if replace { NLM_F_REPLACE } else { NLM_F_EXCL };
         req.header.flags = NLM_F_REQUEST | NLM_F_ACK | replace | NLM_F_CREATE;
 
-        let mut response = handle.request(req)?;
+        let mut response = handle.try_request(req)?;
         while let Some(message) = response.next().await {
+            let message = message?;
             if let NetlinkPayload::Error(err) = message.payload {
-                return Err(Error::NetlinkError(err));
+                return Err(Error::netlink(message.header.flags, err));
             }
         }
 
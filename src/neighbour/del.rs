@@ -27,10 +27,11 @@ impl NeighbourDelRequest {
 
         let mut req = NetlinkMessage::from(RtnlMessage::DelNeighbour(message));
         req.header.flags = NLM_F_REQUEST | NLM_F_ACK;
-        let mut response = handle.request(req)?;
+        let mut response = handle.try_request(req)?;
         while let Some(msg) = response.next().await {
+            let msg = msg?;
             if let NetlinkPayload::Error(e) = msg.payload {
-                return Err(Error::NetlinkError(e));
+                return Err(Error::netlink(msg.header.flags, e));
             }
         }
         Ok(())
@@ -12,7 +12,7 @@ use netlink_packet_route::{
     neighbour::NeighbourMessage, RtnlMessage, NTF_PROXY,
 };
 
-use crate::{Error, Handle, IpVersion};
+use crate::{collect::collect, CollectOptions, Error, Handle, IpVersion};
 
 pub struct NeighbourGetRequest {
     handle: Handle,
@@ -37,6 +37,15 @@ impl NeighbourGetRequest {
         self
     }
 
+    /// Execute the request, gathering the results into a `Vec` according
+    /// to `options`, for dumps small enough not to need a stream.
+    pub async fn execute_collect(
+        self,
+        options: CollectOptions,
+    ) -> Result<Vec<NeighbourMessage>, Error> {
+        collect(self.execute(), options).await
+    }
+
     /// Execute the request
     pub fn execute(
         self,
@@ -49,14 +58,16 @@ impl NeighbourGetRequest {
         let mut req = NetlinkMessage::from(RtnlMessage::GetNeighbour(message));
         req.header.flags = NLM_F_REQUEST | NLM_F_DUMP;
 
-        match handle.request(req) {
+        match handle.try_request(req) {
             Ok(response) => Either::Left(response.map(move |msg| {
-                let (header, payload) = msg.into_parts();
+                let (header, payload) = msg?.into_parts();
                 match payload {
                     NetlinkPayload::InnerMessage(
                         RtnlMessage::NewNeighbour(msg),
                     ) => Ok(msg),
-                    NetlinkPayload::Error(err) => Err(Error::NetlinkError(err)),
+                    NetlinkPayload::Error(err) => {
+                        Err(Error::netlink(header.flags, err))
+                    }
                     _ => Err(Error::UnexpectedMessage(NetlinkMessage::new(
                         header, payload,
                     ))),
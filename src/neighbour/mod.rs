@@ -11,3 +11,6 @@ pub use self::add::*;
 
 mod del;
 pub use self::del::*;
+
+mod view;
+pub use self::view::*;
@@ -0,0 +1,53 @@
+// SPDX-License-Identifier: MIT
+
+use std::net::IpAddr;
+
+use netlink_packet_route::{neighbour::Nla, NeighbourMessage};
+
+use crate::parse_ip_addr;
+
+/// The most commonly used properties of a neighbour entry, extracted from a
+/// [`NeighbourMessage`] (for instance one returned by
+/// [`crate::NeighbourGetRequest::execute`]).
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Neighbour {
+    /// Index of the link the entry belongs to
+    pub index: u32,
+    pub destination: Option<IpAddr>,
+    pub link_local_address: Option<Vec<u8>>,
+    /// `NUD_*` state of the entry
+    pub state: u16,
+    /// `NTF_*` flags of the entry
+    pub flags: u8,
+}
+
+impl From<&NeighbourMessage> for Neighbour {
+    fn from(message: &NeighbourMessage) -> Self {
+        let mut neighbour = Neighbour {
+            index: message.header.ifindex,
+            destination: None,
+            link_local_address: None,
+            state: message.header.state,
+            flags: message.header.flags,
+        };
+        for nla in &message.nlas {
+            match nla {
+                Nla::Destination(bytes) => {
+                    neighbour.destination = parse_ip_addr(bytes)
+                }
+                Nla::LinkLocalAddress(bytes) => {
+                    neighbour.link_local_address = Some(bytes.clone())
+                }
+                _ => (),
+            }
+        }
+        neighbour
+    }
+}
+
+impl From<NeighbourMessage> for Neighbour {
+    fn from(message: NeighbourMessage) -> Self {
+        Neighbour::from(&message)
+    }
+}
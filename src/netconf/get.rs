@@ -0,0 +1,76 @@
+// SPDX-License-Identifier: MIT
+
+use netlink_packet_core::NLM_F_REQUEST;
+use netlink_packet_route::{RTM_GETNETCONF, RTM_NEWNETCONF};
+use netlink_packet_utils::byteorder::{ByteOrder, NativeEndian};
+use netlink_sys::AsyncSocket;
+
+use crate::{
+    raw::{self, SEQUENCE_NUMBER},
+    Error, Handle, IpVersion, Netconf,
+};
+
+const NETCONFA_IFINDEX: u16 = 1;
+
+/// A `RTM_GETNETCONF` request.
+///
+/// netlink-packet-route can't represent the netconf messages, so the request
+/// is sent on a dedicated netlink socket, in the network namespace of the
+/// connection of the handle, and is subject to its timeout and in-flight
+/// limit. It fails with [`Error::InvalidRequest`] on a [`Handle::dry_run`],
+/// [`Handle::mock`] or replay handle.
+pub struct NetconfGetRequest {
+    handle: Handle,
+    family: u8,
+    index: i32,
+}
+
+impl NetconfGetRequest {
+    pub(crate) fn new(
+        handle: Handle,
+        ip_version: IpVersion,
+        index: i32,
+    ) -> Self {
+        NetconfGetRequest {
+            handle,
+            family: ip_version.family(),
+            index,
+        }
+    }
+
+    /// Execute the request
+    #[cfg(feature = "tokio_socket")]
+    pub async fn execute(self) -> Result<Netconf, Error> {
+        self.execute_with_socket::<netlink_sys::TokioSocket>().await
+    }
+
+    /// Same as [`NetconfGetRequest::execute`], using the socket type `S`
+    /// (for instance `SmolSocket`).
+    pub async fn execute_with_socket<S>(self) -> Result<Netconf, Error>
+    where
+        S: AsyncSocket,
+    {
+        raw::request::<S, _, _>(&self.handle, &self.to_bytes(), |message| {
+            match message.message_type() {
+                RTM_NEWNETCONF => Ok(Some(Netconf::parse(message.payload())?)),
+                _ => Ok(None),
+            }
+        })
+        .await
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        // nlmsghdr, netconfmsg (padded to 4 bytes) and NETCONFA_IFINDEX
+        let mut buf = vec![0; 16 + 4 + 8];
+        let len = buf.len() as u32;
+        NativeEndian::write_u32(&mut buf[0..4], len);
+        NativeEndian::write_u16(&mut buf[4..6], RTM_GETNETCONF);
+        NativeEndian::write_u16(&mut buf[6..8], NLM_F_REQUEST);
+        NativeEndian::write_u32(&mut buf[8..12], SEQUENCE_NUMBER);
+        buf[16] = self.family;
+        NativeEndian::write_u16(&mut buf[20..22], 8);
+        NativeEndian::write_u16(&mut buf[22..24], NETCONFA_IFINDEX);
+        NativeEndian::write_i32(&mut buf[24..28], self.index);
+        buf
+    }
+}
@@ -0,0 +1,20 @@
+// SPDX-License-Identifier: MIT
+
+use crate::{Handle, IpVersion, NetconfGetRequest};
+
+pub struct NetconfHandle(pub(crate) Handle);
+
+impl NetconfHandle {
+    pub fn new(handle: Handle) -> Self {
+        NetconfHandle(handle)
+    }
+
+    /// Get the IP settings of the link `index` for the `ip_version` address
+    /// family (equivalent to `ip netconf show dev <interface_name>`).
+    /// `index` can also be [`crate::NETCONFA_IFINDEX_ALL`] or
+    /// [`crate::NETCONFA_IFINDEX_DEFAULT`] for the `all` and `default`
+    /// settings.
+    pub fn get(&self, ip_version: IpVersion, index: i32) -> NetconfGetRequest {
+        NetconfGetRequest::new(self.0.clone(), ip_version, index)
+    }
+}
@@ -0,0 +1,13 @@
+// SPDX-License-Identifier: MIT
+
+mod handle;
+pub use self::handle::*;
+
+mod get;
+pub use self::get::*;
+
+mod monitor;
+pub use self::monitor::*;
+
+mod netconf;
+pub use self::netconf::*;
@@ -0,0 +1,88 @@
+// SPDX-License-Identifier: MIT
+
+use std::io;
+
+use futures::{Stream, StreamExt};
+use netlink_packet_route::{
+    RTM_DELNETCONF, RTM_NEWNETCONF, RTNLGRP_IPV4_NETCONF, RTNLGRP_IPV6_NETCONF,
+};
+use netlink_sys::{AsyncSocket, AsyncSocketExt};
+
+use crate::{
+    raw::{handle_socket, split_messages},
+    Error, Netconf, NetconfHandle,
+};
+
+/// A change of the IP settings of a link, see [`NetconfHandle::monitor`]
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum NetconfEvent {
+    /// Some settings changed. Only these settings are set, except when the
+    /// link is created: all of them are set then.
+    New(Netconf),
+    /// The link was removed
+    Del(Netconf),
+}
+
+impl NetconfHandle {
+    /// Listen to the changes of the IPv4 and IPv6 settings of all the links
+    /// (equivalent to `ip monitor netconf`), for instance `forwarding` or
+    /// `rp_filter`. The events are received on a dedicated socket, in the
+    /// network namespace of the connection of the handle. It fails on a
+    /// dry-run, mock or replay handle.
+    #[cfg(feature = "tokio_socket")]
+    pub fn monitor(
+        &self,
+    ) -> io::Result<impl Stream<Item = io::Result<NetconfEvent>>> {
+        self.monitor_with_socket::<netlink_sys::TokioSocket>()
+    }
+
+    /// Same as [`NetconfHandle::monitor`], using the socket type `S`.
+    pub fn monitor_with_socket<S>(
+        &self,
+    ) -> io::Result<impl Stream<Item = io::Result<NetconfEvent>>>
+    where
+        S: AsyncSocket,
+    {
+        let mut socket = handle_socket::<S>(&self.0).map_err(|e| match e {
+            Error::SocketError(kind, msg) => io::Error::new(kind, msg),
+            e => io::Error::new(io::ErrorKind::Unsupported, e.to_string()),
+        })?;
+        socket.socket_mut().add_membership(RTNLGRP_IPV4_NETCONF)?;
+        socket.socket_mut().add_membership(RTNLGRP_IPV6_NETCONF)?;
+
+        let events = futures::stream::unfold(socket, |socket| async move {
+            let events = match socket.recv_from_full().await {
+                Ok((buf, _)) => parse_events(&buf),
+                Err(e) => vec![Err(e)],
+            };
+            Some((futures::stream::iter(events), socket))
+        });
+        Ok(events.flatten())
+    }
+}
+
+fn parse_events(buf: &[u8]) -> Vec<io::Result<NetconfEvent>> {
+    let messages = match split_messages(buf) {
+        Ok(messages) => messages,
+        Err(e) => return vec![Err(invalid_data(e.to_string()))],
+    };
+    messages
+        .into_iter()
+        .filter_map(|message| {
+            let event = match message.message_type() {
+                RTM_NEWNETCONF => NetconfEvent::New,
+                RTM_DELNETCONF => NetconfEvent::Del,
+                _ => return None,
+            };
+            Some(
+                Netconf::parse(message.payload())
+                    .map(event)
+                    .map_err(|e| invalid_data(e.to_string())),
+            )
+        })
+        .collect()
+}
+
+fn invalid_data(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
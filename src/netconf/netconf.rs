@@ -0,0 +1,106 @@
+// SPDX-License-Identifier: MIT
+
+use netlink_packet_utils::{
+    nla::NlasIterator, parsers::parse_i32, DecodeError,
+};
+
+/// [`Netconf::index`] of the `all` settings, which apply to every link
+pub const NETCONFA_IFINDEX_ALL: i32 = -1;
+/// [`Netconf::index`] of the `default` settings, given to new links
+pub const NETCONFA_IFINDEX_DEFAULT: i32 = -2;
+
+const NETCONFMSG_LEN: usize = 4;
+const NETCONFA_IFINDEX: u16 = 1;
+const NETCONFA_FORWARDING: u16 = 2;
+const NETCONFA_RP_FILTER: u16 = 3;
+const NETCONFA_MC_FORWARDING: u16 = 4;
+const NETCONFA_PROXY_NEIGH: u16 = 5;
+const NETCONFA_IGNORE_ROUTES_WITH_LINKDOWN: u16 = 6;
+const NETCONFA_BC_FORWARDING: u16 = 8;
+
+/// The IP settings of a link for an address family, i.e. the
+/// `net.ipv4.conf.<interface_name>.*` or `net.ipv6.conf.<interface_name>.*`
+/// sysctls, as reported by the kernel in `RTM_NEWNETCONF` messages. The
+/// settings the kernel does not report (`rp_filter` for IPv6 for instance)
+/// are `None`.
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct Netconf {
+    /// `AF_INET` or `AF_INET6`
+    pub family: u8,
+    /// Index of the link, [`NETCONFA_IFINDEX_ALL`] or
+    /// [`NETCONFA_IFINDEX_DEFAULT`]
+    pub index: i32,
+    pub forwarding: Option<bool>,
+    /// Reverse path filtering: 0 (none), 1 (strict) or 2 (loose)
+    pub rp_filter: Option<u32>,
+    pub mc_forwarding: Option<bool>,
+    pub proxy_neigh: Option<bool>,
+    pub ignore_routes_with_linkdown: Option<bool>,
+    pub bc_forwarding: Option<bool>,
+}
+
+impl Netconf {
+    /// Parse the payload of a `RTM_NEWNETCONF` message
+    pub(crate) fn parse(payload: &[u8]) -> Result<Self, DecodeError> {
+        let family = *payload
+            .first()
+            .ok_or_else(|| DecodeError::from("empty netconf message"))?;
+        let mut netconf = Netconf {
+            family,
+            ..Default::default()
+        };
+        let nlas = payload.get(NETCONFMSG_LEN..).unwrap_or_default();
+        for nla in NlasIterator::new(nlas) {
+            let nla = nla?;
+            let value = || parse_i32(nla.value());
+            match nla.kind() {
+                NETCONFA_IFINDEX => netconf.index = value()?,
+                NETCONFA_FORWARDING => netconf.forwarding = Some(value()? != 0),
+                NETCONFA_RP_FILTER => netconf.rp_filter = Some(value()? as u32),
+                NETCONFA_MC_FORWARDING => {
+                    netconf.mc_forwarding = Some(value()? != 0)
+                }
+                NETCONFA_PROXY_NEIGH => {
+                    netconf.proxy_neigh = Some(value()? != 0)
+                }
+                NETCONFA_IGNORE_ROUTES_WITH_LINKDOWN => {
+                    netconf.ignore_routes_with_linkdown = Some(value()? != 0)
+                }
+                NETCONFA_BC_FORWARDING => {
+                    netconf.bc_forwarding = Some(value()? != 0)
+                }
+                _ => {}
+            }
+        }
+        Ok(netconf)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_netconf() {
+        let payload = [
+            2, 0, 0, 0, // netconfmsg, AF_INET
+            8, 0, 1, 0, 0xff, 0xff, 0xff, 0xff, // NETCONFA_IFINDEX, all
+            8, 0, 2, 0, 1, 0, 0, 0, // NETCONFA_FORWARDING
+            8, 0, 3, 0, 2, 0, 0, 0, // NETCONFA_RP_FILTER
+            8, 0, 9, 0, 1, 0, 0, 0, // unknown attribute
+        ];
+        assert_eq!(
+            Netconf::parse(&payload).unwrap(),
+            Netconf {
+                family: 2,
+                index: NETCONFA_IFINDEX_ALL,
+                forwarding: Some(true),
+                rp_filter: Some(2),
+                ..Default::default()
+            }
+        );
+        assert!(Netconf::parse(&[]).is_err());
+        assert!(Netconf::parse(&payload[..10]).is_err());
+    }
+}
@@ -0,0 +1,39 @@
+// SPDX-License-Identifier: MIT
+
+//! Re-exports of the types needed by most users of this crate, and of the
+//! `futures` traits used to consume the responses of the requests.
+//!
+//! ```no_run
+//! use rtnetlink::prelude::*;
+//!
+//! async fn print_links(handle: Handle) -> Result<(), Error> {
+//!     let mut links = handle.link().get().execute();
+//!     while let Some(link) = links.try_next().await? {
+//!         println!("{:?}", Link::from(link));
+//!     }
+//!     Ok(())
+//! }
+//! ```
+
+pub use futures::{StreamExt as _, TryStreamExt as _};
+
+#[cfg(feature = "tokio_socket")]
+pub use crate::new_connection;
+#[cfg(feature = "smol_socket")]
+pub use crate::new_smol_connection;
+pub use crate::{
+    Address, AddressAddRequest, AddressDelRequest, AddressGetRequest,
+    AddressHandle, Batch, BondSetRequest, BridgeBoolOpt,
+    BridgePortMulticastRouter, BridgePortSetRequest, BridgePortState,
+    BridgeSetRequest, CollectOptions, ConnectionOptions, Error, Handle,
+    Icmpv6Stats, IpVersion, Ipv6Stats, Link, LinkAddRequest, LinkDelRequest,
+    LinkGetRequest, LinkHandle, LinkIndexCache, LinkRenameRequest,
+    LinkSetRequest, LinkStats, LinkStatsSample, LinkXstats, LinkXstatsRequest,
+    MacAddress, MacVlanSetRequest, Neighbour, NeighbourAddRequest,
+    NeighbourDelRequest, NeighbourGetRequest, NeighbourHandle, Netconf,
+    NetconfEvent, NetconfGetRequest, NetconfHandle, QDiscHandle, Route,
+    RouteAddRequest, RouteDelRequest, RouteGetRequest, RouteHandle,
+    RuleAddRequest, RuleDelRequest, RuleGetRequest, RuleHandle,
+    TrafficChainHandle, TrafficClassHandle, TrafficFilterHandle,
+    VxlanSetRequest,
+};
@@ -0,0 +1,191 @@
+// SPDX-License-Identifier: MIT
+
+use std::{
+    fs::File,
+    io,
+    os::unix::{fs::MetadataExt, io::AsRawFd},
+};
+
+use netlink_packet_core::{
+    ErrorBuffer, ErrorMessage, NetlinkBuffer, NetlinkMessage, NetlinkPayload,
+    NLMSG_ERROR,
+};
+use netlink_packet_route::RtnlMessage;
+use netlink_packet_utils::{DecodeError, Parseable};
+use netlink_sys::{
+    protocols::NETLINK_ROUTE, AsyncSocket, AsyncSocketExt, SocketAddr,
+};
+use nix::sched::{setns, CloneFlags};
+
+use crate::{
+    handle::{error_code, timeout_after},
+    Error, Handle,
+};
+
+/// The network namespace of the current thread
+pub(crate) const THREAD_NS_PATH: &str = "/proc/thread-self/ns/net";
+
+/// The sequence number of the requests sent with [`request`]. Each request
+/// has its own socket, so they can all use the same one.
+pub(crate) const SEQUENCE_NUMBER: u32 = 1;
+
+/// Create a netlink socket of type `S`, bound to a port chosen by the
+/// kernel. It is used for the messages that can't go through the connection
+/// of a [`crate::Handle`], because netlink-packet-route can't represent them
+/// or because they are decoded lazily.
+pub(crate) fn new_socket<S>() -> io::Result<S>
+where
+    S: AsyncSocket,
+{
+    let mut socket = S::new(NETLINK_ROUTE)?;
+    socket.socket_mut().bind_auto()?;
+    Ok(socket)
+}
+
+/// Create a socket with [`new_socket`], in the network namespace of the
+/// connection of `handle`, so that it reaches the same objects as the
+/// requests of the handle. It fails for the dry-run, mock and replay
+/// handles, which don't talk to the kernel.
+pub(crate) fn handle_socket<S>(handle: &Handle) -> Result<S, Error>
+where
+    S: AsyncSocket,
+{
+    Ok(in_netns(handle.netns()?, new_socket::<S>)?)
+}
+
+// Run `f` in the network namespace `netns` (if it is not the current one),
+// then switch back to the current one. The sockets created by `f` stay in
+// `netns`.
+fn in_netns<T>(
+    netns: Option<&File>,
+    f: impl FnOnce() -> io::Result<T>,
+) -> io::Result<T> {
+    let netns = match netns {
+        Some(netns) => netns,
+        None => return f(),
+    };
+    let current = File::open(THREAD_NS_PATH)?;
+    let (a, b) = (current.metadata()?, netns.metadata()?);
+    if (a.dev(), a.ino()) == (b.dev(), b.ino()) {
+        return f();
+    }
+    setns(netns.as_raw_fd(), CloneFlags::CLONE_NEWNET)?;
+    let result = f();
+    setns(current.as_raw_fd(), CloneFlags::CLONE_NEWNET)?;
+    result
+}
+
+/// Send the serialized `request` (with the [`SEQUENCE_NUMBER`] sequence
+/// number) on a new socket created with [`handle_socket`], and call `f`
+/// with each message of the response until it returns a value. The error
+/// messages of the kernel are returned as errors, and the acknowledgements
+/// (`NLMSG_ERROR` messages with a zero error code) are passed to `f` like
+/// the other messages. The request is subject to the timeout and the
+/// in-flight limit of `handle`.
+pub(crate) async fn request<S, T, F>(
+    handle: &Handle,
+    request: &[u8],
+    mut f: F,
+) -> Result<T, Error>
+where
+    S: AsyncSocket,
+    F: FnMut(NetlinkBuffer<&[u8]>) -> Result<Option<T>, Error>,
+{
+    let socket = handle_socket::<S>(handle)?;
+    let _permit = handle.in_flight_permit().await;
+    socket.send_to(request, &SocketAddr::new(0, 0)).await?;
+    timeout_after(handle.timeout(), async {
+        loop {
+            let (buf, _) = socket.recv_from_full().await?;
+            for mut message in split_messages(&buf)? {
+                if message.sequence_number() != SEQUENCE_NUMBER {
+                    continue;
+                }
+                if message.message_type() == NLMSG_ERROR {
+                    let bytes = message.into_inner();
+                    let error =
+                        NetlinkMessage::<RtnlMessage>::deserialize(bytes)?;
+                    if let NetlinkPayload::Error(err) = error.payload {
+                        if error_code(&err) != 0 {
+                            return Err(Error::netlink(
+                                error.header.flags,
+                                err,
+                            ));
+                        }
+                    }
+                    message = NetlinkBuffer::new(bytes);
+                }
+                if let Some(result) = f(message)? {
+                    return Ok(result);
+                }
+            }
+        }
+    })
+    .await
+}
+
+/// The result of a dump request, from its `NLMSG_DONE` message: the kernel
+/// puts the error of the dump, if any, at the start of the payload.
+pub(crate) fn dump_result(message: &NetlinkBuffer<&[u8]>) -> Result<(), Error> {
+    let payload = message.payload();
+    let err = match ErrorBuffer::new_checked(&payload)
+        .and_then(|buf| ErrorMessage::parse(&buf))
+    {
+        Ok(err) => err,
+        // Old kernels may send an empty NLMSG_DONE
+        Err(_) => return Ok(()),
+    };
+    if error_code(&err) != 0 {
+        return Err(Error::netlink(message.flags(), err));
+    }
+    Ok(())
+}
+
+/// Split a datagram received from the kernel into its netlink messages
+pub(crate) fn split_messages(
+    buf: &[u8],
+) -> Result<Vec<NetlinkBuffer<&[u8]>>, DecodeError> {
+    let mut messages = Vec::new();
+    let mut offset = 0;
+    while offset < buf.len() {
+        let len = NetlinkBuffer::new_checked(&buf[offset..])?.length() as usize;
+        messages.push(NetlinkBuffer::new_checked(&buf[offset..offset + len])?);
+        offset += (len + 3) & !3;
+    }
+    Ok(messages)
+}
+
+#[cfg(test)]
+mod test {
+    use netlink_packet_core::{NetlinkHeader, NLMSG_DONE};
+    use netlink_packet_utils::Emitable;
+
+    use super::*;
+
+    fn done_message(payload: &[u8]) -> Vec<u8> {
+        let mut header = NetlinkHeader::default();
+        header.length = (16 + payload.len()) as u32;
+        header.message_type = NLMSG_DONE;
+        let mut buf = vec![0; 16];
+        header.emit(&mut buf);
+        buf.extend_from_slice(payload);
+        buf
+    }
+
+    #[test]
+    fn test_dump_result() {
+        let buf = done_message(&[]);
+        assert!(dump_result(&NetlinkBuffer::new(&buf[..])).is_ok());
+
+        let buf = done_message(&0i32.to_ne_bytes());
+        assert!(dump_result(&NetlinkBuffer::new(&buf[..])).is_ok());
+
+        // The dump was interrupted by an error, followed by the header of
+        // the request
+        let mut payload = (-nix::libc::EOPNOTSUPP).to_ne_bytes().to_vec();
+        payload.extend_from_slice(&[0; 16]);
+        let buf = done_message(&payload);
+        let err = dump_result(&NetlinkBuffer::new(&buf[..])).unwrap_err();
+        assert_eq!(err.errno(), Some(nix::libc::EOPNOTSUPP));
+    }
+}
@@ -1,6 +1,6 @@
 // SPDX-License-Identifier: MIT
 
-use futures::stream::StreamExt;
+use futures::stream::{StreamExt, TryStreamExt};
 use std::{
     marker::PhantomData,
     net::{Ipv4Addr, Ipv6Addr},
@@ -15,7 +15,11 @@ use netlink_packet_route::{
     RTN_UNICAST, RTPROT_STATIC, RT_SCOPE_UNIVERSE, RT_TABLE_MAIN,
 };
 
-use crate::{try_nl, Error, Handle};
+use super::seg6::{
+    self, Seg6LocalAction, Seg6Mode, Seg6Srh, LWTUNNEL_ENCAP_SEG6,
+    LWTUNNEL_ENCAP_SEG6_LOCAL,
+};
+use crate::{try_nl, Error, Handle, IpVersion, RouteGetRequest};
 
 /// A request to create a new route. This is equivalent to the `ip route add`
 /// commands.
@@ -23,6 +27,7 @@ pub struct RouteAddRequest<T = ()> {
     handle: Handle,
     message: RouteMessage,
     replace: bool,
+    ensure: bool,
     _phantom: PhantomData<T>,
 }
 
@@ -39,10 +44,18 @@ impl<T> RouteAddRequest<T> {
             handle,
             message,
             replace: false,
+            ensure: false,
             _phantom: Default::default(),
         }
     }
 
+    pub(crate) fn ensure(self) -> Self {
+        Self {
+            ensure: true,
+            ..self
+        }
+    }
+
     /// Sets the input interface index.
     pub fn input_interface(mut self, index: u32) -> Self {
         self.message.nlas.push(Nla::Iif(index));
@@ -100,13 +113,22 @@ impl<T> RouteAddRequest<T> {
         self
     }
 
+    /// Add a segment routing header to the packets of the route. This is
+    /// equivalent to `ip route add ... encap seg6 mode MODE segs SEGMENTS`.
+    pub fn seg6(mut self, mode: Seg6Mode, srh: &Seg6Srh) -> Self {
+        self.message.nlas.push(Nla::EncapType(LWTUNNEL_ENCAP_SEG6));
+        self.message.nlas.push(Nla::Encap(seg6::encap(mode, srh)));
+        self
+    }
+
     /// Build an IP v4 route request
     pub fn v4(mut self) -> RouteAddRequest<Ipv4Addr> {
         self.message.header.address_family = AF_INET as u8;
         RouteAddRequest {
             handle: self.handle,
             message: self.message,
-            replace: false,
+            replace: self.replace,
+            ensure: self.ensure,
             _phantom: Default::default(),
         }
     }
@@ -117,12 +139,14 @@ impl<T> RouteAddRequest<T> {
         RouteAddRequest {
             handle: self.handle,
             message: self.message,
-            replace: false,
+            replace: self.replace,
+            ensure: self.ensure,
             _phantom: Default::default(),
         }
     }
 
-    /// Replace existing matching route.
+    /// Replace existing matching route. With [`crate::RouteHandle::ensure`],
+    /// the route is only replaced if it differs from the requested one.
     pub fn replace(self) -> Self {
         Self {
             replace: true,
@@ -130,23 +154,63 @@ impl<T> RouteAddRequest<T> {
         }
     }
 
-    /// Execute the request.
+    /// Execute the request. An [`Error::InvalidRequest`] is returned if a
+    /// prefix length is larger than the addresses of the route.
     pub async fn execute(self) -> Result<(), Error> {
+        let header = &self.message.header;
+        let max_prefix_len = match header.address_family as u16 {
+            AF_INET => 32,
+            AF_INET6 => 128,
+            _ => u8::MAX,
+        };
+        for prefix_len in [
+            header.destination_prefix_length,
+            header.source_prefix_length,
+        ] {
+            if prefix_len > max_prefix_len {
+                return Err(Error::InvalidRequest(format!(
+                    "prefix length {} is out of range",
+                    prefix_len
+                )));
+            }
+        }
         let RouteAddRequest {
             mut handle,
             message,
             replace,
+            ensure,
             ..
         } = self;
-        let mut req = NetlinkMessage::from(RtnlMessage::NewRoute(message));
-        let replace = if replace { NLM_F_REPLACE } else { NLM_F_EXCL };
-        req.header.flags = NLM_F_REQUEST | NLM_F_ACK | replace | NLM_F_CREATE;
+        if !ensure {
+            let flags = if replace { NLM_F_REPLACE } else { NLM_F_EXCL };
+            return add(&mut handle, message, flags).await;
+        }
 
-        let mut response = handle.request(req)?;
-        while let Some(message) = response.next().await {
-            try_nl!(message);
+        // The kernel reports EEXIST as soon as a route has the same
+        // destination, table, TOS and priority, even if its gateway or
+        // output interface differ: compare the existing routes with the
+        // requested one.
+        match add(&mut handle, message.clone(), NLM_F_EXCL).await {
+            Err(e) if e.is_already_exists() => {
+                let version = match message.header.address_family as u16 {
+                    AF_INET6 => IpVersion::V6,
+                    _ => IpVersion::V4,
+                };
+                let routes: Vec<_> =
+                    RouteGetRequest::new(handle.clone(), version)
+                        .execute()
+                        .try_collect()
+                        .await?;
+                if routes.iter().any(|route| is_same_route(&message, route)) {
+                    Ok(())
+                } else if replace {
+                    add(&mut handle, message, NLM_F_REPLACE).await
+                } else {
+                    Err(e)
+                }
+            }
+            result => result,
         }
-        Ok(())
     }
 
     /// Return a mutable reference to the request message.
@@ -155,6 +219,46 @@ impl<T> RouteAddRequest<T> {
     }
 }
 
+async fn add(
+    handle: &mut Handle,
+    message: RouteMessage,
+    flags: u16,
+) -> Result<(), Error> {
+    let mut req = NetlinkMessage::from(RtnlMessage::NewRoute(message));
+    req.header.flags = NLM_F_REQUEST | NLM_F_ACK | NLM_F_CREATE | flags;
+    let mut response = handle.try_request(req)?;
+    while let Some(message) = response.next().await {
+        try_nl!(message?);
+    }
+    Ok(())
+}
+
+// Whether `existing` is the route that `requested` would create: the
+// attributes of the request must all be set on the existing route, which
+// also has the ones the kernel adds (e.g. the table or the cache info).
+fn is_same_route(requested: &RouteMessage, existing: &RouteMessage) -> bool {
+    fn table(message: &RouteMessage) -> u32 {
+        message
+            .nlas
+            .iter()
+            .find_map(|nla| match nla {
+                Nla::Table(table) => Some(*table),
+                _ => None,
+            })
+            .unwrap_or(message.header.table as u32)
+    }
+    let (a, b) = (&requested.header, &existing.header);
+    a.address_family == b.address_family
+        && a.destination_prefix_length == b.destination_prefix_length
+        && a.source_prefix_length == b.source_prefix_length
+        && a.tos == b.tos
+        && a.protocol == b.protocol
+        && a.scope == b.scope
+        && a.kind == b.kind
+        && table(requested) == table(existing)
+        && requested.nlas.iter().all(|nla| existing.nlas.contains(nla))
+}
+
 impl RouteAddRequest<Ipv4Addr> {
     /// Sets the source address prefix.
     pub fn source_prefix(mut self, addr: Ipv4Addr, prefix_length: u8) -> Self {
@@ -225,4 +329,17 @@ impl RouteAddRequest<Ipv6Addr> {
         self.message.nlas.push(Nla::Gateway(gtw));
         self
     }
+
+    /// Make the destination a local SRv6 segment with the given behavior.
+    /// The output interface must be set too. This is equivalent to `ip -6
+    /// route add ... encap seg6local action ACTION`.
+    pub fn seg6local(mut self, action: &Seg6LocalAction) -> Self {
+        self.message
+            .nlas
+            .push(Nla::EncapType(LWTUNNEL_ENCAP_SEG6_LOCAL));
+        self.message
+            .nlas
+            .push(Nla::Encap(seg6::local_encap(action)));
+        self
+    }
 }
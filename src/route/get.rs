@@ -6,13 +6,21 @@ use futures::{
     FutureExt,
 };
 
-use netlink_packet_core::{NetlinkMessage, NLM_F_DUMP, NLM_F_REQUEST};
+use netlink_packet_core::{
+    NetlinkMessage, NLMSG_DONE, NLM_F_DUMP, NLM_F_REQUEST,
+};
 use netlink_packet_route::{
-    RouteMessage, RtnlMessage, AF_INET, AF_INET6, RTN_UNSPEC, RTPROT_UNSPEC,
-    RT_SCOPE_UNIVERSE, RT_TABLE_UNSPEC,
+    RouteMessage, RouteMessageBuffer, RtnlMessage, AF_INET, AF_INET6,
+    RTM_NEWROUTE, RTN_UNSPEC, RTPROT_UNSPEC, RT_SCOPE_UNIVERSE,
+    RT_TABLE_UNSPEC,
 };
+use netlink_sys::AsyncSocket;
 
-use crate::{try_rtnl, Error, Handle};
+use crate::{
+    collect::collect,
+    raw::{self, SEQUENCE_NUMBER},
+    try_rtnl, CollectOptions, Error, Handle,
+};
 
 pub struct RouteGetRequest {
     handle: Handle,
@@ -65,6 +73,15 @@ impl RouteGetRequest {
         &mut self.message
     }
 
+    /// Execute the request, gathering the results into a `Vec` according
+    /// to `options`, for dumps small enough not to need a stream.
+    pub async fn execute_collect(
+        self,
+        options: CollectOptions,
+    ) -> Result<Vec<RouteMessage>, Error> {
+        collect(self.execute(), options).await
+    }
+
     pub fn execute(self) -> impl TryStream<Ok = RouteMessage, Error = Error> {
         let RouteGetRequest {
             mut handle,
@@ -74,14 +91,67 @@ impl RouteGetRequest {
         let mut req = NetlinkMessage::from(RtnlMessage::GetRoute(message));
         req.header.flags = NLM_F_REQUEST | NLM_F_DUMP;
 
-        match handle.request(req) {
+        match handle.try_request(req) {
             Ok(response) => Either::Left(
                 response
-                    .map(move |msg| Ok(try_rtnl!(msg, RtnlMessage::NewRoute))),
+                    .map(move |msg| Ok(try_rtnl!(msg?, RtnlMessage::NewRoute))),
             ),
             Err(e) => Either::Right(
                 future::err::<RouteMessage, Error>(e).into_stream(),
             ),
         }
     }
+
+    /// Execute the request, calling `f` with each route borrowed from the
+    /// receive buffer instead of returning a [`RouteMessage`]: the
+    /// attributes are only decoded when `f` iterates over them with
+    /// [`RouteMessageBuffer::nlas`]. This saves most of the allocations when
+    /// dumping large routing tables.
+    ///
+    /// The request is sent on a dedicated netlink socket, in the network
+    /// namespace of the connection of the handle, and is subject to its
+    /// timeout and in-flight limit. It fails with [`Error::InvalidRequest`]
+    /// on a [`Handle::dry_run`], [`Handle::mock`] or replay handle.
+    #[cfg(feature = "tokio_socket")]
+    pub async fn execute_borrowed<F>(self, f: F) -> Result<(), Error>
+    where
+        F: FnMut(RouteMessageBuffer<&[u8]>),
+    {
+        self.execute_borrowed_with_socket::<netlink_sys::TokioSocket, F>(f)
+            .await
+    }
+
+    /// Same as [`RouteGetRequest::execute_borrowed`], using the socket type
+    /// `S` (for instance `SmolSocket`).
+    pub async fn execute_borrowed_with_socket<S, F>(
+        self,
+        mut f: F,
+    ) -> Result<(), Error>
+    where
+        S: AsyncSocket,
+        F: FnMut(RouteMessageBuffer<&[u8]>),
+    {
+        raw::request::<S, _, _>(&self.handle, &self.to_bytes(), |message| {
+            match message.message_type() {
+                RTM_NEWROUTE => {
+                    f(RouteMessageBuffer::new_checked(message.payload())?);
+                    Ok(None)
+                }
+                NLMSG_DONE => raw::dump_result(&message).map(Some),
+                _ => Ok(None),
+            }
+        })
+        .await
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut req =
+            NetlinkMessage::from(RtnlMessage::GetRoute(self.message.clone()));
+        req.header.flags = NLM_F_REQUEST | NLM_F_DUMP;
+        req.header.sequence_number = SEQUENCE_NUMBER;
+        req.finalize();
+        let mut buf = vec![0; req.buffer_len()];
+        req.serialize(&mut buf);
+        buf
+    }
 }
@@ -1,9 +1,12 @@
 // SPDX-License-Identifier: MIT
 
+use futures::stream::TryStreamExt;
+use netlink_packet_route::{RouteMessage, RTN_UNICAST, RT_TABLE_MAIN};
+
 use crate::{
-    Handle, IpVersion, RouteAddRequest, RouteDelRequest, RouteGetRequest,
+    Error, Handle, IpVersion, Route, RouteAddRequest, RouteDelRequest,
+    RouteGetRequest,
 };
-use netlink_packet_route::RouteMessage;
 
 pub struct RouteHandle(Handle);
 
@@ -18,13 +21,171 @@ impl RouteHandle {
         RouteGetRequest::new(self.0.clone(), ip_version)
     }
 
+    /// Retrieve the default routes of the main routing table (equivalent to
+    /// `ip route show default`). There are several of them when they have
+    /// different metrics, for instance one per interface.
+    pub async fn default_routes(
+        &self,
+        ip_version: IpVersion,
+    ) -> Result<Vec<Route>, Error> {
+        let routes: Vec<RouteMessage> =
+            self.get(ip_version).execute().try_collect().await?;
+        Ok(routes
+            .iter()
+            .map(Route::from)
+            .filter(|route| {
+                route.destination_prefix_length == 0
+                    && route.table == RT_TABLE_MAIN as u32
+                    && route.kind == RTN_UNICAST
+            })
+            .collect())
+    }
+
     /// Add an routing table entry (equivalent to `ip route add`)
     pub fn add(&self) -> RouteAddRequest {
         RouteAddRequest::new(self.0.clone())
     }
 
+    /// Same as [`RouteHandle::add`], but succeeds if the same route already
+    /// exists. A route with the same destination, table, TOS and priority
+    /// but other attributes (e.g. another gateway) is a conflict, reported
+    /// as `EEXIST`, unless [`RouteAddRequest::replace`] is used to replace
+    /// it.
+    pub fn ensure(&self) -> RouteAddRequest {
+        RouteAddRequest::new(self.0.clone()).ensure()
+    }
+
     /// Delete the given routing table entry (equivalent to `ip route del`)
     pub fn del(&self, route: RouteMessage) -> RouteDelRequest {
         RouteDelRequest::new(self.0.clone(), route)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::net::{IpAddr, Ipv4Addr};
+
+    use futures::TryStreamExt;
+    use tokio::runtime::Runtime;
+
+    use super::*;
+    use crate::{new_connection, MockNetwork, Route};
+
+    #[test]
+    fn test_default_routes() {
+        let rt = Runtime::new().unwrap();
+        rt.block_on(async {
+            let network = MockNetwork::new();
+            let handle = network.handle();
+            let route = handle.route();
+            route
+                .add()
+                .v4()
+                .gateway(Ipv4Addr::new(192, 0, 2, 254))
+                .output_interface(1)
+                .execute()
+                .await
+                .unwrap();
+            route
+                .add()
+                .v4()
+                .destination_prefix(Ipv4Addr::new(198, 51, 100, 0), 24)
+                .gateway(Ipv4Addr::new(192, 0, 2, 1))
+                .execute()
+                .await
+                .unwrap();
+
+            let routes = route.default_routes(IpVersion::V4).await.unwrap();
+            assert_eq!(routes.len(), 1);
+            assert_eq!(
+                routes[0].gateway,
+                Some(IpAddr::V4(Ipv4Addr::new(192, 0, 2, 254)))
+            );
+            assert_eq!(routes[0].output_interface, Some(1));
+            assert!(route
+                .default_routes(IpVersion::V6)
+                .await
+                .unwrap()
+                .is_empty());
+        });
+    }
+
+    #[test]
+    fn test_ensure_route() {
+        let rt = Runtime::new().unwrap();
+        rt.block_on(async {
+            let network = MockNetwork::new();
+            let handle = network.handle();
+            let ensure = |gateway| {
+                handle
+                    .route()
+                    .ensure()
+                    .v4()
+                    .destination_prefix(Ipv4Addr::new(198, 51, 100, 0), 24)
+                    .gateway(gateway)
+                    .output_interface(1)
+            };
+            let gateway = |routes: Vec<Route>| {
+                assert_eq!(routes.len(), 1);
+                routes[0].gateway
+            };
+
+            ensure(Ipv4Addr::new(192, 0, 2, 1)).execute().await.unwrap();
+            // The same route already exists
+            ensure(Ipv4Addr::new(192, 0, 2, 1)).execute().await.unwrap();
+            // A route to the same destination through another gateway
+            let err = ensure(Ipv4Addr::new(192, 0, 2, 2))
+                .execute()
+                .await
+                .unwrap_err();
+            assert!(err.is_already_exists());
+            assert_eq!(
+                gateway(network.routes()),
+                Some(IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1)))
+            );
+
+            ensure(Ipv4Addr::new(192, 0, 2, 2))
+                .replace()
+                .execute()
+                .await
+                .unwrap();
+            assert_eq!(
+                gateway(network.routes()),
+                Some(IpAddr::V4(Ipv4Addr::new(192, 0, 2, 2)))
+            );
+        });
+    }
+
+    #[test]
+    #[cfg_attr(not(feature = "test_as_root"), ignore)]
+    fn test_execute_borrowed() {
+        let rt = Runtime::new().unwrap();
+        rt.block_on(async {
+            let (conn, handle, _) = new_connection().unwrap();
+            tokio::spawn(conn);
+            let route = handle.route();
+            let routes: Vec<_> = route
+                .get(IpVersion::V4)
+                .execute()
+                .try_collect()
+                .await
+                .unwrap();
+            let mut borrowed = Vec::new();
+            route
+                .get(IpVersion::V4)
+                .execute_borrowed(|msg| {
+                    borrowed.push((msg.table(), msg.nlas().count()))
+                })
+                .await
+                .unwrap();
+            assert!(!borrowed.is_empty());
+            assert_eq!(
+                borrowed,
+                routes
+                    .iter()
+                    .map(|msg| (msg.header.table, msg.nlas.len()))
+                    .collect::<Vec<_>>()
+            );
+        });
+    }
+}
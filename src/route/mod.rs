@@ -11,3 +11,9 @@ pub use self::del::*;
 
 mod get;
 pub use self::get::*;
+
+mod view;
+pub use self::view::*;
+
+mod seg6;
+pub use self::seg6::*;
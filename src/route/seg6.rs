@@ -0,0 +1,280 @@
+// SPDX-License-Identifier: MIT
+
+use std::net::Ipv6Addr;
+
+use netlink_packet_utils::{
+    byteorder::{BigEndian, ByteOrder, NativeEndian},
+    nla::Nla,
+    Emitable,
+};
+
+use crate::Error;
+
+pub(crate) const LWTUNNEL_ENCAP_SEG6: u16 = 5;
+pub(crate) const LWTUNNEL_ENCAP_SEG6_LOCAL: u16 = 7;
+
+const SEG6_IPTUNNEL_SRH: u16 = 1;
+
+const SEG6_LOCAL_ACTION: u16 = 1;
+const SEG6_LOCAL_SRH: u16 = 2;
+const SEG6_LOCAL_TABLE: u16 = 3;
+const SEG6_LOCAL_NH6: u16 = 5;
+
+const SEG6_LOCAL_ACTION_END: u32 = 1;
+const SEG6_LOCAL_ACTION_END_X: u32 = 2;
+const SEG6_LOCAL_ACTION_END_T: u32 = 3;
+const SEG6_LOCAL_ACTION_END_DT6: u32 = 7;
+const SEG6_LOCAL_ACTION_END_B6: u32 = 9;
+const SEG6_LOCAL_ACTION_END_B6_ENCAP: u32 = 10;
+
+const IPV6_SRCRT_TYPE_4: u8 = 4;
+const SR6_FLAG1_HMAC: u8 = 1 << 3;
+const SR6_TLV_HMAC: u8 = 5;
+const SEG6_HMAC_FIELD_LEN: usize = 32;
+// Type, length, reserved, key id and the HMAC itself
+const SEG6_HMAC_TLV_LEN: usize = 2 + 2 + 4 + SEG6_HMAC_FIELD_LEN;
+
+/// The largest number of segments of a [`Seg6Srh`], so that its length in
+/// 8 octets units still fits in one byte with the HMAC TLV.
+pub const SEG6_MAX_SEGMENTS: usize = 125;
+
+/// How a segment routing header is added to the packets of a route
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Seg6Mode {
+    /// Insert the header into the IPv6 packets
+    Inline,
+    /// Encapsulate the packets in an outer IPv6 header
+    Encap,
+    /// Encapsulate the Ethernet frames in an outer IPv6 header
+    L2Encap,
+}
+
+impl From<Seg6Mode> for i32 {
+    fn from(mode: Seg6Mode) -> Self {
+        match mode {
+            Seg6Mode::Inline => 0,
+            Seg6Mode::Encap => 1,
+            Seg6Mode::L2Encap => 2,
+        }
+    }
+}
+
+/// A segment routing header (SRH), carrying the list of segments of an
+/// SRv6 path and optionally an HMAC TLV.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Seg6Srh {
+    segments: Vec<Ipv6Addr>,
+    hmac: Option<u32>,
+}
+
+impl Seg6Srh {
+    /// Create a header with the segments to visit, in order. An
+    /// [`Error::InvalidRequest`] is returned if there is no segment or more
+    /// than [`SEG6_MAX_SEGMENTS`].
+    pub fn new(segments: Vec<Ipv6Addr>) -> Result<Self, Error> {
+        if segments.is_empty() || segments.len() > SEG6_MAX_SEGMENTS {
+            return Err(Error::InvalidRequest(format!(
+                "a segment routing header takes 1 to {} segments, not {}",
+                SEG6_MAX_SEGMENTS,
+                segments.len()
+            )));
+        }
+        Ok(Seg6Srh {
+            segments,
+            hmac: None,
+        })
+    }
+
+    /// Add an HMAC TLV signed with the key of the given id, which must have
+    /// been configured with `ip sr hmac set`. The kernel computes the HMAC
+    /// of each packet. This is equivalent to the `hmac KEYID` option of `ip
+    /// route add ... encap seg6`.
+    pub fn hmac(mut self, key_id: u32) -> Self {
+        self.hmac = Some(key_id);
+        self
+    }
+
+    fn buffer_len(&self) -> usize {
+        8 + 16 * self.segments.len()
+            + self.hmac.map_or(0, |_| SEG6_HMAC_TLV_LEN)
+    }
+
+    // struct ipv6_sr_hdr, with the segments in reverse order
+    fn emit(&self, buffer: &mut [u8]) {
+        let last_entry = (self.segments.len() - 1) as u8;
+        buffer[0] = 0;
+        buffer[1] = (self.buffer_len() / 8 - 1) as u8;
+        buffer[2] = IPV6_SRCRT_TYPE_4;
+        buffer[3] = last_entry;
+        buffer[4] = last_entry;
+        buffer[5] = if self.hmac.is_some() {
+            SR6_FLAG1_HMAC
+        } else {
+            0
+        };
+        buffer[6..8].fill(0);
+        for (i, segment) in self.segments.iter().rev().enumerate() {
+            buffer[8 + 16 * i..24 + 16 * i].copy_from_slice(&segment.octets());
+        }
+        if let Some(key_id) = self.hmac {
+            let tlv = &mut buffer[8 + 16 * self.segments.len()..];
+            tlv[0] = SR6_TLV_HMAC;
+            tlv[1] = (SEG6_HMAC_TLV_LEN - 2) as u8;
+            tlv[2..4].fill(0);
+            BigEndian::write_u32(&mut tlv[4..8], key_id);
+            tlv[8..SEG6_HMAC_TLV_LEN].fill(0);
+        }
+    }
+}
+
+/// The behavior of a local SRv6 segment (`seg6local` route). When the
+/// `seg6_require_hmac` sysctl of the input interface is set, these
+/// behaviors verify the HMAC TLV of the packets before processing them.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Seg6LocalAction {
+    /// Forward to the next segment, `End`
+    End,
+    /// Forward to the next segment through the given IPv6 next hop, `End.X`
+    EndX(Ipv6Addr),
+    /// Forward to the next segment looking it up in the given table, `End.T`
+    EndT(u32),
+    /// Decapsulate and look the inner IPv6 packet up in the given table,
+    /// `End.DT6`
+    EndDt6(u32),
+    /// Insert the given header, `End.B6`
+    EndB6(Seg6Srh),
+    /// Encapsulate in an outer IPv6 header with the given header,
+    /// `End.B6.Encaps`
+    EndB6Encap(Seg6Srh),
+}
+
+enum Seg6Nla<'a> {
+    IptunnelSrh(Seg6Mode, &'a Seg6Srh),
+    LocalAction(u32),
+    LocalSrh(&'a Seg6Srh),
+    LocalTable(u32),
+    LocalNh6(Ipv6Addr),
+}
+
+impl Nla for Seg6Nla<'_> {
+    fn value_len(&self) -> usize {
+        use self::Seg6Nla::*;
+        match self {
+            IptunnelSrh(_, srh) => 4 + srh.buffer_len(),
+            LocalSrh(srh) => srh.buffer_len(),
+            LocalAction(_) | LocalTable(_) => 4,
+            LocalNh6(_) => 16,
+        }
+    }
+
+    fn emit_value(&self, buffer: &mut [u8]) {
+        use self::Seg6Nla::*;
+        match self {
+            IptunnelSrh(mode, srh) => {
+                NativeEndian::write_i32(buffer, (*mode).into());
+                srh.emit(&mut buffer[4..]);
+            }
+            LocalSrh(srh) => srh.emit(buffer),
+            LocalAction(value) | LocalTable(value) => {
+                NativeEndian::write_u32(buffer, *value)
+            }
+            LocalNh6(addr) => buffer.copy_from_slice(&addr.octets()),
+        }
+    }
+
+    fn kind(&self) -> u16 {
+        use self::Seg6Nla::*;
+        match self {
+            IptunnelSrh(..) => SEG6_IPTUNNEL_SRH,
+            LocalAction(_) => SEG6_LOCAL_ACTION,
+            LocalSrh(_) => SEG6_LOCAL_SRH,
+            LocalTable(_) => SEG6_LOCAL_TABLE,
+            LocalNh6(_) => SEG6_LOCAL_NH6,
+        }
+    }
+}
+
+fn encode(nlas: &[Seg6Nla]) -> Vec<u8> {
+    let mut buf = vec![0; nlas.buffer_len()];
+    nlas.emit(&mut buf);
+    buf
+}
+
+/// The `RTA_ENCAP` attribute of a `seg6` route
+pub(crate) fn encap(mode: Seg6Mode, srh: &Seg6Srh) -> Vec<u8> {
+    encode(&[Seg6Nla::IptunnelSrh(mode, srh)])
+}
+
+/// The `RTA_ENCAP` attribute of a `seg6local` route
+pub(crate) fn local_encap(action: &Seg6LocalAction) -> Vec<u8> {
+    use self::Seg6LocalAction::*;
+    let nlas = match action {
+        End => vec![Seg6Nla::LocalAction(SEG6_LOCAL_ACTION_END)],
+        EndX(nh6) => vec![
+            Seg6Nla::LocalAction(SEG6_LOCAL_ACTION_END_X),
+            Seg6Nla::LocalNh6(*nh6),
+        ],
+        EndT(table) => vec![
+            Seg6Nla::LocalAction(SEG6_LOCAL_ACTION_END_T),
+            Seg6Nla::LocalTable(*table),
+        ],
+        EndDt6(table) => vec![
+            Seg6Nla::LocalAction(SEG6_LOCAL_ACTION_END_DT6),
+            Seg6Nla::LocalTable(*table),
+        ],
+        EndB6(srh) => vec![
+            Seg6Nla::LocalAction(SEG6_LOCAL_ACTION_END_B6),
+            Seg6Nla::LocalSrh(srh),
+        ],
+        EndB6Encap(srh) => vec![
+            Seg6Nla::LocalAction(SEG6_LOCAL_ACTION_END_B6_ENCAP),
+            Seg6Nla::LocalSrh(srh),
+        ],
+    };
+    encode(&nlas)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_seg6_encap_hmac() {
+        let first: Ipv6Addr = "fc00::1".parse().unwrap();
+        let second: Ipv6Addr = "fc00::2".parse().unwrap();
+        let srh = Seg6Srh::new(vec![first, second]).unwrap().hmac(7);
+        let buf = encap(Seg6Mode::Encap, &srh);
+        // Attribute header, mode and SRH
+        assert_eq!(buf.len(), 4 + 4 + 8 + 32 + 40);
+        assert_eq!(&buf[..4], &[88, 0, 1, 0]);
+        assert_eq!(&buf[4..8], &1i32.to_ne_bytes());
+        let hdr = &buf[8..];
+        // 80 bytes of header are 9 units after the first one
+        assert_eq!(&hdr[..8], &[0, 9, 4, 1, 1, SR6_FLAG1_HMAC, 0, 0]);
+        assert_eq!(&hdr[8..24], &second.octets());
+        assert_eq!(&hdr[24..40], &first.octets());
+        assert_eq!(&hdr[40..48], &[5, 38, 0, 0, 0, 0, 0, 7]);
+        assert!(hdr[48..].iter().all(|b| *b == 0));
+    }
+
+    #[test]
+    fn test_seg6_srh_segments() {
+        assert!(Seg6Srh::new(vec![]).is_err());
+        let segments = vec![Ipv6Addr::LOCALHOST; SEG6_MAX_SEGMENTS];
+        let srh = Seg6Srh::new(segments).unwrap().hmac(1);
+        assert_eq!(srh.buffer_len() / 8 - 1, 255);
+        let segments = vec![Ipv6Addr::LOCALHOST; SEG6_MAX_SEGMENTS + 1];
+        assert!(Seg6Srh::new(segments).is_err());
+    }
+
+    #[test]
+    fn test_seg6_local_end_x() {
+        let nh6: Ipv6Addr = "fc00::1".parse().unwrap();
+        let buf = local_encap(&Seg6LocalAction::EndX(nh6));
+        let mut expected = vec![8, 0, 1, 0];
+        expected.extend_from_slice(&SEG6_LOCAL_ACTION_END_X.to_ne_bytes());
+        expected.extend_from_slice(&[20, 0, 5, 0]);
+        expected.extend_from_slice(&nh6.octets());
+        assert_eq!(buf, expected);
+    }
+}
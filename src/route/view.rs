@@ -0,0 +1,73 @@
+// SPDX-License-Identifier: MIT
+
+use std::net::IpAddr;
+
+use netlink_packet_route::{route::Nla, RouteMessage};
+
+use crate::parse_ip_addr;
+
+/// The most commonly used properties of a route, extracted from a
+/// [`RouteMessage`] (for instance one returned by
+/// [`crate::RouteGetRequest::execute`]).
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Route {
+    /// `AF_INET` or `AF_INET6`
+    pub address_family: u8,
+    /// Destination of the route. `None` for a default route.
+    pub destination: Option<IpAddr>,
+    pub destination_prefix_length: u8,
+    pub gateway: Option<IpAddr>,
+    /// Preferred source address
+    pub source: Option<IpAddr>,
+    /// Index of the output interface
+    pub output_interface: Option<u32>,
+    /// Metric of the route
+    pub priority: Option<u32>,
+    pub table: u32,
+    /// `RTPROT_*` protocol of the route
+    pub protocol: u8,
+    /// `RT_SCOPE_*` scope of the route
+    pub scope: u8,
+    /// `RTN_*` type of the route
+    pub kind: u8,
+}
+
+impl From<&RouteMessage> for Route {
+    fn from(message: &RouteMessage) -> Self {
+        let mut route = Route {
+            address_family: message.header.address_family,
+            destination: None,
+            destination_prefix_length: message.header.destination_prefix_length,
+            gateway: None,
+            source: None,
+            output_interface: None,
+            priority: None,
+            table: message.header.table.into(),
+            protocol: message.header.protocol,
+            scope: message.header.scope,
+            kind: message.header.kind,
+        };
+        for nla in &message.nlas {
+            match nla {
+                Nla::Destination(bytes) => {
+                    route.destination = parse_ip_addr(bytes)
+                }
+                Nla::Gateway(bytes) => route.gateway = parse_ip_addr(bytes),
+                Nla::PrefSource(bytes) => route.source = parse_ip_addr(bytes),
+                Nla::Oif(index) => route.output_interface = Some(*index),
+                Nla::Priority(priority) => route.priority = Some(*priority),
+                // tables above 255 are only in the RTA_TABLE attribute
+                Nla::Table(table) => route.table = *table,
+                _ => (),
+            }
+        }
+        route
+    }
+}
+
+impl From<RouteMessage> for Route {
+    fn from(message: RouteMessage) -> Self {
+        Route::from(&message)
+    }
+}
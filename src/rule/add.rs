@@ -135,9 +135,9 @@ impl<T> RuleAddRequest<T> {
         let replace = if replace { NLM_F_REPLACE } else { NLM_F_EXCL };
         req.header.flags = NLM_F_REQUEST | NLM_F_ACK | replace | NLM_F_CREATE;
 
-        let mut response = handle.request(req)?;
+        let mut response = handle.try_request(req)?;
         while let Some(message) = response.next().await {
-            try_nl!(message);
+            try_nl!(message?);
         }
 
         Ok(())
@@ -25,9 +25,9 @@ impl RuleDelRequest {
 
         let mut req = NetlinkMessage::from(RtnlMessage::DelRule(message));
         req.header.flags = NLM_F_REQUEST | NLM_F_ACK;
-        let mut response = handle.request(req)?;
+        let mut response = handle.try_request(req)?;
         while let Some(msg) = response.next().await {
-            try_nl!(msg);
+            try_nl!(msg?);
         }
         Ok(())
     }
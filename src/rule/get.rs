@@ -10,7 +10,9 @@ use netlink_packet_route::{
     RtnlMessage, RuleMessage, FR_ACT_UNSPEC, RT_TABLE_UNSPEC,
 };
 
-use crate::{try_rtnl, Error, Handle, IpVersion};
+use crate::{
+    collect::collect, try_rtnl, CollectOptions, Error, Handle, IpVersion,
+};
 
 pub struct RuleGetRequest {
     handle: Handle,
@@ -35,6 +37,15 @@ impl RuleGetRequest {
         &mut self.message
     }
 
+    /// Execute the request, gathering the results into a `Vec` according
+    /// to `options`, for dumps small enough not to need a stream.
+    pub async fn execute_collect(
+        self,
+        options: CollectOptions,
+    ) -> Result<Vec<RuleMessage>, Error> {
+        collect(self.execute(), options).await
+    }
+
     pub fn execute(self) -> impl TryStream<Ok = RuleMessage, Error = Error> {
         let RuleGetRequest {
             mut handle,
@@ -44,10 +55,10 @@ impl RuleGetRequest {
         let mut req = NetlinkMessage::from(RtnlMessage::GetRule(message));
         req.header.flags = NLM_F_REQUEST | NLM_F_DUMP;
 
-        match handle.request(req) {
+        match handle.try_request(req) {
             Ok(response) => Either::Left(
                 response
-                    .map(move |msg| Ok(try_rtnl!(msg, RtnlMessage::NewRule))),
+                    .map(move |msg| Ok(try_rtnl!(msg?, RtnlMessage::NewRule))),
             ),
             Err(e) => Either::Right(
                 future::err::<RuleMessage, Error>(e).into_stream(),
@@ -0,0 +1,64 @@
+// SPDX-License-Identifier: MIT
+
+use std::net::IpAddr;
+
+use netlink_packet_route::{rule::Nla, RuleMessage};
+
+use crate::parse_ip_addr;
+
+/// The most commonly used properties of a routing rule, extracted from a
+/// [`RuleMessage`] (for instance one returned by
+/// [`crate::RuleGetRequest::execute`]).
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Rule {
+    /// `AF_INET` or `AF_INET6`
+    pub family: u8,
+    pub priority: u32,
+    pub table: u32,
+    pub source: Option<IpAddr>,
+    pub source_prefix_length: u8,
+    pub destination: Option<IpAddr>,
+    pub destination_prefix_length: u8,
+    /// `FR_ACT_*` action of the rule
+    pub action: u8,
+    /// `RTPROT_*` protocol that installed the rule. Only reported since
+    /// Linux 4.17.
+    pub protocol: Option<u8>,
+}
+
+impl From<&RuleMessage> for Rule {
+    fn from(message: &RuleMessage) -> Self {
+        let mut rule = Rule {
+            family: message.header.family,
+            priority: 0,
+            table: message.header.table.into(),
+            source: None,
+            source_prefix_length: message.header.src_len,
+            destination: None,
+            destination_prefix_length: message.header.dst_len,
+            action: message.header.action,
+            protocol: None,
+        };
+        for nla in &message.nlas {
+            match nla {
+                Nla::Priority(priority) => rule.priority = *priority,
+                // tables above 255 are only in the FRA_TABLE attribute
+                Nla::Table(table) => rule.table = *table,
+                Nla::Source(bytes) => rule.source = parse_ip_addr(bytes),
+                Nla::Destination(bytes) => {
+                    rule.destination = parse_ip_addr(bytes)
+                }
+                Nla::Protocol(protocol) => rule.protocol = Some(*protocol),
+                _ => (),
+            }
+        }
+        rule
+    }
+}
+
+impl From<RuleMessage> for Rule {
+    fn from(message: RuleMessage) -> Self {
+        Rule::from(&message)
+    }
+}
@@ -43,9 +43,9 @@ impl TrafficFilterNewRequest {
             NetlinkMessage::from(RtnlMessage::NewTrafficFilter(message));
         req.header.flags = NLM_F_ACK | flags;
 
-        let mut response = handle.request(req)?;
+        let mut response = handle.try_request(req)?;
         while let Some(message) = response.next().await {
-            try_nl!(message);
+            try_nl!(message?);
         }
         Ok(())
     }
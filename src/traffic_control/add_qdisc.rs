@@ -39,9 +39,9 @@ impl QDiscNewRequest {
             NetlinkMessage::from(RtnlMessage::NewQueueDiscipline(message));
         req.header.flags = NLM_F_ACK | flags;
 
-        let mut response = handle.request(req)?;
+        let mut response = handle.try_request(req)?;
         while let Some(message) = response.next().await {
-            try_nl!(message);
+            try_nl!(message?);
         }
         Ok(())
     }
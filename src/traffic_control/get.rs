@@ -11,7 +11,7 @@ use netlink_packet_route::{
     RtnlMessage, TcMessage,
 };
 
-use crate::{try_rtnl, Error, Handle};
+use crate::{collect::collect, try_rtnl, CollectOptions, Error, Handle};
 
 pub struct QDiscGetRequest {
     handle: Handle,
@@ -26,6 +26,15 @@ impl QDiscGetRequest {
         }
     }
 
+    /// Execute the request, gathering the results into a `Vec` according
+    /// to `options`, for dumps small enough not to need a stream.
+    pub async fn execute_collect(
+        self,
+        options: CollectOptions,
+    ) -> Result<Vec<TcMessage>, Error> {
+        collect(self.execute(), options).await
+    }
+
     /// Execute the request
     pub fn execute(self) -> impl TryStream<Ok = TcMessage, Error = Error> {
         let QDiscGetRequest {
@@ -37,9 +46,9 @@ impl QDiscGetRequest {
             NetlinkMessage::from(RtnlMessage::GetQueueDiscipline(message));
         req.header.flags = NLM_F_REQUEST | NLM_F_DUMP;
 
-        match handle.request(req) {
+        match handle.try_request(req) {
             Ok(response) => Either::Left(response.map(move |msg| {
-                Ok(try_rtnl!(msg, RtnlMessage::NewQueueDiscipline))
+                Ok(try_rtnl!(msg?, RtnlMessage::NewQueueDiscipline))
             })),
             Err(e) => {
                 Either::Right(future::err::<TcMessage, Error>(e).into_stream())
@@ -72,6 +81,15 @@ impl TrafficClassGetRequest {
         TrafficClassGetRequest { handle, message }
     }
 
+    /// Execute the request, gathering the results into a `Vec` according
+    /// to `options`, for dumps small enough not to need a stream.
+    pub async fn execute_collect(
+        self,
+        options: CollectOptions,
+    ) -> Result<Vec<TcMessage>, Error> {
+        collect(self.execute(), options).await
+    }
+
     /// Execute the request
     pub fn execute(self) -> impl TryStream<Ok = TcMessage, Error = Error> {
         let TrafficClassGetRequest {
@@ -83,9 +101,9 @@ impl TrafficClassGetRequest {
             NetlinkMessage::from(RtnlMessage::GetTrafficClass(message));
         req.header.flags = NLM_F_REQUEST | NLM_F_DUMP;
 
-        match handle.request(req) {
+        match handle.try_request(req) {
             Ok(response) => Either::Left(response.map(move |msg| {
-                Ok(try_rtnl!(msg, RtnlMessage::NewTrafficClass))
+                Ok(try_rtnl!(msg?, RtnlMessage::NewTrafficClass))
             })),
             Err(e) => {
                 Either::Right(future::err::<TcMessage, Error>(e).into_stream())
@@ -106,6 +124,15 @@ impl TrafficFilterGetRequest {
         TrafficFilterGetRequest { handle, message }
     }
 
+    /// Execute the request, gathering the results into a `Vec` according
+    /// to `options`, for dumps small enough not to need a stream.
+    pub async fn execute_collect(
+        self,
+        options: CollectOptions,
+    ) -> Result<Vec<TcMessage>, Error> {
+        collect(self.execute(), options).await
+    }
+
     /// Execute the request
     pub fn execute(self) -> impl TryStream<Ok = TcMessage, Error = Error> {
         let TrafficFilterGetRequest {
@@ -117,9 +144,9 @@ impl TrafficFilterGetRequest {
             NetlinkMessage::from(RtnlMessage::GetTrafficFilter(message));
         req.header.flags = NLM_F_REQUEST | NLM_F_DUMP;
 
-        match handle.request(req) {
+        match handle.try_request(req) {
             Ok(response) => Either::Left(response.map(move |msg| {
-                Ok(try_rtnl!(msg, RtnlMessage::NewTrafficFilter))
+                Ok(try_rtnl!(msg?, RtnlMessage::NewTrafficFilter))
             })),
             Err(e) => {
                 Either::Right(future::err::<TcMessage, Error>(e).into_stream())
@@ -147,6 +174,15 @@ impl TrafficChainGetRequest {
         TrafficChainGetRequest { handle, message }
     }
 
+    /// Execute the request, gathering the results into a `Vec` according
+    /// to `options`, for dumps small enough not to need a stream.
+    pub async fn execute_collect(
+        self,
+        options: CollectOptions,
+    ) -> Result<Vec<TcMessage>, Error> {
+        collect(self.execute(), options).await
+    }
+
     /// Execute the request
     pub fn execute(self) -> impl TryStream<Ok = TcMessage, Error = Error> {
         let TrafficChainGetRequest {
@@ -158,9 +194,9 @@ impl TrafficChainGetRequest {
             NetlinkMessage::from(RtnlMessage::GetTrafficChain(message));
         req.header.flags = NLM_F_REQUEST | NLM_F_DUMP;
 
-        match handle.request(req) {
+        match handle.try_request(req) {
             Ok(response) => Either::Left(response.map(move |msg| {
-                Ok(try_rtnl!(msg, RtnlMessage::NewTrafficChain))
+                Ok(try_rtnl!(msg?, RtnlMessage::NewTrafficChain))
             })),
             Err(e) => {
                 Either::Right(future::err::<TcMessage, Error>(e).into_stream())
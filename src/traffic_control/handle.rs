@@ -17,13 +17,13 @@ impl QDiscHandle {
     }
 
     /// Retrieve the list of qdisc (equivalent to `tc qdisc show`)
-    pub fn get(&mut self) -> QDiscGetRequest {
+    pub fn get(&self) -> QDiscGetRequest {
         QDiscGetRequest::new(self.0.clone())
     }
 
     /// Create a new qdisc, don't replace if the object already exists.
     /// ( equivalent to `tc qdisc add dev STRING`)
-    pub fn add(&mut self, index: i32) -> QDiscNewRequest {
+    pub fn add(&self, index: i32) -> QDiscNewRequest {
         let msg = TcMessage::with_index(index);
         QDiscNewRequest::new(self.0.clone(), msg, NLM_F_EXCL | NLM_F_CREATE)
     }
@@ -31,27 +31,27 @@ impl QDiscHandle {
     /// Change the qdisc, the handle cannot be changed and neither can the
     /// parent. In other words, change cannot move a node.
     /// ( equivalent to `tc qdisc change dev STRING`)
-    pub fn change(&mut self, index: i32) -> QDiscNewRequest {
+    pub fn change(&self, index: i32) -> QDiscNewRequest {
         let msg = TcMessage::with_index(index);
         QDiscNewRequest::new(self.0.clone(), msg, 0)
     }
 
     /// Replace existing matching qdisc, create qdisc if it doesn't already
     /// exist. ( equivalent to `tc qdisc replace dev STRING`)
-    pub fn replace(&mut self, index: i32) -> QDiscNewRequest {
+    pub fn replace(&self, index: i32) -> QDiscNewRequest {
         let msg = TcMessage::with_index(index);
         QDiscNewRequest::new(self.0.clone(), msg, NLM_F_CREATE | NLM_F_REPLACE)
     }
 
     /// Performs a replace where the node must exist already.
     /// ( equivalent to `tc qdisc link dev STRING`)
-    pub fn link(&mut self, index: i32) -> QDiscNewRequest {
+    pub fn link(&self, index: i32) -> QDiscNewRequest {
         let msg = TcMessage::with_index(index);
         QDiscNewRequest::new(self.0.clone(), msg, NLM_F_REPLACE)
     }
 
     /// Delete the qdisc ( equivalent to `tc qdisc del dev STRING`)
-    pub fn del(&mut self, index: i32) -> QDiscDelRequest {
+    pub fn del(&self, index: i32) -> QDiscDelRequest {
         let msg = TcMessage::with_index(index);
         QDiscDelRequest::new(self.0.clone(), msg)
     }
@@ -69,7 +69,7 @@ impl TrafficClassHandle {
 
     /// Retrieve the list of traffic class (equivalent to
     /// `tc class show dev <interface_name>`)
-    pub fn get(&mut self) -> TrafficClassGetRequest {
+    pub fn get(&self) -> TrafficClassGetRequest {
         TrafficClassGetRequest::new(self.handle.clone(), self.ifindex)
     }
 }
@@ -86,13 +86,13 @@ impl TrafficFilterHandle {
 
     /// Retrieve the list of filter (equivalent to
     /// `tc filter show dev <iface_name>`)
-    pub fn get(&mut self) -> TrafficFilterGetRequest {
+    pub fn get(&self) -> TrafficFilterGetRequest {
         TrafficFilterGetRequest::new(self.handle.clone(), self.ifindex)
     }
 
     /// Add a filter to a node, don't replace if the object already exists.
     /// ( equivalent to `tc filter add dev STRING`)
-    pub fn add(&mut self) -> TrafficFilterNewRequest {
+    pub fn add(&self) -> TrafficFilterNewRequest {
         TrafficFilterNewRequest::new(
             self.handle.clone(),
             self.ifindex,
@@ -103,13 +103,13 @@ impl TrafficFilterHandle {
     /// Change the filter, the handle cannot be changed and neither can the
     /// parent. In other words, change cannot move a node.
     /// ( equivalent to `tc filter change dev STRING`)
-    pub fn change(&mut self) -> TrafficFilterNewRequest {
+    pub fn change(&self) -> TrafficFilterNewRequest {
         TrafficFilterNewRequest::new(self.handle.clone(), self.ifindex, 0)
     }
 
     /// Replace existing matching filter, create filter if it doesn't already
     /// exist. ( equivalent to `tc filter replace dev STRING`)
-    pub fn replace(&mut self) -> TrafficFilterNewRequest {
+    pub fn replace(&self) -> TrafficFilterNewRequest {
         TrafficFilterNewRequest::new(
             self.handle.clone(),
             self.ifindex,
@@ -130,7 +130,7 @@ impl TrafficChainHandle {
 
     /// Retrieve the list of chain (equivalent to
     /// `tc chain show dev <iface_name>`)
-    pub fn get(&mut self) -> TrafficChainGetRequest {
+    pub fn get(&self) -> TrafficChainGetRequest {
         TrafficChainGetRequest::new(self.handle.clone(), self.ifindex)
     }
 }
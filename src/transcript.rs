@@ -0,0 +1,374 @@
+// SPDX-License-Identifier: MIT
+
+use std::{
+    fs, io,
+    path::Path,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+};
+
+use bytes::BufMut;
+use futures::channel::mpsc::UnboundedReceiver;
+use netlink_packet_core::{NetlinkMessage, NLM_F_REQUEST};
+use netlink_packet_route::RtnlMessage;
+use netlink_packet_utils::DecodeError;
+use netlink_proto::Connection;
+#[cfg(feature = "tokio_socket")]
+use netlink_sys::TokioSocket;
+use netlink_sys::{AsyncSocket, Socket, SocketAddr};
+
+use crate::{new_connection_with_socket, raw::split_messages, Error, Handle};
+
+/// A request and the responses it received, as sent to and received from
+/// the netlink socket
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Exchange {
+    pub request: Vec<u8>,
+    pub responses: Vec<Vec<u8>>,
+}
+
+/// The requests sent on a netlink socket and the responses of the kernel,
+/// see [`new_recorded_connection`] and [`crate::Handle::replay`].
+///
+/// The messages are recorded as the raw bytes exchanged with the kernel, so
+/// that they are replayed exactly as the kernel sent them, even when
+/// netlink-packet-route can't serialize them back. A transcript is saved as
+/// the sequence of these messages, each request (with `NLM_F_REQUEST` set)
+/// being followed by its responses.
+#[derive(Clone, Debug, Default)]
+pub struct Transcript(Arc<Mutex<TranscriptState>>);
+
+#[derive(Debug, Default)]
+struct TranscriptState {
+    exchanges: Vec<Exchange>,
+    // Index of the next exchange to replay
+    next: usize,
+}
+
+impl Transcript {
+    /// The exchanges recorded so far
+    pub fn exchanges(&self) -> Vec<Exchange> {
+        self.0.lock().unwrap().exchanges.clone()
+    }
+
+    /// Serialize the transcript
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        for exchange in self.0.lock().unwrap().exchanges.iter() {
+            let messages =
+                std::iter::once(&exchange.request).chain(&exchange.responses);
+            for message in messages {
+                buf.extend_from_slice(message);
+                buf.resize((buf.len() + 3) & !3, 0);
+            }
+        }
+        buf
+    }
+
+    /// Parse a transcript serialized by [`Transcript::to_bytes`]
+    pub fn from_bytes(buf: &[u8]) -> Result<Self, DecodeError> {
+        let mut exchanges: Vec<Exchange> = Vec::new();
+        for message in split_messages(buf)? {
+            let is_request = message.flags() & NLM_F_REQUEST != 0;
+            let bytes = message.into_inner().to_vec();
+            if is_request {
+                exchanges.push(Exchange {
+                    request: bytes,
+                    responses: vec![],
+                });
+            } else {
+                exchanges
+                    .last_mut()
+                    .ok_or_else(|| {
+                        DecodeError::from(format!(
+                            "response without a request: {:?}",
+                            bytes
+                        ))
+                    })?
+                    .responses
+                    .push(bytes);
+            }
+        }
+        Ok(Transcript(Arc::new(Mutex::new(TranscriptState {
+            exchanges,
+            next: 0,
+        }))))
+    }
+
+    /// Write the transcript to the file at `path`
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::write(path, self.to_bytes())
+    }
+
+    /// Read a transcript saved with [`Transcript::save`]
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let buf = fs::read(path)?;
+        Self::from_bytes(&buf)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    fn record_sent(&self, buf: &[u8]) {
+        let messages = match split_messages(buf) {
+            Ok(messages) => messages,
+            Err(_) => return,
+        };
+        let mut state = self.0.lock().unwrap();
+        for message in messages {
+            state.exchanges.push(Exchange {
+                request: message.into_inner().to_vec(),
+                responses: vec![],
+            });
+        }
+    }
+
+    fn record_received(&self, buf: &[u8]) {
+        let messages = match split_messages(buf) {
+            Ok(messages) => messages,
+            Err(_) => return,
+        };
+        let mut state = self.0.lock().unwrap();
+        for message in messages {
+            // Multicast messages are not responses to a request
+            let sequence_number = message.sequence_number();
+            if sequence_number == 0 {
+                continue;
+            }
+            let exchange = state.exchanges.iter_mut().rev().find(|exchange| {
+                sequence(&exchange.request) == sequence_number
+            });
+            if let Some(exchange) = exchange {
+                exchange.responses.push(message.into_inner().to_vec());
+            }
+        }
+    }
+
+    /// The responses to `request`, which must be the next recorded request
+    pub(crate) fn replay(
+        &self,
+        mut request: NetlinkMessage<RtnlMessage>,
+    ) -> Result<Vec<NetlinkMessage<RtnlMessage>>, Error> {
+        request.finalize();
+        let mut state = self.0.lock().unwrap();
+        let exchange = match state.exchanges.get(state.next) {
+            Some(exchange) if same_request(&exchange.request, &request) => {
+                exchange
+            }
+            _ => return Err(Error::TranscriptMismatch(request)),
+        };
+        let responses = exchange
+            .responses
+            .iter()
+            .map(|bytes| NetlinkMessage::deserialize(bytes))
+            .collect::<Result<Vec<_>, _>>()?;
+        state.next += 1;
+        Ok(responses)
+    }
+}
+
+fn sequence(message: &[u8]) -> u32 {
+    split_messages(message)
+        .ok()
+        .and_then(|messages| messages.first().map(|m| m.sequence_number()))
+        .unwrap_or(0)
+}
+
+// The recorded request is compared byte for byte, except for its sequence
+// number and port number, which are only set by the connection when the
+// request is sent.
+fn same_request(
+    recorded: &[u8],
+    request: &NetlinkMessage<RtnlMessage>,
+) -> bool {
+    let mut buf = vec![0; request.buffer_len()];
+    request.serialize(&mut buf);
+    buf.len() == recorded.len()
+        && buf[..8] == recorded[..8]
+        && buf[16..] == recorded[16..]
+}
+
+/// A netlink socket recording the messages it sends and receives in a
+/// [`Transcript`], see [`new_recorded_connection`]
+pub struct RecordingSocket<S> {
+    socket: S,
+    transcript: Transcript,
+}
+
+impl<S> RecordingSocket<S> {
+    /// The messages exchanged on the socket so far
+    pub fn transcript(&self) -> &Transcript {
+        &self.transcript
+    }
+}
+
+impl<S> AsyncSocket for RecordingSocket<S>
+where
+    S: AsyncSocket,
+{
+    fn socket_ref(&self) -> &Socket {
+        self.socket.socket_ref()
+    }
+
+    fn socket_mut(&mut self) -> &mut Socket {
+        self.socket.socket_mut()
+    }
+
+    fn new(protocol: isize) -> io::Result<Self> {
+        Ok(RecordingSocket {
+            socket: S::new(protocol)?,
+            transcript: Transcript::default(),
+        })
+    }
+
+    fn poll_send(
+        &self,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let poll = self.socket.poll_send(cx, buf);
+        if let Poll::Ready(Ok(len)) = poll {
+            self.transcript.record_sent(&buf[..len]);
+        }
+        poll
+    }
+
+    fn poll_send_to(
+        &self,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+        addr: &SocketAddr,
+    ) -> Poll<io::Result<usize>> {
+        let poll = self.socket.poll_send_to(cx, buf, addr);
+        if let Poll::Ready(Ok(len)) = poll {
+            self.transcript.record_sent(&buf[..len]);
+        }
+        poll
+    }
+
+    fn poll_recv<B>(
+        &self,
+        cx: &mut Context<'_>,
+        buf: &mut B,
+    ) -> Poll<io::Result<()>>
+    where
+        B: BufMut,
+    {
+        self.poll_recv_from(cx, buf).map_ok(|_| ())
+    }
+
+    fn poll_recv_from<B>(
+        &self,
+        cx: &mut Context<'_>,
+        buf: &mut B,
+    ) -> Poll<io::Result<SocketAddr>>
+    where
+        B: BufMut,
+    {
+        self.poll_recv_from_full(cx).map_ok(|(data, addr)| {
+            let len = data.len().min(buf.remaining_mut());
+            buf.put_slice(&data[..len]);
+            addr
+        })
+    }
+
+    fn poll_recv_from_full(
+        &self,
+        cx: &mut Context<'_>,
+    ) -> Poll<io::Result<(Vec<u8>, SocketAddr)>> {
+        let poll = self.socket.poll_recv_from_full(cx);
+        if let Poll::Ready(Ok((data, _))) = &poll {
+            self.transcript.record_received(data);
+        }
+        poll
+    }
+}
+
+/// Same as [`crate::new_connection`], with a socket recording the requests
+/// sent by the connection and the responses of the kernel in the returned
+/// transcript. The transcript can be saved to a file and replayed later
+/// with [`Handle::replay`], to reproduce a bug or to write tests that do
+/// not depend on the state of the host.
+#[cfg(feature = "tokio_socket")]
+#[allow(clippy::type_complexity)]
+pub fn new_recorded_connection() -> io::Result<(
+    Connection<RtnlMessage, RecordingSocket<TokioSocket>>,
+    Handle,
+    UnboundedReceiver<(NetlinkMessage<RtnlMessage>, SocketAddr)>,
+    Transcript,
+)> {
+    new_recorded_connection_with_socket()
+}
+
+/// Same as [`new_recorded_connection`], using the socket type `S`.
+#[allow(clippy::type_complexity)]
+pub fn new_recorded_connection_with_socket<S>() -> io::Result<(
+    Connection<RtnlMessage, RecordingSocket<S>>,
+    Handle,
+    UnboundedReceiver<(NetlinkMessage<RtnlMessage>, SocketAddr)>,
+    Transcript,
+)>
+where
+    S: AsyncSocket,
+{
+    let (mut conn, handle, messages) =
+        new_connection_with_socket::<RecordingSocket<S>>()?;
+    let transcript = conn.socket_mut().transcript().clone();
+    Ok((conn, handle, messages, transcript))
+}
+
+#[cfg(test)]
+mod test {
+    use futures::stream::TryStreamExt;
+    use tokio::runtime::Runtime;
+
+    use crate::MockNetwork;
+
+    use super::*;
+
+    #[test]
+    fn test_record_replay() {
+        let rt = Runtime::new().unwrap();
+        rt.block_on(async {
+            let (conn, handle, _, transcript) =
+                new_recorded_connection().unwrap();
+            tokio::spawn(conn);
+            let links: Vec<_> = handle
+                .link()
+                .get()
+                .match_index(1)
+                .execute()
+                .try_collect()
+                .await
+                .unwrap();
+            assert_eq!(links.len(), 1);
+            let err = handle.link().del(0x7fff_fff0).execute().await;
+            assert!(err.unwrap_err().is_not_found());
+            assert_eq!(transcript.exchanges().len(), 2);
+
+            let transcript =
+                Transcript::from_bytes(&transcript.to_bytes()).unwrap();
+            assert_eq!(transcript.exchanges().len(), 2);
+            let handle = MockNetwork::new().handle().replay(&transcript);
+            let replayed: Vec<_> = handle
+                .link()
+                .get()
+                .match_index(1)
+                .execute()
+                .try_collect()
+                .await
+                .unwrap();
+            assert_eq!(replayed, links);
+            // Requests must be replayed in order
+            let err = handle.link().del(1).execute().await.unwrap_err();
+            assert!(matches!(err, Error::TranscriptMismatch(_)));
+            let err = handle.link().del(0x7fff_fff0).execute().await;
+            assert!(err.unwrap_err().is_not_found());
+        });
+    }
+
+    #[test]
+    fn test_invalid_transcript() {
+        assert!(Transcript::from_bytes(&[4, 0, 0, 0]).is_err());
+        assert!(Transcript::from_bytes(&[32, 0, 0, 0, 2, 0]).is_err());
+        assert!(Transcript::from_bytes(&[]).unwrap().exchanges().is_empty());
+    }
+}